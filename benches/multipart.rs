@@ -0,0 +1,88 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const MESSAGE_LENGTH: usize = 1 << 20;
+
+fn message(length: usize) -> Vec<u8> {
+    (0..=u8::MAX).cycle().take(length).collect()
+}
+
+/// Generates enough parts to decode the message after dropping the given
+/// percentage of them, deterministically spread over the sequence.
+fn lossy_parts(message: &[u8], max_fragment_length: usize, loss: usize) -> Vec<String> {
+    let mut encoder = ur::Encoder::bytes(message, max_fragment_length).unwrap();
+    let mut decoder = ur::Decoder::default();
+    let mut parts = Vec::new();
+    for sequence in 0.. {
+        let part = encoder.next_part().unwrap();
+        if sequence * 37 % 100 < loss {
+            continue;
+        }
+        decoder.receive(&part).unwrap();
+        parts.push(part);
+        if decoder.complete() {
+            return parts;
+        }
+    }
+    unreachable!()
+}
+
+fn encode(c: &mut Criterion) {
+    let message = message(MESSAGE_LENGTH);
+    let mut group = c.benchmark_group("encode");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(MESSAGE_LENGTH as u64));
+    for max_fragment_length in [100, 500, 2000] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(max_fragment_length),
+            &max_fragment_length,
+            |b, &max_fragment_length| {
+                b.iter(|| {
+                    let mut encoder = ur::Encoder::bytes(&message, max_fragment_length).unwrap();
+                    for _ in 0..encoder.fragment_count() {
+                        encoder.next_part().unwrap();
+                    }
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let message = message(MESSAGE_LENGTH);
+    let mut group = c.benchmark_group("decode");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(MESSAGE_LENGTH as u64));
+    for loss in [0, 30, 60] {
+        let parts = lossy_parts(&message, 500, loss);
+        group.bench_with_input(BenchmarkId::new("loss", loss), &parts, |b, parts| {
+            b.iter(|| {
+                let mut decoder = ur::Decoder::default();
+                for part in parts {
+                    decoder.receive(part).unwrap();
+                }
+                decoder.message().unwrap().unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bytewords(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytewords");
+    for length in [1 << 10, 1 << 16] {
+        let encoded = ur::bytewords::encode(&message(length), ur::bytewords::Style::Minimal);
+        group.throughput(Throughput::Bytes(encoded.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("decode_minimal", length),
+            &encoded,
+            |b, encoded| {
+                b.iter(|| ur::bytewords::decode(encoded, ur::bytewords::Style::Minimal).unwrap());
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode, bytewords);
+criterion_main!(benches);
@@ -0,0 +1,127 @@
+use honggfuzz::fuzz;
+use ur::fountain::{Decoder, Encoder, Limits, Part};
+
+const LIMITS: Limits = Limits {
+    max_buffered_bytes: 1 << 16,
+    max_fragment_count: 1 << 8,
+};
+
+/// Only bounds the buffered bytes, which must also bound the number of
+/// segments the decoder allocates and schedules.
+const BYTE_LIMITS: Limits = Limits {
+    max_buffered_bytes: 1 << 16,
+    max_fragment_count: usize::MAX,
+};
+
+fn main() {
+    loop {
+        fuzz!(|data: &[u8]| {
+            let Some((&mode, data)) = data.split_first() else {
+                return;
+            };
+            let limits = if mode & 2 == 2 { BYTE_LIMITS } else { LIMITS };
+            if mode & 1 == 1 {
+                mutated_parts(limits, usize::from(mode >> 2), data);
+            } else {
+                arbitrary_parts(limits, data);
+            }
+        });
+    }
+}
+
+/// The fragment and message lengths announced by the first accepted part.
+#[derive(Clone, Copy)]
+struct Transfer {
+    fragment_length: usize,
+    message_length: usize,
+}
+
+/// Feeds length-prefixed chunks of the input to the decoder as CBOR parts.
+fn arbitrary_parts(limits: Limits, mut data: &[u8]) {
+    let mut decoder = Decoder::default().with_limits(limits);
+    let mut transfer = None;
+    while let Some((&len, rest)) = data.split_first() {
+        let (chunk, rest) = rest.split_at(usize::from(len).min(rest.len()));
+        data = rest;
+        if let Ok(part) = minicbor::decode::<Part>(chunk) {
+            receive(&mut decoder, part, &mut transfer);
+        }
+    }
+    check(&decoder, limits, transfer);
+}
+
+/// Encodes the first half of the input and tampers with the generated parts
+/// as instructed by the second half.
+fn mutated_parts(limits: Limits, max_fragment_length: usize, data: &[u8]) {
+    let (message, mutations) = data.split_at(data.len() / 2);
+    let Ok(mut encoder) = Encoder::new(message, max_fragment_length + 1) else {
+        return;
+    };
+    let mut decoder = Decoder::default().with_limits(limits);
+    let mut transfer = None;
+    let mut tampered = false;
+    for mutation in mutations.chunks_exact(3) {
+        let honest = encoder.next_part();
+        let (kind, position, value) = (mutation[0], usize::from(mutation[1]), mutation[2]);
+        let mut sequence = honest.sequence();
+        let mut sequence_count = honest.sequence_count();
+        let mut message_length = honest.message_length();
+        let mut checksum = honest.checksum();
+        let mut data = honest.data().to_vec();
+        match kind % 8 {
+            0 => {
+                let index = position % data.len();
+                data[index] ^= value;
+            }
+            1 => sequence = usize::from(value),
+            2 => sequence_count = usize::from(value) << (position % 32),
+            3 => message_length = usize::from(value) << (position % 16),
+            4 => checksum ^= u32::from(value) << (position % 25),
+            5 => data.truncate(position % data.len()),
+            6 => data.resize(data.len() + usize::from(value), 0),
+            _ => {}
+        }
+        let part = Part::new(sequence, sequence_count, message_length, checksum, data);
+        tampered |= part != honest;
+        receive(&mut decoder, part, &mut transfer);
+    }
+    check(&decoder, limits, transfer);
+    if !tampered && decoder.complete() {
+        assert_eq!(decoder.message().unwrap().as_deref(), Some(message));
+    }
+}
+
+/// Receives a part, recording the transfer announced by the first accepted one.
+fn receive(decoder: &mut Decoder, part: Part, transfer: &mut Option<Transfer>) {
+    let fragment_length = part.data().len();
+    let message_length = part.message_length();
+    if decoder.receive(part).is_ok() && transfer.is_none() && decoder.stats().parts_received > 0 {
+        *transfer = Some(Transfer {
+            fragment_length,
+            message_length,
+        });
+    }
+}
+
+/// Asserts that the decoder stayed within its limits and only claims completion
+/// if a message of the announced length can be extracted.
+fn check(decoder: &Decoder, limits: Limits, transfer: Option<Transfer>) {
+    let Some(transfer) = transfer else {
+        assert!(!decoder.complete());
+        assert_eq!(decoder.expected_fragment_count(), None);
+        return;
+    };
+    // the segments of the accepted message fit the buffer, so the storage
+    // slots and scheduled indexes are bounded as well
+    let count = decoder.expected_fragment_count().unwrap();
+    assert!(count <= limits.max_fragment_count);
+    assert!(count * transfer.fragment_length <= limits.max_buffered_bytes);
+    let held = decoder.decoded_count() + decoder.stats().buffered_parts;
+    assert!(held * transfer.fragment_length <= limits.max_buffered_bytes);
+    if decoder.complete() {
+        // forged segments may carry a non-zero padding, but never a short message
+        if let Ok(message) = decoder.message() {
+            assert_eq!(message.map(|m| m.len()), Some(transfer.message_length));
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use honggfuzz::fuzz;
+
+fn main() {
+    loop {
+        fuzz!(|data: &str| {
+            let mut decoder = ur::Decoder::default().with_limits(ur::fountain::Limits {
+                max_buffered_bytes: 1 << 20,
+                max_fragment_count: 1 << 10,
+            });
+            for line in data.lines() {
+                ur::decode(line).ok();
+                decoder.receive(line).ok();
+            }
+            decoder.message().ok();
+        });
+    }
+}
@@ -52,24 +52,36 @@ pub enum Style {
 
 /// The two different errors that can be returned when decoding.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
-    /// Usually indicates a wrong encoding [`Style`] was passed.
-    InvalidWord,
+    /// The word at the given position, starting at zero, isn't a `bytewords`
+    /// word. Usually indicates a wrong encoding [`Style`] was passed.
+    InvalidWord {
+        /// The index of the word within the encoded string.
+        position: usize,
+        /// The unrecognized word.
+        word: alloc::string::String,
+    },
     /// The CRC32 checksum doesn't validate.
     InvalidChecksum,
     /// Invalid bytewords string length.
     InvalidLength,
     /// The bytewords string contains non-ASCII characters.
     NonAscii,
+    /// The output buffer is too small for the encoded or decoded data.
+    BufferTooSmall,
 }
 
 impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            Self::InvalidWord => write!(f, "invalid word"),
+            Self::InvalidWord { position, word } => {
+                write!(f, "invalid word {word:?} at position {position}")
+            }
             Self::InvalidChecksum => write!(f, "invalid checksum"),
             Self::InvalidLength => write!(f, "invalid length"),
             Self::NonAscii => write!(f, "bytewords string contains non-ASCII characters"),
+            Self::BufferTooSmall => write!(f, "output buffer too small"),
         }
     }
 }
@@ -77,6 +89,48 @@ impl core::fmt::Display for Error {
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+impl Error {
+    /// Returns guidance on how to recover from the error, suitable to be shown
+    /// to users, or `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::bytewords::{decode, Style};
+    /// let error = decode("aetdaowslg", Style::Uri).unwrap_err();
+    /// assert_eq!(
+    ///     error.hint(),
+    ///     Some("the input looks like minimal bytewords, but words separated by spaces or dashes were expected")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn hint(&self) -> Option<&'static str> {
+        let hint = match self {
+            Self::InvalidWord { word, .. } if word.contains('-') => {
+                "the input looks like URI-style bytewords, but another style was expected"
+            }
+            Self::InvalidWord { word, .. } if word.contains(' ') => {
+                "the input looks like standard bytewords, but another style was expected"
+            }
+            Self::InvalidWord { word, .. } if word.bytes().any(|b| b.is_ascii_uppercase()) => {
+                "the input contains uppercase letters, convert it to lowercase before decoding"
+            }
+            Self::InvalidWord { word, .. } if word.len() > 4 => {
+                "the input looks like minimal bytewords, but words separated by spaces or \
+                 dashes were expected"
+            }
+            Self::InvalidWord { .. } => {
+                "the input may be misspelled, or of another bytewords style"
+            }
+            Self::InvalidChecksum => "the input may be truncated or misread, scan it again",
+            Self::InvalidLength => "the input may be truncated, scan it again",
+            Self::NonAscii => "bytewords consist of ASCII letters only, check the input encoding",
+            Self::BufferTooSmall => return None,
+        };
+        Some(hint)
+    }
+}
+
 /// Decodes a `bytewords`-encoded String back into a byte payload. The encoding
 /// must contain a four-byte checksum.
 ///
@@ -102,6 +156,53 @@ impl std::error::Error for Error {}
 /// the provided `style`, or contains an invalid checksum, an error will be
 /// returned.
 pub fn decode(encoded: &str, style: Style) -> Result<Vec<u8>, Error> {
+    decode_with_checksum(encoded, style).map(|(payload, _)| payload)
+}
+
+/// Decodes a `bytewords`-encoded ASCII byte slice like [`decode`], e.g. the
+/// raw output of a QR code scanner or serial port.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{decode_bytes, Style};
+/// assert_eq!(
+///     decode_bytes(b"able tied also webs lung", Style::Standard).unwrap(),
+///     vec![0]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If the encoded bytes aren't ASCII, [`Error::NonAscii`] will be returned.
+/// Otherwise, the same errors as for [`decode`] apply.
+pub fn decode_bytes(encoded: &[u8], style: Style) -> Result<Vec<u8>, Error> {
+    if !encoded.is_ascii() {
+        return Err(Error::NonAscii);
+    }
+    // ASCII is valid UTF-8
+    decode(core::str::from_utf8(encoded).unwrap_or_default(), style)
+}
+
+/// Decodes a `bytewords`-encoded String like [`decode`], but also returns the
+/// CRC32 checksum of the payload.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{decode_with_checksum, Style};
+/// assert_eq!(
+///     decode_with_checksum("able tied also webs lung", Style::Standard).unwrap(),
+///     (vec![0], 0xd202_ef8d)
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If the encoded string contains unrecognized words, is inconsistent with
+/// the provided `style`, or contains an invalid checksum, an error will be
+/// returned.
+pub fn decode_with_checksum(encoded: &str, style: Style) -> Result<(Vec<u8>, u32), Error> {
     if !encoded.is_ascii() {
         return Err(Error::NonAscii);
     }
@@ -111,46 +212,499 @@ pub fn decode(encoded: &str, style: Style) -> Result<Vec<u8>, Error> {
         Style::Uri => '-',
         Style::Minimal => return decode_minimal(encoded),
     };
-    decode_from_index(&mut encoded.split(separator), &crate::constants::WORD_IDXS)
+    decode_from_index(&mut encoded.split(separator), word_byte)
+}
+
+/// Infers the encoding [`Style`] of a `bytewords`-encoded String from its
+/// separators and length.
+///
+/// Returns `None` if the string contains characters other than lowercase
+/// letters and a single kind of separator, or if it has no separators and an
+/// odd length.
+///
+/// The detection doesn't validate the words or the checksum, see
+/// [`decode_any`] for that.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{detect_style, Style};
+/// assert_eq!(detect_style("able tied also webs lung"), Some(Style::Standard));
+/// assert_eq!(detect_style("able-tied-also-webs-lung"), Some(Style::Uri));
+/// assert_eq!(detect_style("aetdaowslg"), Some(Style::Minimal));
+/// assert_eq!(detect_style("able-tied also-webs lung"), None);
+/// ```
+#[must_use]
+pub fn detect_style(encoded: &str) -> Option<Style> {
+    let (style, separator) = if encoded.contains(' ') {
+        (Style::Standard, Some(b' '))
+    } else if encoded.contains('-') {
+        (Style::Uri, Some(b'-'))
+    } else if encoded.is_empty() || encoded.len() % 2 == 1 {
+        return None;
+    } else {
+        (Style::Minimal, None)
+    };
+    encoded
+        .bytes()
+        .all(|b| b.is_ascii_lowercase() || Some(b) == separator)
+        .then_some(style)
 }
 
-fn decode_minimal(encoded: &str) -> Result<Vec<u8>, Error> {
+/// Decodes a `bytewords`-encoded String like [`decode`], but infers the
+/// encoding [`Style`] with [`detect_style`].
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::decode_any;
+/// assert_eq!(decode_any("able tied also webs lung").unwrap(), vec![0]);
+/// assert_eq!(decode_any("able-tied-also-webs-lung").unwrap(), vec![0]);
+/// assert_eq!(decode_any("aetdaowslg").unwrap(), vec![0]);
+/// ```
+///
+/// # Errors
+///
+/// If the encoded string contains unrecognized words or mixed separators, or
+/// contains an invalid checksum, an error will be returned.
+pub fn decode_any(encoded: &str) -> Result<Vec<u8>, Error> {
+    let style = detect_style(encoded).unwrap_or_else(|| {
+        if encoded.contains(' ') {
+            Style::Standard
+        } else {
+            // reports non-ASCII characters, odd lengths and unrecognized words
+            Style::Minimal
+        }
+    });
+    decode(encoded, style)
+}
+
+/// A word which [`decode_fuzzy`] corrected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correction {
+    /// The index of the word within the encoded string.
+    pub position: usize,
+    /// The word as it appeared in the encoded string.
+    pub word: alloc::string::String,
+    /// The `bytewords` word it was corrected to.
+    pub corrected: &'static str,
+}
+
+/// Decodes a `bytewords`-encoded String like [`decode`], but corrects words
+/// which are a single edit away from exactly one `bytewords` word. This is
+/// useful for hand-transcribed backups.
+///
+/// An edit is the substitution, insertion or deletion of a letter, and the
+/// checksum is verified after the correction. Since almost every pair of
+/// letters is a single edit away from several two-letter words, words of
+/// [`Style::Minimal`] encodings aren't corrected.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{decode_fuzzy, Correction, Style};
+/// let (payload, corrections) = decode_fuzzy("able tied alxo webs lung", Style::Standard).unwrap();
+/// assert_eq!(payload, vec![0]);
+/// assert_eq!(
+///     corrections,
+///     vec![Correction {
+///         position: 2,
+///         word: "alxo".into(),
+///         corrected: "also",
+///     }]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If a word can't be corrected unambiguously, [`Error::InvalidWord`] will be
+/// returned. Otherwise, the same errors as for [`decode`] apply.
+pub fn decode_fuzzy(encoded: &str, style: Style) -> Result<(Vec<u8>, Vec<Correction>), Error> {
+    if !encoded.is_ascii() {
+        return Err(Error::NonAscii);
+    }
+    let separator = match style {
+        Style::Standard => ' ',
+        Style::Uri => '-',
+        Style::Minimal => return decode_minimal(encoded).map(|(payload, _)| (payload, Vec::new())),
+    };
+    let mut corrections = Vec::new();
+    let data = encoded
+        .split(separator)
+        .enumerate()
+        .map(|(position, word)| {
+            if let Some(byte) = word_byte(word) {
+                return Ok(byte);
+            }
+            let mut candidates = (0..=u8::MAX).filter(|&b| is_single_edit(word, word_for(b)));
+            match (candidates.next(), candidates.next()) {
+                (Some(byte), None) => {
+                    corrections.push(Correction {
+                        position,
+                        word: word.into(),
+                        corrected: word_for(byte),
+                    });
+                    Ok(byte)
+                }
+                _ => Err(invalid_word(position, word)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    strip_checksum(data).map(|(payload, _)| (payload, corrections))
+}
+
+/// Returns whether the two words differ by at most one substitution,
+/// insertion or deletion of a letter.
+fn is_single_edit(a: &str, b: &str) -> bool {
+    let (short, long) = if a.len() <= b.len() {
+        (a.as_bytes(), b.as_bytes())
+    } else {
+        (b.as_bytes(), a.as_bytes())
+    };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    let prefix = short.iter().zip(long).take_while(|(x, y)| x == y).count();
+    if prefix == short.len() {
+        return true;
+    }
+    let skip = usize::from(short.len() == long.len());
+    short[prefix + skip..] == long[prefix + 1..]
+}
+
+fn decode_minimal(encoded: &str) -> Result<(Vec<u8>, u32), Error> {
     if encoded.len() % 2 != 0 {
         return Err(Error::InvalidLength);
     }
 
-    decode_from_index(
-        &mut (0..encoded.len())
-            .step_by(2)
-            .map(|idx| encoded.get(idx..idx + 2).unwrap()),
-        &crate::constants::MINIMAL_IDXS,
-    )
+    decode_from_index(&mut minimal_words(encoded), minimal_byte)
+}
+
+fn minimal_words(encoded: &str) -> impl Iterator<Item = &str> {
+    (0..encoded.len())
+        .step_by(2)
+        // slices are in bounds and on character boundaries for ASCII input
+        .map(|idx| encoded.get(idx..idx + 2).unwrap_or_default())
 }
 
 fn decode_from_index(
     keys: &mut dyn Iterator<Item = &str>,
-    indexes: &phf::Map<&'static str, u8>,
-) -> Result<Vec<u8>, Error> {
+    lookup: fn(&str) -> Option<u8>,
+) -> Result<(Vec<u8>, u32), Error> {
     strip_checksum(
-        keys.map(|k| indexes.get(k).copied())
-            .collect::<Option<Vec<_>>>()
-            .ok_or(Error::InvalidWord)?,
+        keys.enumerate()
+            .map(|(position, word)| lookup(word).ok_or_else(|| invalid_word(position, word)))
+            .collect::<Result<Vec<_>, _>>()?,
     )
 }
 
-fn strip_checksum(mut data: Vec<u8>) -> Result<Vec<u8>, Error> {
+fn word_byte(word: &str) -> Option<u8> {
+    match *word.as_bytes() {
+        [first, _, _, last] => letter_pair_byte(first, last).filter(|&b| word_for(b) == word),
+        _ => None,
+    }
+}
+
+pub(crate) fn minimal_byte(word: &str) -> Option<u8> {
+    match *word.as_bytes() {
+        [first, last] => letter_pair_byte(first, last),
+        _ => None,
+    }
+}
+
+/// Looks up the first and last letter of a word in a table instead of hashing
+/// the word, which speeds up decoding large encodings.
+fn letter_pair_byte(first: u8, last: u8) -> Option<u8> {
+    if !first.is_ascii_lowercase() || !last.is_ascii_lowercase() {
+        return None;
+    }
+    crate::constants::LETTER_PAIRS[usize::from(first - b'a')][usize::from(last - b'a')]
+}
+
+fn invalid_word(position: usize, word: &str) -> Error {
+    Error::InvalidWord {
+        position,
+        word: word.into(),
+    }
+}
+
+fn strip_checksum(mut data: Vec<u8>) -> Result<(Vec<u8>, u32), Error> {
     if data.len() < 4 {
         return Err(Error::InvalidChecksum);
     }
     let (payload, checksum) = data.split_at(data.len() - 4);
-    if crate::crc32().checksum(payload).to_be_bytes() == checksum {
+    let crc = crate::CRC32.checksum(payload);
+    if crc.to_be_bytes() == checksum {
         data.truncate(data.len() - 4);
-        Ok(data)
+        Ok((data, crc))
     } else {
         Err(Error::InvalidChecksum)
     }
 }
 
+/// Verifies a `bytewords`-encoded String without allocating the decoded
+/// payload, which is useful to validate large inputs.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{verify, Error, Style};
+/// assert!(verify("able tied also webs lung", Style::Standard).is_ok());
+/// assert_eq!(
+///     verify("able tied also webs limp", Style::Standard),
+///     Err(Error::InvalidChecksum)
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If [`decode`] would fail for the encoded string, the same error will be
+/// returned. Since the input is checked word by word, an overlong word is
+/// reported as soon as its fifth letter is read.
+pub fn verify(encoded: &str, style: Style) -> Result<(), Error> {
+    decode_with(encoded, style, &mut |_| {})
+}
+
+/// Decodes a `bytewords`-encoded String like [`decode`], but writes the
+/// payload into the given buffer instead of allocating it. Returns the
+/// written part of the buffer.
+///
+/// A buffer of [`decoded_length`] bytes is large enough for any valid
+/// encoding.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{decode_into, decoded_length, Style};
+/// let encoded = "able tied also webs lung";
+/// let mut buffer = [0; 1];
+/// assert_eq!(decoded_length(encoded.len(), Style::Standard), Some(1));
+/// assert_eq!(
+///     decode_into(encoded, Style::Standard, &mut buffer).unwrap(),
+///     &[0]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If [`decode`] would fail for the encoded string, the same error will be
+/// returned, see also [`verify`]. If the payload doesn't fit into the buffer,
+/// [`Error::BufferTooSmall`] will be returned.
+pub fn decode_into<'a>(
+    encoded: &str,
+    style: Style,
+    buffer: &'a mut [u8],
+) -> Result<&'a [u8], Error> {
+    let mut length = 0;
+    let mut overflow = false;
+    decode_with(encoded, style, &mut |byte| {
+        if let Some(slot) = buffer.get_mut(length) {
+            *slot = byte;
+            length += 1;
+        } else {
+            overflow = true;
+        }
+    })?;
+    if overflow {
+        return Err(Error::BufferTooSmall);
+    }
+    Ok(&buffer[..length])
+}
+
+/// Returns the payload length of a `bytewords` encoding with the given number
+/// of characters, or `None` if no valid encoding has that length.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{decoded_length, Style};
+/// assert_eq!(decoded_length(24, Style::Standard), Some(1));
+/// assert_eq!(decoded_length(10, Style::Minimal), Some(1));
+/// assert_eq!(decoded_length(6, Style::Minimal), None);
+/// ```
+#[must_use]
+pub const fn decoded_length(encoded_length: usize, style: Style) -> Option<usize> {
+    match style {
+        Style::Standard | Style::Uri if encoded_length % 5 != 4 => None,
+        Style::Standard | Style::Uri => ((encoded_length + 1) / 5).checked_sub(4),
+        Style::Minimal if encoded_length % 2 == 1 => None,
+        Style::Minimal => (encoded_length / 2).checked_sub(4),
+    }
+}
+
+/// Decodes a `bytewords`-encoded String without allocating, passing the
+/// payload bytes to a closure.
+fn decode_with(encoded: &str, style: Style, payload: &mut dyn FnMut(u8)) -> Result<(), Error> {
+    if !encoded.is_ascii() {
+        return Err(Error::NonAscii);
+    }
+    if style == Style::Minimal && encoded.len() % 2 == 1 {
+        return Err(Error::InvalidLength);
+    }
+    let mut decoder = StreamDecoder::new(style);
+    decoder.push_with(encoded, payload)?;
+    decoder.finish_with(payload)
+}
+
+/// A decoder for `bytewords`-encoded strings arriving in arbitrary chunks, e.g.
+/// from a serial line or network connection.
+///
+/// The decoded payload is returned as the chunks are pushed, and the checksum is
+/// verified once the input is complete. Since the checksum can only be verified
+/// then, callers have to discard the payload if [`finish`] fails.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{Style, StreamDecoder};
+/// let mut decoder = StreamDecoder::new(Style::Standard);
+/// let mut payload = decoder.push("able ac").unwrap();
+/// payload.extend(decoder.push("id also lava zoom jade ne").unwrap());
+/// payload.extend(decoder.push("ed echo taxi").unwrap());
+/// payload.extend(decoder.finish().unwrap());
+/// assert_eq!(payload, vec![0, 1, 2, 128, 255]);
+/// ```
+///
+/// [`finish`]: StreamDecoder::finish
+#[derive(Clone)]
+pub struct StreamDecoder {
+    style: Style,
+    word: [u8; 4],
+    word_length: usize,
+    position: usize,
+    checksum: [u8; 4],
+    checksum_length: usize,
+    digest: crc::Digest<'static, u32>,
+}
+
+impl StreamDecoder {
+    /// Creates a new [`StreamDecoder`] for the given encoding style.
+    ///
+    /// # Examples
+    ///
+    /// See the [`StreamDecoder`] documentation for an example.
+    #[must_use]
+    pub fn new(style: Style) -> Self {
+        Self {
+            style,
+            word: [0; 4],
+            word_length: 0,
+            position: 0,
+            checksum: [0; 4],
+            checksum_length: 0,
+            digest: crate::CRC32.digest(),
+        }
+    }
+
+    /// Decodes the next chunk of the encoded string, returning the payload bytes
+    /// which have been completed by the chunk. The last four decoded bytes are
+    /// held back, since they could be the checksum.
+    ///
+    /// # Examples
+    ///
+    /// See the [`StreamDecoder`] documentation for an example.
+    ///
+    /// # Errors
+    ///
+    /// If the chunk contains unrecognized words or non-ASCII characters, an
+    /// error will be returned and the decoder shouldn't be used any further.
+    pub fn push(&mut self, chunk: &str) -> Result<Vec<u8>, Error> {
+        let mut payload = Vec::new();
+        self.push_with(chunk, &mut |byte| payload.push(byte))?;
+        Ok(payload)
+    }
+
+    /// Completes decoding and verifies the checksum, returning the remaining
+    /// payload bytes of a final word without trailing separator.
+    ///
+    /// # Examples
+    ///
+    /// See the [`StreamDecoder`] documentation for an example.
+    ///
+    /// # Errors
+    ///
+    /// If the input ended within a word or the checksum doesn't validate, an
+    /// error will be returned.
+    pub fn finish(self) -> Result<Vec<u8>, Error> {
+        let mut payload = Vec::new();
+        self.finish_with(&mut |byte| payload.push(byte))?;
+        Ok(payload)
+    }
+
+    /// Like [`StreamDecoder::push`], but passes the payload bytes to a closure.
+    fn push_with(&mut self, chunk: &str, payload: &mut dyn FnMut(u8)) -> Result<(), Error> {
+        if !chunk.is_ascii() {
+            return Err(Error::NonAscii);
+        }
+        for c in chunk.bytes() {
+            match self.style {
+                Style::Standard if c == b' ' => self.decode_word(payload)?,
+                Style::Uri if c == b'-' => self.decode_word(payload)?,
+                Style::Standard | Style::Uri => {
+                    // words are four letters long
+                    let Some(letter) = self.word.get_mut(self.word_length) else {
+                        let mut word = alloc::string::String::from(self.word());
+                        word.push(char::from(c));
+                        return Err(invalid_word(self.position, &word));
+                    };
+                    *letter = c;
+                    self.word_length += 1;
+                }
+                Style::Minimal => {
+                    self.word[self.word_length] = c;
+                    self.word_length += 1;
+                    if self.word_length == 2 {
+                        self.decode_word(payload)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [`StreamDecoder::finish`], but passes the payload bytes to a closure.
+    fn finish_with(mut self, payload: &mut dyn FnMut(u8)) -> Result<(), Error> {
+        match self.style {
+            Style::Standard | Style::Uri => self.decode_word(payload)?,
+            Style::Minimal if self.word_length > 0 => return Err(Error::InvalidLength),
+            Style::Minimal => {}
+        }
+        if self.checksum_length < 4 || self.digest.finalize().to_be_bytes() != self.checksum {
+            return Err(Error::InvalidChecksum);
+        }
+        Ok(())
+    }
+
+    /// Returns the buffered word.
+    fn word(&self) -> &str {
+        // the buffered letters are ASCII
+        core::str::from_utf8(&self.word[..self.word_length]).unwrap_or_default()
+    }
+
+    /// Decodes the buffered word, passing bytes which can't be part of the
+    /// checksum on to the payload.
+    fn decode_word(&mut self, payload: &mut dyn FnMut(u8)) -> Result<(), Error> {
+        let lookup = match self.style {
+            Style::Standard | Style::Uri => word_byte,
+            Style::Minimal => minimal_byte,
+        };
+        let byte = lookup(self.word()).ok_or_else(|| invalid_word(self.position, self.word()))?;
+        self.word_length = 0;
+        self.position += 1;
+        if self.checksum_length < 4 {
+            self.checksum[self.checksum_length] = byte;
+            self.checksum_length += 1;
+        } else {
+            let released = self.checksum[0];
+            self.checksum.rotate_left(1);
+            self.checksum[3] = byte;
+            self.digest.update(&[released]);
+            payload(released);
+        }
+        Ok(())
+    }
+}
+
 /// Encodes a byte payload into a `bytewords` encoded String.
 ///
 /// # Examples
@@ -164,15 +718,11 @@ fn strip_checksum(mut data: Vec<u8>) -> Result<Vec<u8>, Error> {
 /// ```
 #[must_use]
 pub fn encode(data: &[u8], style: Style) -> alloc::string::String {
-    let checksum = crate::crc32().checksum(data).to_be_bytes();
+    let checksum = crate::CRC32.checksum(data).to_be_bytes();
     let data = data.iter().chain(checksum.iter());
     let words: Vec<&str> = match style {
-        Style::Standard | Style::Uri => data
-            .map(|&b| crate::constants::WORDS.get(b as usize).copied().unwrap())
-            .collect(),
-        Style::Minimal => data
-            .map(|&b| crate::constants::MINIMALS.get(b as usize).copied().unwrap())
-            .collect(),
+        Style::Standard | Style::Uri => data.map(|&b| word_for(b)).collect(),
+        Style::Minimal => data.map(|&b| minimal_for(b)).collect(),
     };
     let separator = match style {
         Style::Standard => " ",
@@ -182,14 +732,410 @@ pub fn encode(data: &[u8], style: Style) -> alloc::string::String {
     words.join(separator)
 }
 
+/// Encodes a byte payload lazily, returning an iterator over the words of the
+/// payload and its checksum.
+///
+/// The words are the four-letter words for
+/// [`Style::Standard`] and [`Style::Uri`] and the two-letter words for
+/// [`Style::Minimal`], and joining them with the separator of the style
+/// yields the result of [`encode`].
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{encode_iter, Style};
+/// let words: Vec<_> = encode_iter(&[0], Style::Standard).collect();
+/// assert_eq!(words, ["able", "tied", "also", "webs", "lung"]);
+/// assert_eq!(encode_iter(&[0], Style::Minimal).collect::<String>(), "aetdaowslg");
+/// ```
+pub fn encode_iter(data: &[u8], style: Style) -> impl Iterator<Item = &'static str> + '_ {
+    let lookup = match style {
+        Style::Standard | Style::Uri => word_for,
+        Style::Minimal => minimal_for,
+    };
+    let checksum = crate::CRC32.checksum(data).to_be_bytes();
+    data.iter().copied().chain(checksum).map(lookup)
+}
+
+/// Encodes a byte payload like [`encode`], but writes the encoding into the
+/// given buffer instead of allocating it. Returns the written part of the
+/// buffer.
+///
+/// The buffer has to hold at least [`encoded_length`] bytes.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{encode_into, encoded_length, Style};
+/// let mut buffer = [0; 24];
+/// assert_eq!(encoded_length(1, Style::Standard), 24);
+/// assert_eq!(
+///     encode_into(&[0], Style::Standard, &mut buffer).unwrap(),
+///     "able tied also webs lung"
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If the encoding doesn't fit into the buffer, [`Error::BufferTooSmall`]
+/// will be returned.
+pub fn encode_into<'a>(data: &[u8], style: Style, buffer: &'a mut [u8]) -> Result<&'a str, Error> {
+    let buffer = buffer
+        .get_mut(..encoded_length(data.len(), style))
+        .ok_or(Error::BufferTooSmall)?;
+    let checksum = crate::CRC32.checksum(data).to_be_bytes();
+    let data = data.iter().chain(checksum.iter());
+    let separator = match style {
+        Style::Standard => b' ',
+        Style::Uri => b'-',
+        Style::Minimal => {
+            for (chunk, &b) in buffer.chunks_mut(2).zip(data) {
+                chunk.copy_from_slice(minimal_for(b).as_bytes());
+            }
+            // the minimal words are ASCII
+            return Ok(core::str::from_utf8(buffer).unwrap_or_default());
+        }
+    };
+    for (chunk, &b) in buffer.chunks_mut(5).zip(data) {
+        chunk[..4].copy_from_slice(word_for(b).as_bytes());
+        if let Some(c) = chunk.get_mut(4) {
+            *c = separator;
+        }
+    }
+    // the words and separators are ASCII
+    Ok(core::str::from_utf8(buffer).unwrap_or_default())
+}
+
+/// Returns the number of characters of the `bytewords` encoding of a payload
+/// with the given length, including the checksum.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::{encoded_length, Style};
+/// assert_eq!(encoded_length(1, Style::Standard), 24);
+/// assert_eq!(encoded_length(1, Style::Minimal), 10);
+/// ```
+#[must_use]
+pub const fn encoded_length(data_length: usize, style: Style) -> usize {
+    let words = data_length + 4;
+    match style {
+        Style::Standard | Style::Uri => words * 5 - 1,
+        Style::Minimal => words * 2,
+    }
+}
+
+/// Returns the four-letter word encoding the given byte.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::word_for;
+/// assert_eq!(word_for(0), "able");
+/// assert_eq!(word_for(255), "zoom");
+/// ```
+#[must_use]
+pub const fn word_for(byte: u8) -> &'static str {
+    crate::constants::WORDS[byte as usize]
+}
+
+/// Returns the two-letter minimal word encoding the given byte, which
+/// consists of the first and last letter of its [`word_for`].
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::minimal_for;
+/// assert_eq!(minimal_for(0), "ae");
+/// assert_eq!(minimal_for(255), "zm");
+/// ```
+#[must_use]
+pub const fn minimal_for(byte: u8) -> &'static str {
+    crate::constants::MINIMALS[byte as usize]
+}
+
+/// Returns the byte encoded by a four-letter word or a two-letter minimal
+/// word, or `None` if it isn't a `bytewords` word.
+///
+/// # Examples
+///
+/// ```
+/// use ur::bytewords::byte_for_word;
+/// assert_eq!(byte_for_word("zoom"), Some(255));
+/// assert_eq!(byte_for_word("zm"), Some(255));
+/// assert_eq!(byte_for_word("zoo"), None);
+/// ```
+#[must_use]
+pub fn byte_for_word(word: &str) -> Option<u8> {
+    word_byte(word).or_else(|| minimal_byte(word))
+}
+
+/// Defines a newtype around a byte payload which is displayed and parsed as
+/// `bytewords` of the given style.
+macro_rules! bytewords_type {
+    ($(#[$attr:meta])* $name:ident, $style:expr) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+        pub struct $name(pub Vec<u8>);
+
+        impl core::fmt::Display for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                for (index, word) in encode_iter(&self.0, $style).enumerate() {
+                    if index > 0 {
+                        match $style {
+                            Style::Standard => f.write_str(" ")?,
+                            Style::Uri => f.write_str("-")?,
+                            Style::Minimal => {}
+                        }
+                    }
+                    f.write_str(word)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl core::str::FromStr for $name {
+            type Err = Error;
+
+            fn from_str(encoded: &str) -> Result<Self, Self::Err> {
+                decode(encoded, $style).map(Self)
+            }
+        }
+
+        impl From<Vec<u8>> for $name {
+            fn from(data: Vec<u8>) -> Self {
+                Self(data)
+            }
+        }
+
+        impl From<$name> for Vec<u8> {
+            fn from(bytewords: $name) -> Self {
+                bytewords.0
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+    };
+}
+
+bytewords_type!(
+    /// A byte payload which is displayed and parsed as [`Style::Minimal`]
+    /// `bytewords`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::bytewords::MinimalBytewords;
+    /// let bytewords: MinimalBytewords = "aetdaowslg".parse().unwrap();
+    /// assert_eq!(bytewords.0, vec![0]);
+    /// assert_eq!(bytewords.to_string(), "aetdaowslg");
+    /// ```
+    MinimalBytewords,
+    Style::Minimal
+);
+
+bytewords_type!(
+    /// A byte payload which is displayed and parsed as [`Style::Standard`]
+    /// `bytewords`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::bytewords::StandardBytewords;
+    /// let bytewords: StandardBytewords = "able tied also webs lung".parse().unwrap();
+    /// assert_eq!(bytewords.0, vec![0]);
+    /// assert_eq!(bytewords.to_string(), "able tied also webs lung");
+    /// ```
+    StandardBytewords,
+    Style::Standard
+);
+
+bytewords_type!(
+    /// A byte payload which is displayed and parsed as [`Style::Uri`]
+    /// `bytewords`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::bytewords::UriBytewords;
+    /// let bytewords: UriBytewords = "able-tied-also-webs-lung".parse().unwrap();
+    /// assert_eq!(bytewords.0, vec![0]);
+    /// assert_eq!(bytewords.to_string(), "able-tied-also-webs-lung");
+    /// ```
+    UriBytewords,
+    Style::Uri
+);
+
+/// Serializes byte fields as minimal `bytewords` strings with
+/// `#[serde(with = "ur::bytewords::serde_minimal")]`.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Config {
+///     #[serde(with = "ur::bytewords::serde_minimal")]
+///     seed: Vec<u8>,
+/// }
+///
+/// let config: Config = serde_json::from_str(r#"{"seed":"aetdaowslg"}"#).unwrap();
+/// assert_eq!(config.seed, vec![0]);
+/// assert_eq!(
+///     serde_json::to_string(&config).unwrap(),
+///     r#"{"seed":"aetdaowslg"}"#
+/// );
+/// ```
+#[cfg(feature = "serde")]
+pub mod serde_minimal {
+    /// Serializes the bytes as a minimal `bytewords` string.
+    ///
+    /// # Errors
+    ///
+    /// Errors of the serializer are passed on.
+    pub fn serialize<T, S>(data: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+        S: serde::Serializer,
+    {
+        super::serialize_with(data.as_ref(), super::Style::Minimal, serializer)
+    }
+
+    /// Deserializes the bytes from a minimal `bytewords` string.
+    ///
+    /// # Errors
+    ///
+    /// If the string isn't a valid minimal `bytewords` encoding, an error will
+    /// be returned.
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<alloc::vec::Vec<u8>, D::Error> {
+        super::deserialize_with(deserializer, super::Style::Minimal)
+    }
+}
+
+/// Serializes byte fields as standard `bytewords` strings with
+/// `#[serde(with = "ur::bytewords::serde_standard")]`.
+///
+/// See [`serde_minimal`] for an example.
+#[cfg(feature = "serde")]
+pub mod serde_standard {
+    /// Serializes the bytes as a standard `bytewords` string.
+    ///
+    /// # Errors
+    ///
+    /// Errors of the serializer are passed on.
+    pub fn serialize<T, S>(data: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+        S: serde::Serializer,
+    {
+        super::serialize_with(data.as_ref(), super::Style::Standard, serializer)
+    }
+
+    /// Deserializes the bytes from a standard `bytewords` string.
+    ///
+    /// # Errors
+    ///
+    /// If the string isn't a valid standard `bytewords` encoding, an error will
+    /// be returned.
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<alloc::vec::Vec<u8>, D::Error> {
+        super::deserialize_with(deserializer, super::Style::Standard)
+    }
+}
+
+/// Serializes byte fields as URI `bytewords` strings with
+/// `#[serde(with = "ur::bytewords::serde_uri")]`.
+///
+/// See [`serde_minimal`] for an example.
+#[cfg(feature = "serde")]
+pub mod serde_uri {
+    /// Serializes the bytes as a URI `bytewords` string.
+    ///
+    /// # Errors
+    ///
+    /// Errors of the serializer are passed on.
+    pub fn serialize<T, S>(data: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+        S: serde::Serializer,
+    {
+        super::serialize_with(data.as_ref(), super::Style::Uri, serializer)
+    }
+
+    /// Deserializes the bytes from a URI `bytewords` string.
+    ///
+    /// # Errors
+    ///
+    /// If the string isn't a valid URI `bytewords` encoding, an error will be
+    /// returned.
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<alloc::vec::Vec<u8>, D::Error> {
+        super::deserialize_with(deserializer, super::Style::Uri)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn serialize_with<S: serde::Serializer>(
+    data: &[u8],
+    style: Style,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode(data, style))
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_with<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+    style: Style,
+) -> Result<Vec<u8>, D::Error> {
+    let encoded: alloc::string::String = serde::Deserialize::deserialize(deserializer)?;
+    decode(&encoded, style).map_err(serde::de::Error::custom)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_crc() {
-        assert_eq!(crate::crc32().checksum(b"Hello, world!"), 0xebe6_c6e6);
-        assert_eq!(crate::crc32().checksum(b"Wolf"), 0x598c_84dc);
+        assert_eq!(crate::CRC32.checksum(b"Hello, world!"), 0xebe6_c6e6);
+        assert_eq!(crate::CRC32.checksum(b"Wolf"), 0x598c_84dc);
+    }
+
+    #[test]
+    fn test_lookups() {
+        for byte in 0..=u8::MAX {
+            let word = word_for(byte);
+            let minimal = minimal_for(byte);
+            assert_eq!(word.len(), 4);
+            assert_eq!(minimal, [&word[..1], &word[3..]].concat());
+            assert_eq!(byte_for_word(word), Some(byte));
+            assert_eq!(byte_for_word(minimal), Some(byte));
+        }
+        assert_eq!(byte_for_word(""), None);
+        assert_eq!(byte_for_word("Able"), None);
+        assert_eq!(byte_for_word("ablee"), None);
+        // shares the first and last letter with "able"
+        assert_eq!(byte_for_word("axle"), None);
+    }
+
+    #[test]
+    fn test_minimal_byte() {
+        for byte in 0..=u8::MAX {
+            assert_eq!(minimal_byte(minimal_for(byte)), Some(byte));
+        }
+        assert_eq!(minimal_byte("ab"), None);
+        assert_eq!(minimal_byte("AE"), None);
+        assert_eq!(minimal_byte("a{"), None);
+        assert_eq!(minimal_byte("a"), None);
+        assert_eq!(minimal_byte("aea"), None);
     }
 
     #[test]
@@ -245,7 +1191,38 @@ mod tests {
             decode("wolf", Style::Standard).unwrap_err(),
             Error::InvalidChecksum
         );
-        assert_eq!(decode("", Style::Standard).unwrap_err(), Error::InvalidWord);
+        assert_eq!(
+            decode("", Style::Standard).unwrap_err(),
+            Error::InvalidWord {
+                position: 0,
+                word: String::new()
+            }
+        );
+
+        // invalid words
+        assert_eq!(
+            decode("able acid also lava zoom jade need echo taxi", Style::Uri).unwrap_err(),
+            Error::InvalidWord {
+                position: 0,
+                word: "able acid also lava zoom jade need echo taxi".into()
+            }
+        );
+        assert_eq!(
+            decode(
+                "able acid also lava zoom jade nerd echo taxi",
+                Style::Standard
+            )
+            .unwrap_err()
+            .to_string(),
+            "invalid word \"nerd\" at position 6"
+        );
+        assert_eq!(
+            decode("aeadaolazmjendeotx", Style::Minimal).unwrap_err(),
+            Error::InvalidWord {
+                position: 8,
+                word: "tx".into()
+            }
+        );
 
         // invalid length
         assert_eq!(
@@ -259,6 +1236,389 @@ mod tests {
         assert_eq!(decode("₿", Style::Minimal).unwrap_err(), Error::NonAscii);
     }
 
+    #[test]
+    fn test_decode_bytes() {
+        let input = vec![0, 1, 2, 128, 255];
+        for style in [Style::Standard, Style::Uri, Style::Minimal] {
+            let encoded = encode(&input, style);
+            assert_eq!(decode_bytes(encoded.as_bytes(), style).unwrap(), input);
+        }
+        assert_eq!(
+            decode_bytes(b"aeadaolazojendeowf", Style::Minimal),
+            Err(Error::InvalidChecksum)
+        );
+        assert_eq!(
+            decode_bytes(b"ae\xff", Style::Minimal),
+            Err(Error::NonAscii)
+        );
+        assert_eq!(
+            decode_bytes(&[0x80, 0x80], Style::Minimal),
+            Err(Error::NonAscii)
+        );
+    }
+
+    #[test]
+    fn test_decode_fuzzy() {
+        let input = vec![0, 1, 2, 128, 255];
+        for style in [Style::Standard, Style::Uri, Style::Minimal] {
+            let encoded = encode(&input, style);
+            assert_eq!(
+                decode_fuzzy(&encoded, style).unwrap(),
+                (input.clone(), vec![])
+            );
+        }
+
+        let correction = |position, word: &str, corrected| Correction {
+            position,
+            word: word.into(),
+            corrected,
+        };
+        assert_eq!(
+            decode_fuzzy(
+                "abl acidd alxo lsva zoom jade need echo taxi",
+                Style::Standard
+            )
+            .unwrap(),
+            (
+                input.clone(),
+                vec![
+                    correction(0, "abl", "able"),
+                    correction(1, "acidd", "acid"),
+                    correction(2, "alxo", "also"),
+                    correction(3, "lsva", "lava"),
+                ]
+            )
+        );
+        assert_eq!(
+            decode_fuzzy("able-acid-also-lava-zoom-jade-need-echo-xaxi", Style::Uri).unwrap(),
+            (input.clone(), vec![correction(8, "xaxi", "taxi")])
+        );
+        assert_eq!(
+            decode_fuzzy("aeadaolazmjendeotx", Style::Minimal),
+            Err(Error::InvalidWord {
+                position: 8,
+                word: "tx".into()
+            })
+        );
+
+        // ambiguous corrections
+        assert_eq!(
+            decode_fuzzy(
+                "ible acid also lava zoom jade need echo taxi",
+                Style::Standard
+            ),
+            Err(Error::InvalidWord {
+                position: 0,
+                word: "ible".into()
+            })
+        );
+        assert_eq!(
+            decode_fuzzy(
+                "able acid also lava zoom jade need echo taxxi",
+                Style::Standard
+            ),
+            Ok((
+                vec![0, 1, 2, 128, 255],
+                vec![correction(8, "taxxi", "taxi")]
+            ))
+        );
+        assert_eq!(
+            decode_fuzzy(
+                "able acid also lava zoom jade need echo tax",
+                Style::Standard
+            ),
+            Ok((vec![0, 1, 2, 128, 255], vec![correction(8, "tax", "taxi")]))
+        );
+        // corrections don't circumvent the checksum
+        assert_eq!(
+            decode_fuzzy(
+                "able acid alsx lava zoom jade need echo wolf",
+                Style::Standard
+            ),
+            Err(Error::InvalidChecksum)
+        );
+        assert_eq!(
+            decode_fuzzy("aea", Style::Minimal),
+            Err(Error::InvalidLength)
+        );
+        assert_eq!(decode_fuzzy("₿", Style::Standard), Err(Error::NonAscii));
+    }
+
+    #[test]
+    fn test_is_single_edit() {
+        assert!(is_single_edit("able", "able"));
+        assert!(is_single_edit("able", "ablx"));
+        assert!(is_single_edit("able", "xble"));
+        assert!(is_single_edit("able", "abe"));
+        assert!(is_single_edit("able", "xable"));
+        assert!(is_single_edit("able", "ablex"));
+        assert!(is_single_edit("", "a"));
+        assert!(!is_single_edit("able", "bale"));
+        assert!(!is_single_edit("able", "ab"));
+        assert!(!is_single_edit("able", "xablex"));
+    }
+
+    #[test]
+    fn test_detect_style() {
+        let input = vec![0, 1, 2, 128, 255];
+        for style in [Style::Standard, Style::Uri, Style::Minimal] {
+            let encoded = encode(&input, style);
+            assert_eq!(detect_style(&encoded), Some(style));
+            assert_eq!(decode_any(&encoded).unwrap(), input);
+        }
+        assert_eq!(detect_style(""), None);
+        assert_eq!(detect_style("aea"), None);
+        assert_eq!(detect_style("able-acid also"), None);
+        assert_eq!(detect_style("able  acid"), Some(Style::Standard));
+        assert_eq!(detect_style("ABLE ACID"), None);
+        assert_eq!(detect_style("₿"), None);
+
+        assert_eq!(decode_any(""), Err(Error::InvalidChecksum));
+        assert_eq!(decode_any("aea"), Err(Error::InvalidLength));
+        assert_eq!(decode_any("₿"), Err(Error::NonAscii));
+        assert_eq!(
+            decode_any("able-acid also"),
+            Err(Error::InvalidWord {
+                position: 0,
+                word: "able-acid".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_hints() {
+        let hint = |encoded, style| decode(encoded, style).unwrap_err().hint().unwrap();
+        assert!(hint("ae-dlg", Style::Minimal).starts_with("the input looks like URI-style"));
+        assert!(hint("ae tdl", Style::Minimal).starts_with("the input looks like standard"));
+        assert!(hint("able-acid also", Style::Standard).starts_with("the input looks like URI"));
+        assert!(hint("aetdaowslg", Style::Standard).starts_with("the input looks like minimal"));
+        assert!(hint("AETDAOWSLG", Style::Minimal).contains("uppercase"));
+        assert!(hint("zzzzzzzz", Style::Minimal).contains("misspelled"));
+        assert!(hint("aetdaowslf", Style::Minimal).contains("truncated or misread"));
+        assert!(hint("aea", Style::Minimal).contains("truncated"));
+        assert!(hint("₿", Style::Minimal).contains("ASCII"));
+        assert_eq!(Error::BufferTooSmall.hint(), None);
+    }
+
+    #[test]
+    fn test_verify() {
+        let inputs = [
+            "able acid also lava zoom jade need echo taxi",
+            "able-acid-also-lava-zoom-jade-need-echo-taxi",
+            "able acid also lava zero jade need echo wolf",
+            "aeadaolazmjendeoti",
+            "aeadaolazojendeowf",
+            "wolf",
+            "",
+            "able ",
+            "aea",
+            "aeae",
+            "₿",
+        ];
+        assert_eq!(
+            verify("able acid also lava", Style::Uri),
+            Err(Error::InvalidWord {
+                position: 0,
+                word: "able ".into()
+            })
+        );
+        for encoded in inputs {
+            for style in [Style::Standard, Style::Uri, Style::Minimal] {
+                assert_eq!(
+                    verify(encoded, style).map_err(|e| core::mem::discriminant(&e)),
+                    decode(encoded, style)
+                        .map(|_| ())
+                        .map_err(|e| core::mem::discriminant(&e)),
+                    "{encoded} {style:?}"
+                );
+            }
+        }
+        assert_eq!(
+            decode_with_checksum("aeadaolazmjendeoti", Style::Minimal).unwrap(),
+            (vec![0, 1, 2, 128, 255], 0x6b9b_33d0)
+        );
+    }
+
+    #[test]
+    fn test_encode_iter() {
+        let input = vec![0, 1, 2, 128, 255];
+        let words: Vec<_> = encode_iter(&input, Style::Standard).collect();
+        assert_eq!(
+            words,
+            ["able", "acid", "also", "lava", "zoom", "jade", "need", "echo", "taxi"]
+        );
+        for (style, separator) in [
+            (Style::Standard, " "),
+            (Style::Uri, "-"),
+            (Style::Minimal, ""),
+        ] {
+            let words: Vec<_> = encode_iter(&input, style).collect();
+            assert_eq!(words.join(separator), encode(&input, style));
+        }
+        assert_eq!(encode_iter(&[], Style::Minimal).count(), 4);
+    }
+
+    #[test]
+    fn test_encode_into_decode_into() {
+        let input = vec![0, 1, 2, 128, 255];
+        for style in [Style::Standard, Style::Uri, Style::Minimal] {
+            let encoded = encode(&input, style);
+            assert_eq!(encoded_length(input.len(), style), encoded.len());
+            assert_eq!(decoded_length(encoded.len(), style), Some(input.len()));
+
+            let mut buffer = vec![0; encoded.len() + 1];
+            assert_eq!(encode_into(&input, style, &mut buffer).unwrap(), encoded);
+            assert_eq!(
+                encode_into(&input, style, &mut buffer[..encoded.len() - 1]),
+                Err(Error::BufferTooSmall)
+            );
+
+            let mut buffer = vec![0; input.len()];
+            assert_eq!(decode_into(&encoded, style, &mut buffer).unwrap(), input);
+            assert_eq!(
+                decode_into(&encoded, style, &mut buffer[..input.len() - 1]),
+                Err(Error::BufferTooSmall)
+            );
+        }
+        let mut buffer = [0; 8];
+        assert_eq!(
+            encode_into(&[], Style::Minimal, &mut buffer).unwrap(),
+            "aeaeaeae"
+        );
+        assert_eq!(
+            decode_into("aeaeaeae", Style::Minimal, &mut []).unwrap(),
+            &[] as &[u8]
+        );
+        assert_eq!(
+            decode_into("aeadaolazojendeowf", Style::Minimal, &mut buffer),
+            Err(Error::InvalidChecksum)
+        );
+
+        assert_eq!(decoded_length(0, Style::Standard), None);
+        assert_eq!(decoded_length(14, Style::Standard), None);
+        assert_eq!(decoded_length(19, Style::Uri), Some(0));
+        assert_eq!(decoded_length(8, Style::Minimal), Some(0));
+        assert_eq!(decoded_length(9, Style::Minimal), None);
+    }
+
+    #[test]
+    fn test_bytewords_types() {
+        let input = vec![0, 1, 2, 128, 255];
+        let minimal = MinimalBytewords::from(input.clone());
+        assert_eq!(minimal.to_string(), encode(&input, Style::Minimal));
+        assert_eq!(
+            minimal.to_string().parse::<MinimalBytewords>().unwrap(),
+            minimal
+        );
+        let standard = StandardBytewords(input.clone());
+        assert_eq!(standard.to_string(), encode(&input, Style::Standard));
+        assert_eq!(
+            standard.to_string().parse::<StandardBytewords>().unwrap(),
+            standard
+        );
+        let uri = UriBytewords(input.clone());
+        assert_eq!(uri.to_string(), encode(&input, Style::Uri));
+        assert_eq!(uri.to_string().parse::<UriBytewords>().unwrap(), uri);
+        assert_eq!(Vec::from(uri), input);
+
+        assert_eq!(MinimalBytewords::default().to_string(), "aeaeaeae");
+        assert_eq!(
+            "able tied also webs lung".parse::<UriBytewords>(),
+            Err(Error::InvalidWord {
+                position: 0,
+                word: "able tied also webs lung".into()
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Fields {
+            #[serde(with = "super::serde_minimal")]
+            minimal: Vec<u8>,
+            #[serde(with = "super::serde_standard")]
+            standard: Vec<u8>,
+            #[serde(with = "super::serde_uri")]
+            uri: Vec<u8>,
+        }
+
+        let fields = Fields {
+            minimal: vec![0, 1, 2, 128, 255],
+            standard: vec![0, 1, 2, 128, 255],
+            uri: vec![],
+        };
+        let value = serde_json::to_value(&fields).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "minimal": "aeadaolazmjendeoti",
+                "standard": "able acid also lava zoom jade need echo taxi",
+                "uri": "able-able-able-able",
+            })
+        );
+        assert_eq!(serde_json::from_value::<Fields>(value).unwrap(), fields);
+
+        let error = serde_json::from_value::<Fields>(serde_json::json!({
+            "minimal": "aeadaolazojendeowf",
+            "standard": "able tied also webs lung",
+            "uri": "able-tied-also-webs-lung",
+        }))
+        .unwrap_err();
+        assert_eq!(error.to_string(), "invalid checksum");
+    }
+
+    #[test]
+    fn test_stream_decoder() {
+        let input = vec![0, 1, 2, 128, 255];
+        for style in [Style::Standard, Style::Uri, Style::Minimal] {
+            let encoded = encode(&input, style);
+            for chunk_size in 1..=encoded.len() {
+                let mut decoder = StreamDecoder::new(style);
+                let mut payload = Vec::new();
+                for chunk in encoded.as_bytes().chunks(chunk_size) {
+                    let chunk = core::str::from_utf8(chunk).unwrap();
+                    payload.extend(decoder.push(chunk).unwrap());
+                }
+                payload.extend(decoder.finish().unwrap());
+                assert_eq!(payload, input);
+            }
+        }
+
+        let decode = |encoded: &str, style| {
+            let mut decoder = StreamDecoder::new(style);
+            let mut payload = decoder.push(encoded)?;
+            payload.extend(decoder.finish()?);
+            Ok(payload)
+        };
+        assert_eq!(decode("", Style::Minimal), Err(Error::InvalidChecksum));
+        let invalid_word = |position, word: &str| {
+            Err(Error::InvalidWord {
+                position,
+                word: word.into(),
+            })
+        };
+        assert_eq!(decode("", Style::Standard), invalid_word(0, ""));
+        assert_eq!(decode("able ", Style::Standard), invalid_word(1, ""));
+        assert_eq!(
+            decode("able ables", Style::Standard),
+            invalid_word(1, "ables")
+        );
+        assert_eq!(decode("aeadzz", Style::Minimal), invalid_word(2, "zz"));
+        assert_eq!(decode("aea", Style::Minimal), Err(Error::InvalidLength));
+        assert_eq!(decode("₿", Style::Uri), Err(Error::NonAscii));
+        assert_eq!(
+            decode("aeadaolazojendeowf", Style::Minimal),
+            Err(Error::InvalidChecksum)
+        );
+        assert_eq!(decode("able-tied-also-webs-lung", Style::Uri), Ok(vec![0]));
+        assert_eq!(
+            decode("able able able able", Style::Standard),
+            Ok(Vec::new())
+        );
+    }
+
     #[test]
     fn test_encoding() {
         let input: [u8; 100] = [
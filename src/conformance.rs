@@ -0,0 +1,417 @@
+//! Test vectors of the reference implementation, with a runner verifying
+//! them against this crate.
+//!
+//! The vectors cover `bytewords` encodings, fountain parts and single- and
+//! multi-part URs. Alternative implementations and bindings can iterate over
+//! the vectors to verify byte-exact compatibility, while [`run`] checks this
+//! crate itself. Byte payloads are hex-encoded.
+//!
+//! # Examples
+//!
+//! ```
+//! ur::conformance::run().unwrap();
+//! for vector in ur::conformance::BYTEWORDS {
+//!     assert_eq!(vector.minimal.len(), vector.payload.len() + 8);
+//! }
+//! ```
+
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A `bytewords` test vector, encoding a payload in all three styles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BytewordsVector {
+    /// The name of the vector.
+    pub name: &'static str,
+    /// The hex-encoded payload.
+    pub payload: &'static str,
+    /// The [`Style::Standard`](crate::bytewords::Style::Standard) encoding.
+    pub standard: &'static str,
+    /// The [`Style::Uri`](crate::bytewords::Style::Uri) encoding.
+    pub uri: &'static str,
+    /// The [`Style::Minimal`](crate::bytewords::Style::Minimal) encoding.
+    pub minimal: &'static str,
+}
+
+/// A fountain encoder test vector, listing the data of the first parts
+/// emitted for a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FountainVector {
+    /// The name of the vector.
+    pub name: &'static str,
+    /// The hex-encoded message.
+    pub message: &'static str,
+    /// The maximum fragment length passed to the encoder.
+    pub max_fragment_length: usize,
+    /// The CRC32 checksum of the message.
+    pub checksum: u32,
+    /// The number of fragments the message is split into.
+    pub sequence_count: usize,
+    /// The hex-encoded data of the parts, starting at sequence number one.
+    pub parts: &'static [&'static str],
+}
+
+/// A UR test vector, listing the first parts emitted for a CBOR payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UrVector {
+    /// The name of the vector.
+    pub name: &'static str,
+    /// The UR type.
+    pub ur_type: &'static str,
+    /// The hex-encoded CBOR payload.
+    pub cbor: &'static str,
+    /// The maximum fragment length of a multi-part UR, or `None` for a
+    /// single-part UR.
+    pub max_fragment_length: Option<usize>,
+    /// The URs, starting at sequence number one for multi-part URs.
+    pub parts: &'static [&'static str],
+}
+
+/// The `bytewords` test vectors.
+pub static BYTEWORDS: [BytewordsVector; 3] = [
+    BytewordsVector {
+        name: "bytewords-short",
+        payload: "00010280ff",
+        standard: "able acid also lava zoom jade need echo taxi",
+        uri: "able-acid-also-lava-zoom-jade-need-echo-taxi",
+        minimal: "aeadaolazmjendeoti",
+    },
+    BytewordsVector {
+        name: "bytewords-empty",
+        payload: "",
+        standard: "able able able able",
+        uri: "able-able-able-able",
+        minimal: "aeaeaeae",
+    },
+    BytewordsVector {
+        name: "bytewords-100-bytes",
+        payload: concat!(
+            "f5d714c6f1eb453bd1cda512969e7487e5d4139f1125eff0fd0b6dbf25f22678",
+            "df299cbdf2fe93cc42a3d8afbf48a936203c90e6d289b8c52171580e9d1fb12e",
+            "0173cd45e19641eb3a9041f0854571f73f35f2a5a0901a0d4fed85475245fea5",
+            "8a295518",
+        ),
+        standard: concat!(
+            "yank toys bulb skew when warm free fair tent swan ",
+            "open brag mint noon jury list view tiny brew note ",
+            "body data webs what zinc bald join runs data whiz ",
+            "days keys user diet news ruby whiz zone menu surf ",
+            "flew omit trip pose runs fund part even crux fern ",
+            "math visa tied loud redo silk curl jugs hard beta ",
+            "next cost puma drum acid junk swan free very mint ",
+            "flap warm fact math flap what limp free jugs yell ",
+            "fish epic whiz open numb math city belt glow wave ",
+            "limp fuel grim free zone open love diet gyro cats ",
+            "fizz holy city puff",
+        ),
+        uri: concat!(
+            "yank-toys-bulb-skew-when-warm-free-fair-tent-swan-",
+            "open-brag-mint-noon-jury-list-view-tiny-brew-note-",
+            "body-data-webs-what-zinc-bald-join-runs-data-whiz-",
+            "days-keys-user-diet-news-ruby-whiz-zone-menu-surf-",
+            "flew-omit-trip-pose-runs-fund-part-even-crux-fern-",
+            "math-visa-tied-loud-redo-silk-curl-jugs-hard-beta-",
+            "next-cost-puma-drum-acid-junk-swan-free-very-mint-",
+            "flap-warm-fact-math-flap-what-limp-free-jugs-yell-",
+            "fish-epic-whiz-open-numb-math-city-belt-glow-wave-",
+            "limp-fuel-grim-free-zone-open-love-diet-gyro-cats-",
+            "fizz-holy-city-puff",
+        ),
+        minimal: concat!(
+            "yktsbbswwnwmfefrttsnonbgmtnnjyltvwtybwnebydawswtzcbdjnrsdawz",
+            "dsksurdtnsrywzzemusffwottppersfdptencxfnmhvatdldroskcljshdba",
+            "ntctpadmadjksnfevymtfpwmftmhfpwtlpfejsylfhecwzonnbmhcybtgwwe",
+            "lpflgmfezeonledtgocsfzhycypf",
+        ),
+    },
+];
+
+/// The fountain encoder test vectors.
+pub static FOUNTAIN: [FountainVector; 1] = [FountainVector {
+    name: "fountain-256-bytes",
+    message: concat!(
+        "916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3ccba44f",
+        "7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a8cde6d0e2ec4",
+        "3f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f965e25ee29039fdf8c",
+        "a74f1c769fc07eb7ebaec46e0695aea6cbd60b3ec4bbff1b9ffe8a9e72401293",
+        "77b9d3711ed38d412fbb4442256f1e6f595e0fc57fed451fb0a0101fb76b1fb1",
+        "e1b88cfdfdaa946294a47de8fff173f021c0e6f65b05c0a494e50791270a0050",
+        "a73ae69b6725505a2ec8a5791457c9876dd34aadd192a53aa0dc66b556c0c215",
+        "c7ceb8248b717c22951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d",
+    ),
+    max_fragment_length: 30,
+    checksum: 0x0167_aa07,
+    sequence_count: 9,
+    parts: &[
+        "916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3c",
+        "cba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a",
+        "8cde6d0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f",
+        "965e25ee29039fdf8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3e",
+        "c4bbff1b9ffe8a9e7240129377b9d3711ed38d412fbb4442256f1e6f59",
+        "5e0fc57fed451fb0a0101fb76b1fb1e1b88cfdfdaa946294a47de8fff1",
+        "73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
+        "791457c9876dd34aadd192a53aa0dc66b556c0c215c7ceb8248b717c22",
+        "951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d0000000000",
+        "330f0f33a05eead4f331df229871bee733b50de71afd2e5a79f196de09",
+        "3b205ce5e52d8c24a52cffa34c564fa1af3fdffcd349dc4258ee4ee828",
+        "dd7bf725ea6c16d531b5f03254783803048ca08b87148daacd1cd7a006",
+        "760be7ad1c6187902bbc04f539b9ee5eb8ea6833222edea36031306c01",
+        "5bf4031217d2c3254b088fa7553778b5003632f46e21db129416f65b55",
+        "73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
+        "b8546ebfe2048541348910267331c643133f828afec9337c318f71b7df",
+        "23dedeea74e3a0fb052befabefa13e2f80e4315c9dceed4c8630612e64",
+        "d01a8daee769ce34b6b35d3ca0005302724abddae405bdb419c0a6b208",
+        "3171c5dc365766eff25ae47c6f10e7de48cfb8474e050e5fe997a6dc24",
+        "e055c2433562184fa71b4be94f262e200f01c6f74c284b0dc6fae6673f",
+    ],
+}];
+
+/// The UR test vectors.
+pub static URS: [UrVector; 3] = [
+    UrVector {
+        name: "ur-single-part",
+        ur_type: "bytes",
+        cbor: concat!(
+            "5832916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3ccb",
+            "a44f7f04f2de44f42d84c374a0e149136f25b018",
+        ),
+        max_fragment_length: None,
+        parts: &["ur:bytes/hdeymejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtgwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsdwkbrkch"],
+    },
+    UrVector {
+        name: "ur-crypto-request",
+        ur_type: "crypto-request",
+        cbor: concat!(
+            "a201d82550020c223a86f7464693fc650ef3cac04702d901f4a101d902585820",
+            "e824467caffeaf3bbc3e0ca095e660a9bad80ddb6a919433a37161908b9a3986",
+        ),
+        max_fragment_length: None,
+        parts: &["ur:crypto-request/oeadtpdagdaobncpftlnylfgfgmuztihbawfsgrtflaotaadwkoyadtaaohdhdcxvsdkfgkepezepefrrffmbnnbmdvahnptrdtpbtuyimmemweootjshsmhlunyeslnameyhsdi"],
+    },
+    UrVector {
+        name: "ur-multi-part",
+        ur_type: "bytes",
+        cbor: concat!(
+            "590100916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3c",
+            "cba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a8cde6d",
+            "0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f965e25ee2903",
+            "9fdf8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3ec4bbff1b9ffe8a9e72",
+            "40129377b9d3711ed38d412fbb4442256f1e6f595e0fc57fed451fb0a0101fb7",
+            "6b1fb1e1b88cfdfdaa946294a47de8fff173f021c0e6f65b05c0a494e5079127",
+            "0a0050a73ae69b6725505a2ec8a5791457c9876dd34aadd192a53aa0dc66b556",
+            "c0c215c7ceb8248b717c22951e65305b56a3706e3e86eb01c803bbf915d80edc",
+            "d64d4d",
+        ),
+        max_fragment_length: Some(30),
+        parts: &[
+            "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh",
+            "ur:bytes/2-9/lpaoascfadaxcywenbpljkhdcagwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsgmghhkhstlrdcxaefz",
+            "ur:bytes/3-9/lpaxascfadaxcywenbpljkhdcahelbknlkuejnbadmssfhfrdpsbiegecpasvssovlgeykssjykklronvsjksopdzmol",
+            "ur:bytes/4-9/lpaaascfadaxcywenbpljkhdcasotkhemthydawydtaxneurlkosgwcekonertkbrlwmplssjtammdplolsbrdzcrtas",
+            "ur:bytes/5-9/lpahascfadaxcywenbpljkhdcatbbdfmssrkzmcwnezelennjpfzbgmuktrhtejscktelgfpdlrkfyfwdajldejokbwf",
+            "ur:bytes/6-9/lpamascfadaxcywenbpljkhdcackjlhkhybssklbwefectpfnbbectrljectpavyrolkzczcpkmwidmwoxkilghdsowp",
+            "ur:bytes/7-9/lpatascfadaxcywenbpljkhdcavszmwnjkwtclrtvaynhpahrtoxmwvwatmedibkaegdosftvandiodagdhthtrlnnhy",
+            "ur:bytes/8-9/lpayascfadaxcywenbpljkhdcadmsponkkbbhgsoltjntegepmttmoonftnbuoiyrehfrtsabzsttorodklubbuyaetk",
+            "ur:bytes/9-9/lpasascfadaxcywenbpljkhdcajskecpmdckihdyhphfotjojtfmlnwmadspaxrkytbztpbauotbgtgtaeaevtgavtny",
+            "ur:bytes/10-9/lpbkascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtwdkiplzs",
+            "ur:bytes/11-9/lpbdascfadaxcywenbpljkhdcahelbknlkuejnbadmssfhfrdpsbiegecpasvssovlgeykssjykklronvsjkvetiiapk",
+            "ur:bytes/12-9/lpbnascfadaxcywenbpljkhdcarllaluzmdmgstospeyiefmwejlwtpedamktksrvlcygmzemovovllarodtmtbnptrs",
+            "ur:bytes/13-9/lpbtascfadaxcywenbpljkhdcamtkgtpknghchchyketwsvwgwfdhpgmgtylctotzopdrpayoschcmhplffziachrfgd",
+            "ur:bytes/14-9/lpbaascfadaxcywenbpljkhdcapazewnvonnvdnsbyleynwtnsjkjndeoldydkbkdslgjkbbkortbelomueekgvstegt",
+            "ur:bytes/15-9/lpbsascfadaxcywenbpljkhdcaynmhpddpzmversbdqdfyrehnqzlugmjzmnmtwmrouohtstgsbsahpawkditkckynwt",
+            "ur:bytes/16-9/lpbeascfadaxcywenbpljkhdcawygekobamwtlihsnpalnsghenskkiynthdzotsimtojetprsttmukirlrsbtamjtpd",
+            "ur:bytes/17-9/lpbyascfadaxcywenbpljkhdcamklgftaxykpewyrtqzhydntpnytyisincxmhtbceaykolduortotiaiaiafhiaoyce",
+            "ur:bytes/18-9/lpbgascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtntwkbkwy",
+            "ur:bytes/19-9/lpbwascfadaxcywenbpljkhdcadekicpaajootjzpsdrbalpeywllbdsnbinaerkurspbncxgslgftvtsrjtksplcpeo",
+            "ur:bytes/20-9/lpbbascfadaxcywenbpljkhdcayapmrleeleaxpasfrtrdkncffwjyjzgyetdmlewtkpktgllepfrltataztksmhkbot",
+        ],
+    },
+];
+
+/// A mismatch between a test vector and this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// The name of the failing vector.
+    pub vector: &'static str,
+    /// The expected value.
+    pub expected: String,
+    /// The actual value.
+    pub actual: String,
+}
+
+impl core::fmt::Display for Failure {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "vector {} failed: expected {}, got {}",
+            self.vector, self.expected, self.actual
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Failure {}
+
+/// Verifies all test vectors against this crate, encoding and decoding each
+/// of them.
+///
+/// # Examples
+///
+/// See the [module documentation](self) for an example.
+///
+/// # Errors
+///
+/// The first mismatch between a vector and this crate is returned.
+pub fn run() -> Result<(), Failure> {
+    use crate::bytewords::Style;
+
+    for vector in &BYTEWORDS {
+        let payload = from_hex(vector.payload);
+        for (style, encoded) in [
+            (Style::Standard, vector.standard),
+            (Style::Uri, vector.uri),
+            (Style::Minimal, vector.minimal),
+        ] {
+            check(
+                vector.name,
+                encoded,
+                crate::bytewords::encode(&payload, style),
+            )?;
+            let decoded = crate::bytewords::decode(encoded, style).map(|data| to_hex(&data));
+            check(vector.name, vector.payload, flatten(decoded))?;
+        }
+    }
+
+    for vector in &FOUNTAIN {
+        let message = from_hex(vector.message);
+        let mut encoder = crate::fountain::Encoder::new(&message, vector.max_fragment_length)
+            .map_err(|e| failure(vector.name, "an encoder", e))?;
+        let mut decoder = crate::fountain::Decoder::default();
+        for (index, data) in vector.parts.iter().enumerate() {
+            let expected = alloc::format!(
+                "[{}, {}, {}, 0x{:08x}, h'{data}']",
+                index + 1,
+                vector.sequence_count,
+                message.len(),
+                vector.checksum,
+            );
+            let part = encoder.next_part();
+            check(vector.name, &expected, part.to_diagnostic())?;
+            decoder
+                .receive(part)
+                .map_err(|e| failure(vector.name, "a valid part", e))?;
+        }
+        let result = decoder.message().map(Option::unwrap_or_default);
+        check(
+            vector.name,
+            vector.message,
+            flatten(result.map(|m| to_hex(&m))),
+        )?;
+    }
+
+    for vector in &URS {
+        let cbor = from_hex(vector.cbor);
+        let Some(max_fragment_length) = vector.max_fragment_length else {
+            let ur_type = crate::ur::Type::Custom(vector.ur_type);
+            for part in vector.parts {
+                check(vector.name, part, crate::ur::encode(&cbor, &ur_type))?;
+                let result = crate::ur::decode(part).map(|parsed| to_hex(parsed.payload()));
+                check(vector.name, vector.cbor, flatten(result))?;
+            }
+            continue;
+        };
+
+        let mut encoder = crate::ur::Encoder::new(&cbor, max_fragment_length, vector.ur_type)
+            .map_err(|e| failure(vector.name, "an encoder", e))?;
+        let mut decoder = crate::ur::Decoder::default();
+        for part in vector.parts {
+            check(vector.name, part, flatten(encoder.next_part()))?;
+            if !decoder.complete() {
+                decoder
+                    .receive(part)
+                    .map_err(|e| failure(vector.name, "a valid part", e))?;
+            }
+        }
+        let result = decoder.message().map(Option::unwrap_or_default);
+        check(
+            vector.name,
+            vector.cbor,
+            flatten(result.map(|m| to_hex(&m))),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn check(vector: &'static str, expected: &str, actual: String) -> Result<(), Failure> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Failure {
+            vector,
+            expected: expected.into(),
+            actual,
+        })
+    }
+}
+
+fn failure(vector: &'static str, expected: &str, error: impl core::fmt::Display) -> Failure {
+    Failure {
+        vector,
+        expected: expected.into(),
+        actual: alloc::format!("error: {error}"),
+    }
+}
+
+/// Renders errors into the compared value.
+fn flatten<E: core::fmt::Display>(result: Result<String, E>) -> String {
+    result.unwrap_or_else(|e| alloc::format!("error: {e}"))
+}
+
+fn to_hex(data: &[u8]) -> String {
+    use core::fmt::Write;
+    let mut hex = String::with_capacity(2 * data.len());
+    for byte in data {
+        // writing to a string can't fail
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+/// Decodes the hex-encoded payloads of the vectors, which are valid.
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .filter_map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        run().unwrap();
+    }
+
+    #[test]
+    fn test_hex() {
+        for vector in &BYTEWORDS {
+            assert_eq!(to_hex(&from_hex(vector.payload)), vector.payload);
+        }
+        assert_eq!(from_hex("00ff10"), vec![0, 255, 16]);
+    }
+
+    #[test]
+    fn test_failure() {
+        assert_eq!(
+            check("name", "expected", "actual".into())
+                .unwrap_err()
+                .to_string(),
+            "vector name failed: expected expected, got actual"
+        );
+        assert!(check("name", "value", "value".into()).is_ok());
+    }
+}
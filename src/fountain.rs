@@ -8,12 +8,7 @@
 //! A seeded `Xoshiro` RNG ensures that the receiver can reconstruct which segments
 //! were combined into the part.
 //! ```
-//! let xor = |a: &[u8], b: &[u8]| {
-//!     a.iter()
-//!         .zip(b.iter())
-//!         .map(|(&x1, &x2)| x1 ^ x2)
-//!         .collect::<Vec<_>>()
-//! };
+//! use ur::fountain::xor;
 //!
 //! let data = String::from("Ten chars!");
 //! let max_length = 4;
@@ -78,6 +73,10 @@
 //! );
 //! ```
 
+pub mod analysis;
+#[cfg(feature = "raptorq")]
+pub mod raptorq;
+
 extern crate alloc;
 use alloc::vec::Vec;
 use core::convert::Infallible;
@@ -89,11 +88,13 @@ pub enum Error {
     CborDecode(minicbor::decode::Error),
     /// CBOR encoding error.
     CborEncode(minicbor::encode::Error<Infallible>),
-    /// Expected non-empty message.
+    /// Expected non-empty message. Empty payloads can't be split into
+    /// fountain parts and need to be transmitted as a single-part UR.
     EmptyMessage,
     /// Expected non-empty part.
     EmptyPart,
-    /// Fragment length should be a positive integer greater than 0.
+    /// Fragment length bounds should be positive integers, with the minimum
+    /// not exceeding the maximum.
     InvalidFragmentLen,
     /// Received part is inconsistent with previous ones.
     InconsistentPart,
@@ -101,6 +102,15 @@ pub enum Error {
     ExpectedItem,
     /// Invalid padding detected.
     InvalidPadding,
+    /// Receiving the part would exceed the configured decoder [`Limits`].
+    LimitExceeded,
+    /// The segments announced by the part are too short to hold the message
+    /// length announced by the part.
+    InvalidMessageLength,
+    /// Sequence numbers start at one.
+    InvalidSequence,
+    /// The transmission can't be [planned](plan) for the given parameters.
+    InvalidPlan,
 }
 
 impl core::fmt::Display for Error {
@@ -108,16 +118,90 @@ impl core::fmt::Display for Error {
         match self {
             Self::CborDecode(e) => write!(f, "{e}"),
             Self::CborEncode(e) => write!(f, "{e}"),
-            Self::EmptyMessage => write!(f, "expected non-empty message"),
+            Self::EmptyMessage => write!(f, "expected non-empty message for multi-part encoding"),
             Self::EmptyPart => write!(f, "expected non-empty part"),
             Self::InvalidFragmentLen => write!(f, "expected positive maximum fragment length"),
             Self::InconsistentPart => write!(f, "part is inconsistent with previous ones"),
             Self::ExpectedItem => write!(f, "expected item"),
             Self::InvalidPadding => write!(f, "invalid padding"),
+            Self::LimitExceeded => write!(f, "decoder limits exceeded"),
+            Self::InvalidMessageLength => {
+                write!(f, "message length exceeds the length of all segments")
+            }
+            Self::InvalidSequence => write!(f, "sequence numbers start at one"),
+            Self::InvalidPlan => write!(f, "can't plan a transmission for the parameters"),
         }
     }
 }
 
+impl Error {
+    /// Returns guidance on how to recover from the error, suitable to be shown
+    /// to users, or `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// decoder.receive(Encoder::new(b"data", 3).unwrap().next_part()).unwrap();
+    /// let other = Encoder::new(b"other", 3).unwrap().next_part();
+    /// assert_eq!(
+    ///     decoder.receive(other).unwrap_err().hint(),
+    ///     Some("the part belongs to another transfer, reset the decoder to start a new one")
+    /// );
+    /// ```
+    #[must_use]
+    pub const fn hint(&self) -> Option<&'static str> {
+        let hint = match self {
+            Self::CborDecode(_) => "the part may stem from an incompatible encoder",
+            Self::EmptyMessage => "transmit empty messages as a single-part UR",
+            Self::InvalidFragmentLen => "choose a positive maximum fragment length",
+            Self::InconsistentPart => {
+                "the part belongs to another transfer, reset the decoder to start a new one"
+            }
+            Self::LimitExceeded => {
+                "the transfer exceeds the decoder limits, raise them if it is expected to be \
+                 this large"
+            }
+            Self::EmptyPart | Self::InvalidMessageLength | Self::InvalidSequence => {
+                "the part is malformed, the sender may be faulty"
+            }
+            Self::InvalidPadding => {
+                "the received parts are corrupt, reset the decoder and scan again"
+            }
+            Self::InvalidPlan => "lower the target success probability or the loss rate",
+            Self::CborEncode(_) | Self::ExpectedItem => return None,
+        };
+        Some(hint)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Formats the CBOR errors without their details, which are only available
+/// through [`core::fmt`].
+#[cfg(feature = "defmt")]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let message = match self {
+            Self::CborDecode(_) => "CBOR decoding error",
+            Self::CborEncode(_) => "CBOR encoding error",
+            Self::EmptyMessage => "expected non-empty message for multi-part encoding",
+            Self::EmptyPart => "expected non-empty part",
+            Self::InvalidFragmentLen => "expected positive maximum fragment length",
+            Self::InconsistentPart => "part is inconsistent with previous ones",
+            Self::ExpectedItem => "expected item",
+            Self::InvalidPadding => "invalid padding",
+            Self::LimitExceeded => "decoder limits exceeded",
+            Self::InvalidMessageLength => "message length exceeds the length of all segments",
+            Self::InvalidSequence => "sequence numbers start at one",
+            Self::InvalidPlan => "can't plan a transmission for the parameters",
+        };
+        defmt::write!(f, "{=str}", message);
+    }
+}
+
 impl From<minicbor::decode::Error> for Error {
     fn from(e: minicbor::decode::Error) -> Self {
         Self::CborDecode(e)
@@ -130,17 +214,84 @@ impl From<minicbor::encode::Error<Infallible>> for Error {
     }
 }
 
+/// Determines which message segments are combined into a fountain part.
+///
+/// Encoder and decoder have to use the same scheduler in order to recombine
+/// the message. The [`XoshiroScheduler`] implements the selection mandated by
+/// the uniform resource specification and is used by default.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{Decoder, Encoder, PartScheduler};
+///
+/// /// Cycles through the message segments without ever mixing them.
+/// struct RoundRobin;
+///
+/// impl PartScheduler for RoundRobin {
+///     fn choose_fragments(&self, sequence: usize, fragment_count: usize, _: u32) -> Vec<usize> {
+///         vec![(sequence - 1) % fragment_count]
+///     }
+/// }
+///
+/// let mut encoder = Encoder::new(b"Ten chars!", 4)
+///     .unwrap()
+///     .with_scheduler(RoundRobin);
+/// let mut decoder = Decoder::default().with_scheduler(RoundRobin);
+/// encoder.next_part();
+/// for _ in 0..3 {
+///     decoder.receive(encoder.next_part()).unwrap();
+/// }
+/// assert_eq!(decoder.message().unwrap().as_deref(), Some(&b"Ten chars!"[..]));
+/// ```
+pub trait PartScheduler {
+    /// Returns the indexes of the message segments to be combined into the part
+    /// with the given sequence number, which starts at one. At least one index
+    /// has to be returned, and all indexes have to be distinct and smaller than
+    /// `fragment_count`.
+    fn choose_fragments(&self, sequence: usize, fragment_count: usize, checksum: u32)
+        -> Vec<usize>;
+}
+
+/// The [`PartScheduler`] of the uniform resource specification.
+///
+/// The first parts each consist of a single message segment, in order. For all
+/// subsequent parts, a `Xoshiro` RNG seeded with the sequence number and message
+/// checksum selects a degree biased towards few segments and then the segments
+/// to be combined.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct XoshiroScheduler;
+
+impl PartScheduler for XoshiroScheduler {
+    fn choose_fragments(
+        &self,
+        sequence: usize,
+        fragment_count: usize,
+        checksum: u32,
+    ) -> Vec<usize> {
+        choose_fragments(sequence, fragment_count, checksum)
+    }
+}
+
 /// An encoder capable of emitting fountain-encoded transmissions.
 ///
+/// The encoder owns a copy of the message by default. Large messages can be
+/// borrowed or shared instead with [`Encoder::from_source`], as the fragments
+/// are computed as slices of the message on demand.
+///
 /// # Examples
 ///
 /// See the [`crate::fountain`] module documentation for an example.
 #[derive(Debug)]
-pub struct Encoder {
-    parts: Vec<Vec<u8>>,
+pub struct Encoder<S = XoshiroScheduler, M = Vec<u8>> {
+    message: M,
     message_length: usize,
+    fragment_length: usize,
     checksum: u32,
     current_sequence: usize,
+    scheduler: S,
+    missing: FragmentIndexes,
+    pending: FragmentIndexes,
 }
 
 impl Encoder {
@@ -165,23 +316,150 @@ impl Encoder {
     /// # Errors
     ///
     /// If an empty message or a zero maximum fragment length is passed, an error
-    /// will be returned.
+    /// will be returned. Empty messages can only be encoded as a single-part UR,
+    /// see [`crate::ur::encode`].
     pub fn new(message: &[u8], max_fragment_length: usize) -> Result<Self, Error> {
+        Self::new_with_bounds(message, 1, max_fragment_length)
+    }
+
+    /// Constructs a new [`Encoder`], given a message and both a minimum and maximum
+    /// fragment length.
+    ///
+    /// This mirrors the encoders of the reference implementations, which choose
+    /// the smallest number of fragments respecting the maximum fragment length,
+    /// but never split the message into fragments shorter than the minimum
+    /// fragment length. The latter takes precedence if both can't be satisfied.
+    /// Messages shorter than the minimum fragment length form a single fragment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let encoder = Encoder::new_with_bounds(&[0; 25], 10, 15).unwrap();
+    /// assert_eq!(encoder.fragment_count(), 2);
+    /// // the minimum fragment length takes precedence
+    /// let mut encoder = Encoder::new_with_bounds(&[0; 25], 10, 12).unwrap();
+    /// assert_eq!(encoder.fragment_count(), 2);
+    /// assert_eq!(encoder.next_part().data().len(), 13);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an empty message, a zero minimum fragment length or a maximum fragment
+    /// length smaller than the minimum is passed, an error will be returned.
+    pub fn new_with_bounds(
+        message: &[u8],
+        min_fragment_length: usize,
+        max_fragment_length: usize,
+    ) -> Result<Self, Error> {
         if message.is_empty() {
             return Err(Error::EmptyMessage);
         }
-        if max_fragment_length == 0 {
+        Self::from_source_with_bounds(message.to_vec(), min_fragment_length, max_fragment_length)
+    }
+}
+
+impl<M: AsRef<[u8]>> Encoder<XoshiroScheduler, M> {
+    /// Constructs a new [`Encoder`] reading the fragments from the given
+    /// message source, without copying it.
+    ///
+    /// Any source exposing the message as a byte slice can be used, e.g. a
+    /// borrowed slice, an `Arc<[u8]>` shared with other encoders, or a wrapper
+    /// around a memory-mapped file. This allows serving large messages without
+    /// holding additional copies in memory.
+    ///
+    /// The source has to expose the same message for the lifetime of the
+    /// encoder, since its length and checksum are computed once. If it
+    /// changes, the emitted parts are inconsistent and can't be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use ur::fountain::Encoder;
+    /// let message: Arc<[u8]> = Arc::from(&b"Ten chars!"[..]);
+    /// let mut encoder = Encoder::from_source(message.clone(), 4).unwrap();
+    /// assert_eq!(encoder.next_part().data(), b"Ten ");
+    ///
+    /// let mut encoder = Encoder::from_source(&message[..], 4).unwrap();
+    /// assert_eq!(encoder.next_part().data(), b"Ten ");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an empty message or a zero maximum fragment length is passed, an error
+    /// will be returned.
+    pub fn from_source(message: M, max_fragment_length: usize) -> Result<Self, Error> {
+        Self::from_source_with_bounds(message, 1, max_fragment_length)
+    }
+
+    /// Constructs a new [`Encoder`] reading the fragments from the given
+    /// message source, respecting both a minimum and maximum fragment length.
+    /// See [`Encoder::from_source`] and [`Encoder::new_with_bounds`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let message = [0; 25];
+    /// let encoder = Encoder::from_source_with_bounds(&message, 10, 15).unwrap();
+    /// assert_eq!(encoder.fragment_count(), 2);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an empty message, a zero minimum fragment length or a maximum fragment
+    /// length smaller than the minimum is passed, an error will be returned.
+    pub fn from_source_with_bounds(
+        message: M,
+        min_fragment_length: usize,
+        max_fragment_length: usize,
+    ) -> Result<Self, Error> {
+        let bytes = message.as_ref();
+        if bytes.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+        if min_fragment_length == 0 || max_fragment_length < min_fragment_length {
             return Err(Error::InvalidFragmentLen);
         }
-        let fragment_length = fragment_length(message.len(), max_fragment_length);
-        let fragments = partition(message.to_vec(), fragment_length);
         Ok(Self {
-            parts: fragments,
-            message_length: message.len(),
-            checksum: crate::crc32().checksum(message),
+            fragment_length: bounded_fragment_length(
+                bytes.len(),
+                min_fragment_length,
+                max_fragment_length,
+            ),
+            checksum: crate::CRC32.checksum(bytes),
+            message_length: bytes.len(),
+            message,
             current_sequence: 0,
+            scheduler: XoshiroScheduler,
+            missing: FragmentIndexes::new(),
+            pending: FragmentIndexes::new(),
         })
     }
+}
+
+impl<S: PartScheduler, M: AsRef<[u8]>> Encoder<S, M> {
+    /// Replaces the [`PartScheduler`] selecting which segments are combined
+    /// into the emitted parts. Note that the receiving [`Decoder`] has to use the
+    /// same scheduler.
+    ///
+    /// # Examples
+    ///
+    /// See the [`PartScheduler`] documentation for an example.
+    #[must_use]
+    pub fn with_scheduler<T: PartScheduler>(self, scheduler: T) -> Encoder<T, M> {
+        Encoder {
+            message: self.message,
+            message_length: self.message_length,
+            fragment_length: self.fragment_length,
+            checksum: self.checksum,
+            current_sequence: self.current_sequence,
+            scheduler,
+            missing: self.missing,
+            pending: self.pending,
+        }
+    }
 
     /// Returns the current count of how many parts have been emitted.
     ///
@@ -199,697 +477,4392 @@ impl Encoder {
         self.current_sequence
     }
 
+    /// Sets the count of already emitted parts, such that the next emitted part
+    /// has sequence number `sequence + 1`. This allows a restarted sender to
+    /// continue emitting parts where it left off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// for _ in 0..10 {
+    ///     encoder.next_part();
+    /// }
+    /// let mut resumed = Encoder::new(b"data", 3).unwrap();
+    /// resumed.set_sequence(encoder.current_sequence());
+    /// assert_eq!(resumed.next_part(), encoder.next_part());
+    /// ```
+    pub fn set_sequence(&mut self, sequence: usize) {
+        self.current_sequence = sequence;
+    }
+
     /// Returns the next part to be emitted by the fountain encoder.
     /// After all parts of the original message have been emitted once,
     /// the fountain encoder will emit the result of xoring together the parts
     /// selected by the Xoshiro RNG (which could be a single part).
     ///
+    /// Sequence numbers are transmitted as 32-bit integers. After emitting the
+    /// part with sequence number `u32::MAX`, the encoder wraps around and
+    /// continues with sequence number one.
+    ///
     /// # Examples
     ///
     /// See the [`crate::fountain`] module documentation for an example.
     pub fn next_part(&mut self) -> Part {
-        self.current_sequence += 1;
-        let indexes = choose_fragments(self.current_sequence, self.parts.len(), self.checksum);
+        self.advance_prioritized();
+        self.part_at(self.current_sequence)
+    }
 
-        let mut mixed = alloc::vec![0; self.parts[0].len()];
-        for item in indexes {
-            xor(&mut mixed, &self.parts[item]);
-        }
+    /// Writes the next part to be emitted into an existing part, reusing the
+    /// allocation of its data. This avoids allocating a fresh buffer for every
+    /// part when parts are consumed one at a time, e.g. to render QR codes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// let mut part = encoder.next_part();
+    /// for sequence in 2..10 {
+    ///     encoder.next_part_into(&mut part);
+    ///     assert_eq!(part, encoder.part_at(sequence));
+    /// }
+    /// ```
+    pub fn next_part_into(&mut self, part: &mut Part) {
+        self.advance_prioritized();
+        self.part_at_into(self.current_sequence, part);
+    }
 
-        Part {
-            sequence: self.current_sequence,
-            sequence_count: self.parts.len(),
-            message_length: self.message_length,
-            checksum: self.checksum,
-            data: mixed,
-        }
+    /// Increments the sequence number, wrapping around at the 32-bit limit.
+    fn advance(&mut self) {
+        self.current_sequence = match u32::try_from(self.current_sequence) {
+            Ok(sequence) if sequence < u32::MAX => self.current_sequence + 1,
+            _ => 1,
+        };
     }
 
-    /// Returns the number of segments the original message has been split up into.
+    /// Focuses the upcoming parts on the message segments the receiver reports
+    /// missing, e.g. as the [`Ack::missing`] indexes of an acknowledgment sent
+    /// over a back-channel.
+    ///
+    /// The encoder then skips ahead to the parts which combine exactly one
+    /// missing segment with segments the receiver already has, such that each
+    /// received part recovers a missing segment. It cycles through the missing
+    /// segments until it is given a new set of indexes. Skipped parts count
+    /// towards the [`current_sequence`]. Passing no indexes, or only indexes
+    /// out of range, restores the regular order of parts. The encoder also
+    /// falls back to the regular order if none of the next thousand parts
+    /// recovers a pending missing segment, instead of searching again for
+    /// every part.
     ///
     /// # Examples
     ///
     /// ```
-    /// use ur::fountain::Encoder;
-    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
-    /// assert_eq!(encoder.fragment_count(), 2);
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let message = b"Ten chars!".repeat(10);
+    /// let mut encoder = Encoder::new(&message, 4).unwrap();
+    /// let mut decoder = Decoder::default();
+    /// // the receiver misses most of the first pass over the segments
+    /// for _ in 0..encoder.fragment_count() {
+    ///     let part = encoder.next_part();
+    ///     if part.sequence() % 4 == 0 {
+    ///         decoder.receive(part).unwrap();
+    ///     }
+    /// }
+    /// let ack = decoder.ack().unwrap();
+    /// encoder.prioritize(ack.missing().iter());
+    /// for _ in 0..ack.missing().len() {
+    ///     decoder.receive(encoder.next_part()).unwrap();
+    /// }
+    /// assert!(decoder.complete());
     /// ```
-    #[must_use]
-    pub fn fragment_count(&self) -> usize {
-        self.parts.len()
+    ///
+    /// [`current_sequence`]: Encoder::current_sequence
+    pub fn prioritize<I: IntoIterator<Item = usize>>(&mut self, missing_indexes: I) {
+        let fragment_count = self.fragment_count();
+        self.missing = missing_indexes
+            .into_iter()
+            .filter(|&idx| idx < fragment_count)
+            .collect();
+        self.pending.clone_from(&self.missing);
     }
 
-    /// Returns whether all original segments have been emitted at least once.
-    /// The fountain encoding is defined as doing this before combining segments
-    /// with each other. Thus, this is equivalent to checking whether
-    /// [`current_sequence`] >= [`fragment_count`].
+    /// Advances to the next part, skipping ahead to a part recovering one of
+    /// the pending missing segments if [`prioritize`] was called.
+    ///
+    /// [`prioritize`]: Encoder::prioritize
+    fn advance_prioritized(&mut self) {
+        self.advance();
+        if self.missing.is_empty() {
+            return;
+        }
+        let start = self.current_sequence;
+        for _ in 0..PRIORITY_SEARCH_LIMIT {
+            let hits = self
+                .indexes_for(self.current_sequence)
+                .intersection(&self.missing);
+            if matches!(hits.single(), Some(idx) if self.pending.remove(idx)) {
+                if self.pending.is_empty() {
+                    self.pending.clone_from(&self.missing);
+                }
+                return;
+            }
+            self.advance();
+        }
+        // emit the regular next part rather than stalling the transfer, and
+        // don't repeat the search until given new indexes
+        self.current_sequence = start;
+        self.missing = FragmentIndexes::new();
+        self.pending = FragmentIndexes::new();
+    }
+
+    /// Returns the part with the given sequence number, without affecting
+    /// the state of the encoder. This allows generating parts out of order,
+    /// for example to render multiple frames in parallel.
     ///
     /// # Examples
     ///
     /// ```
     /// use ur::fountain::Encoder;
-    /// let mut encoder = Encoder::new(&b"data".repeat(10), 3).unwrap();
-    /// while !encoder.complete() {
-    ///     assert!(encoder.current_sequence() < encoder.fragment_count());
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// let fifth = encoder.part_at(5);
+    /// assert_eq!(encoder.current_sequence(), 0);
+    /// for _ in 0..4 {
     ///     encoder.next_part();
     /// }
-    /// assert_eq!(encoder.current_sequence(), encoder.fragment_count());
+    /// assert_eq!(encoder.next_part(), fifth);
     /// ```
     ///
-    /// [`fragment_count`]: Encoder::fragment_count
-    /// [`current_sequence`]: Encoder::current_sequence
+    /// # Panics
+    ///
+    /// Sequence numbers start at one and are transmitted as 32-bit integers,
+    /// so this panics if `sequence` is zero or exceeds `u32::MAX`.
     #[must_use]
-    pub fn complete(&self) -> bool {
-        self.current_sequence >= self.parts.len()
+    pub fn part_at(&self, sequence: usize) -> Part {
+        let mut part = Part::new(0, 0, 0, 0, Vec::new());
+        self.part_at_into(sequence, &mut part);
+        part
     }
-}
-
-/// A decoder capable of receiving and recombining fountain-encoded transmissions.
-///
-/// # Examples
-///
-/// See the [`crate::fountain`] module documentation for an example.
-#[derive(Default)]
-pub struct Decoder {
-    decoded: alloc::collections::btree_map::BTreeMap<usize, Part>,
-    received: alloc::collections::btree_set::BTreeSet<Vec<usize>>,
-    buffer: alloc::collections::btree_map::BTreeMap<Vec<usize>, Part>,
-    queue: Vec<(usize, Part)>,
-    sequence_count: usize,
-    message_length: usize,
-    checksum: u32,
-    fragment_length: usize,
-}
 
-impl Decoder {
-    /// Receives a fountain-encoded part into the decoder.
+    /// Writes the part with the given sequence number into an existing part,
+    /// reusing the allocation of its data, without affecting the state of the
+    /// encoder.
     ///
     /// # Examples
     ///
-    /// See the [`crate::fountain`] module documentation for an example.
-    ///
-    /// # Errors
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// let mut part = encoder.part_at(1);
+    /// encoder.part_at_into(5, &mut part);
+    /// assert_eq!(part, encoder.part_at(5));
+    /// ```
     ///
-    /// If the part would fail [`validate`] because it is inconsistent
-    /// with previously received parts, an error will be returned.
+    /// # Panics
     ///
-    /// [`validate`]: Decoder::validate
-    pub fn receive(&mut self, part: Part) -> Result<bool, Error> {
-        if self.complete() {
-            return Ok(false);
-        }
-
-        // Only receive parts that will yield data.
-        if part.sequence_count == 0 || part.data.is_empty() || part.message_length == 0 {
-            return Err(Error::EmptyPart);
-        }
+    /// Sequence numbers start at one and are transmitted as 32-bit integers,
+    /// so this panics if `sequence` is zero or exceeds `u32::MAX`.
+    pub fn part_at_into(&self, sequence: usize, part: &mut Part) {
+        let indexes = self.indexes_for(sequence);
 
-        if self.received.is_empty() {
-            self.sequence_count = part.sequence_count;
-            self.message_length = part.message_length;
-            self.checksum = part.checksum;
-            self.fragment_length = part.data.len();
-        } else if !self.validate(&part) {
-            return Err(Error::InconsistentPart);
-        }
-        let indexes = part.indexes();
-        if self.received.contains(&indexes) {
-            return Ok(false);
-        }
-        self.received.insert(indexes);
-        if part.is_simple() {
-            self.process_simple(part)?;
-        } else {
-            self.process_complex(part)?;
+        part.data.clear();
+        part.data.resize(self.fragment_length, 0);
+        for item in indexes.iter() {
+            let fragment = self.fragment(item);
+            // the zero padding of the last fragment doesn't affect the xor
+            xor_into(&mut part.data[..fragment.len()], fragment);
         }
-        Ok(true)
-    }
 
-    fn process_simple(&mut self, part: Part) -> Result<(), Error> {
-        let index = *part.indexes().first().ok_or(Error::ExpectedItem)?;
-        self.decoded.insert(index, part.clone());
-        self.queue.push((index, part));
-        self.process_queue()?;
-        Ok(())
+        part.sequence = sequence;
+        part.sequence_count = self.fragment_count();
+        part.message_length = self.message_length;
+        part.checksum = self.checksum;
     }
 
-    fn process_queue(&mut self) -> Result<(), Error> {
-        while !self.queue.is_empty() {
-            let (index, simple) = self.queue.pop().ok_or(Error::ExpectedItem)?;
-            let to_process: Vec<Vec<usize>> = self
-                .buffer
-                .keys()
-                .filter(|&idxs| idxs.iter().any(|&idx| idx == index))
-                .cloned()
-                .collect();
-            for indexes in to_process {
-                let mut part = self.buffer.remove(&indexes).ok_or(Error::ExpectedItem)?;
-                let mut new_indexes = indexes.clone();
-                let to_remove = indexes
-                    .iter()
-                    .position(|&x| x == index)
-                    .ok_or(Error::ExpectedItem)?;
-                new_indexes.remove(to_remove);
-                xor(&mut part.data, &simple.data);
-                if new_indexes.len() == 1 {
-                    self.decoded
-                        .insert(*new_indexes.first().unwrap(), part.clone());
-                    self.queue.push((*new_indexes.first().unwrap(), part));
-                } else {
-                    self.buffer.insert(new_indexes, part);
-                }
-            }
-        }
-        Ok(())
+    /// Returns the indexes of the message segments which are combined into the
+    /// part with the given sequence number, without computing the part itself.
+    ///
+    /// This allows predicting which segments future parts will carry, for
+    /// instance to analyse coverage or to decide when to stop transmitting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// assert_eq!(encoder.indexes_for(2).to_vec(), vec![1]);
+    /// assert_eq!(encoder.indexes_for(9), encoder.part_at(9).indexes());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Sequence numbers start at one and are transmitted as 32-bit integers,
+    /// so this panics if `sequence` is zero or exceeds `u32::MAX`.
+    #[must_use]
+    pub fn indexes_for(&self, sequence: usize) -> FragmentIndexes {
+        assert!(sequence > 0, "sequence numbers start at one");
+        assert!(
+            u32::try_from(sequence).is_ok(),
+            "sequence numbers are limited to 32 bits"
+        );
+        self.scheduler
+            .choose_fragments(sequence, self.fragment_count(), self.checksum)
+            .into_iter()
+            .collect()
     }
 
-    fn process_complex(&mut self, mut part: Part) -> Result<(), Error> {
-        let mut indexes = part.indexes();
-        let to_remove: Vec<usize> = indexes
-            .clone()
-            .into_iter()
-            .filter(|idx| self.decoded.keys().any(|k| k == idx))
-            .collect();
-        if indexes.len() == to_remove.len() {
-            return Ok(());
-        }
-        for remove in to_remove {
-            let idx_to_remove = indexes
-                .iter()
-                .position(|&x| x == remove)
-                .ok_or(Error::ExpectedItem)?;
-            indexes.remove(idx_to_remove);
-            xor(
-                &mut part.data,
-                &self.decoded.get(&remove).ok_or(Error::ExpectedItem)?.data,
-            );
-        }
-        if indexes.len() == 1 {
-            self.decoded.insert(*indexes.first().unwrap(), part.clone());
-            self.queue.push((*indexes.first().unwrap(), part));
-        } else {
-            self.buffer.insert(indexes, part);
-        }
-        Ok(())
+    /// Returns the message segment at the given index, without the zero
+    /// padding of the last segment.
+    fn fragment(&self, index: usize) -> &[u8] {
+        // the source may misbehave and expose a shorter message than when the
+        // encoder was constructed
+        let message = self.message.as_ref();
+        let start = (index * self.fragment_length).min(message.len());
+        let end = (start + self.fragment_length).min(message.len());
+        &message[start..end]
     }
 
-    /// Returns whether the decoder is complete and hence the message available.
+    /// Returns the number of segments the original message has been split up into.
     ///
     /// # Examples
     ///
-    /// See the [`crate::fountain`] module documentation for an example.
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(encoder.fragment_count(), 2);
+    /// ```
     #[must_use]
-    pub fn complete(&self) -> bool {
-        self.message_length != 0 && self.decoded.len() == self.sequence_count
+    pub const fn fragment_count(&self) -> usize {
+        div_ceil(self.message_length, self.fragment_length)
     }
 
-    /// Checks whether a [`Part`] is receivable by the decoder.
-    /// This can fail if other parts were previously received whose
-    /// metadata (such as number of segments) is inconsistent with the
-    /// present [`Part`]. Note that a fresh decoder will always return
-    /// false here.
+    /// Returns the length of the message segments, which is the length of the
+    /// data of every emitted part.
     ///
     /// # Examples
     ///
     /// ```
-    /// use ur::fountain::{Decoder, Encoder};
-    /// let mut decoder = Decoder::default();
-    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
-    /// let part = encoder.next_part();
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// assert_eq!(encoder.fragment_length(), 4);
+    /// assert_eq!(encoder.next_part().data().len(), 4);
+    /// // the last of the three segments holds two bytes of the message
+    /// assert_eq!(encoder.padding_length(), 2);
+    /// ```
+    #[must_use]
+    pub const fn fragment_length(&self) -> usize {
+        self.fragment_length
+    }
+
+    /// Returns the number of zero bytes padding the last message segment to the
+    /// [`fragment_length`], as the message length needn't be a multiple of it.
     ///
-    /// // a fresh decoder always returns false
-    /// assert!(!decoder.validate(&part));
+    /// # Examples
     ///
-    /// // parts with the same metadata validate successfully
-    /// decoder.receive(part).unwrap();
-    /// let part = encoder.next_part();
-    /// assert!(decoder.validate(&part));
+    /// See [`Encoder::fragment_length`] for an example.
     ///
-    /// // parts with the different metadata don't validate
-    /// let mut encoder = Encoder::new(b"more data", 3).unwrap();
-    /// let part = encoder.next_part();
-    /// assert!(!decoder.validate(&part));
-    /// ```
+    /// [`fragment_length`]: Encoder::fragment_length
     #[must_use]
-    pub fn validate(&self, part: &Part) -> bool {
-        if self.received.is_empty() {
-            return false;
-        }
-
-        if part.sequence_count != self.sequence_count
-            || part.message_length != self.message_length
-            || part.checksum != self.checksum
-            || part.data.len() != self.fragment_length
-        {
-            return false;
-        }
+    pub const fn padding_length(&self) -> usize {
+        self.fragment_count() * self.fragment_length - self.message_length
+    }
 
-        true
+    /// Returns the CRC32 checksum of the message, which is carried by every
+    /// emitted part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(encoder.checksum(), encoder.next_part().checksum());
+    /// ```
+    #[must_use]
+    pub const fn checksum(&self) -> u32 {
+        self.checksum
     }
 
-    /// If [`complete`], returns the decoded message, `None` otherwise.
+    /// Returns whether all original segments have been emitted at least once.
+    /// The fountain encoding is defined as doing this before combining segments
+    /// with each other. Thus, this is equivalent to checking whether
+    /// [`current_sequence`] >= [`fragment_count`].
     ///
-    /// # Errors
+    /// # Examples
     ///
-    /// If an inconsistent internal state is detected, an error will be returned.
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(&b"data".repeat(10), 3).unwrap();
+    /// while !encoder.complete() {
+    ///     assert!(encoder.current_sequence() < encoder.fragment_count());
+    ///     encoder.next_part();
+    /// }
+    /// assert_eq!(encoder.current_sequence(), encoder.fragment_count());
+    /// ```
+    ///
+    /// [`fragment_count`]: Encoder::fragment_count
+    /// [`current_sequence`]: Encoder::current_sequence
+    #[must_use]
+    pub const fn complete(&self) -> bool {
+        self.current_sequence >= self.fragment_count()
+    }
+
+    /// Returns whether `redundancy` times as many parts as there are segments
+    /// have been emitted, e.g. `1.5` for 50% more parts than segments.
+    ///
+    /// Senders without a back-channel can use this as a stopping criterion,
+    /// as decoders typically need slightly more parts than there are segments
+    /// to recombine the message, in particular over lossy channels. See [`plan`]
+    /// for estimating the number of parts a given channel requires. With a
+    /// redundancy of `1.0`, this is equivalent to [`complete`]. Redundancies
+    /// below `1.0` and NaN are treated as `1.0`, so that every segment is
+    /// emitted at least once.
     ///
     /// # Examples
     ///
-    /// See the [`crate::fountain`] module documentation for an example.
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(&b"data".repeat(10), 4).unwrap();
+    /// while !encoder.complete_with_redundancy(1.5) {
+    ///     encoder.next_part();
+    /// }
+    /// assert_eq!(encoder.fragment_count(), 10);
+    /// assert_eq!(encoder.current_sequence(), 15);
+    /// assert!(encoder.complete_with_redundancy(1.0));
+    /// ```
     ///
-    /// [`complete`]: Decoder::complete
-    pub fn message(&self) -> Result<Option<Vec<u8>>, Error> {
-        if !self.complete() {
-            return Ok(None);
-        }
-        let combined = (0..self.sequence_count)
-            .map(|idx| self.decoded.get(&idx).ok_or(Error::ExpectedItem))
-            .collect::<Result<Vec<&Part>, Error>>()?
-            .iter()
-            .fold(alloc::vec![], |a, b| [a, b.data.clone()].concat());
-        if !combined
-            .get(self.message_length..)
-            .ok_or(Error::ExpectedItem)?
-            .iter()
-            .all(|&x| x == 0)
-        {
-            return Err(Error::InvalidPadding);
+    /// [`complete`]: Encoder::complete
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn complete_with_redundancy(&self, redundancy: f64) -> bool {
+        // `max` also replaces NaN
+        self.current_sequence as f64 >= redundancy.max(1.0) * self.fragment_count() as f64
+    }
+}
+
+/// Upper bounds on the resources a [`Decoder`] commits to a single transfer.
+///
+/// Parts are self-describing, so without limits a malicious sender could make
+/// a decoder buffer arbitrary amounts of data. By default, no limits apply.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{Decoder, Encoder, Error, Limits};
+/// let mut decoder = Decoder::default().with_limits(Limits {
+///     max_fragment_count: 2,
+///     ..Limits::default()
+/// });
+/// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+/// assert!(matches!(
+///     decoder.receive(encoder.next_part()),
+///     Err(Error::LimitExceeded)
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum number of bytes held in decoded and buffered parts. Messages
+    /// whose segments alone exceed it are rejected with the first part, before
+    /// the decoder allocates a slot per segment.
+    pub max_buffered_bytes: usize,
+    /// The maximum number of segments a message may be split into.
+    pub max_fragment_count: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_buffered_bytes: usize::MAX,
+            max_fragment_count: usize::MAX,
         }
-        Ok(Some(
-            combined
-                .get(..self.message_length)
-                .ok_or(Error::ExpectedItem)?
-                .to_vec(),
-        ))
     }
 }
 
-/// A part emitted by a fountain [`Encoder`].
+/// Statistics on the parts received by a [`Decoder`], to monitor the channel
+/// quality and the decoding effort.
 ///
-/// Most commonly, this is obtained by calling [`next_part`] on the encoder.
+/// Only the number of received parts is retained when the decoder state is
+/// persisted, the remaining counters restart at zero.
 ///
-/// [`next_part`]: Encoder::next_part
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Part {
-    sequence: usize,
-    sequence_count: usize,
-    message_length: usize,
+/// With the `metrics` feature, decoders also report their activity through the
+/// [`metrics`](https://docs.rs/metrics) facade, aggregated over all decoders:
+///  - `ur_parts_received_total`: valid parts received, including duplicates
+///  - `ur_duplicate_parts_total`: parts ignored as duplicates
+///  - `ur_bytes_processed_total`: data bytes of all valid received parts
+///  - `ur_bytes_decoded_total`: message bytes of all completed transfers
+///  - `ur_transfers_completed_total`: completed transfers
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{Decoder, Encoder};
+/// let mut decoder = Decoder::default();
+/// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+/// let part = encoder.next_part();
+/// decoder.receive(part.clone()).unwrap();
+/// decoder.receive(part).unwrap();
+/// let stats = decoder.stats();
+/// assert_eq!(stats.parts_received, 2);
+/// assert_eq!(stats.duplicate_parts, 1);
+/// assert_eq!(stats.bytes_processed, 8);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of valid parts received, including duplicates.
+    pub parts_received: usize,
+    /// The number of received parts ignored as duplicates.
+    pub duplicate_parts: usize,
+    /// The number of mixed parts currently buffered until they can be reduced.
+    pub buffered_parts: usize,
+    /// The number of part combinations performed to reduce mixed parts.
+    pub xor_operations: usize,
+    /// The number of data bytes of all valid received parts.
+    pub bytes_processed: usize,
+}
+
+/// The progress of a [`Decoder`], ready to be displayed to users, as returned
+/// by [`Decoder::progress`].
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{Decoder, Encoder};
+/// let mut decoder = Decoder::default();
+/// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+/// assert_eq!(decoder.progress().label, "0/? fragments");
+/// decoder.receive(encoder.next_part()).unwrap();
+/// let progress = decoder.progress();
+/// assert_eq!(progress.decoded_fragments, 1);
+/// assert_eq!(progress.total_fragments, Some(3));
+/// assert_eq!(progress.label, "1/3 fragments");
+/// assert_eq!(progress.to_string(), progress.label);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Progress {
+    /// The number of message segments recovered so far.
+    pub decoded_fragments: usize,
+    /// The number of message segments, or `None` if no part has been received yet.
+    pub total_fragments: Option<usize>,
+    /// The estimated progress between `0.0` and `1.0`, see
+    /// [`Decoder::estimated_percent_complete`].
+    pub estimated_percent_complete: f64,
+    /// A label like `17/34 fragments`, with a `?` as long as the number of
+    /// segments is unknown.
+    pub label: alloc::string::String,
+}
+
+impl core::fmt::Display for Progress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// An acknowledgment of the message segments recovered by a [`Decoder`], as
+/// returned by [`Decoder::ack`].
+///
+/// Acknowledgments are meant to be sent back to the encoding side over a
+/// back-channel, e.g. as a single QR code, such that it can focus on the
+/// missing segments. Their CBOR encoding is an array of the message checksum,
+/// the number of segments and a bitmap of the recovered segments, which takes
+/// a single bit per segment.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{Ack, Decoder, Encoder};
+/// let mut decoder = Decoder::default();
+/// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+/// assert_eq!(decoder.ack(), None);
+/// encoder.next_part();
+/// decoder.receive(encoder.next_part()).unwrap();
+/// let ack = decoder.ack().unwrap();
+/// assert_eq!(ack.checksum(), encoder.checksum());
+/// assert_eq!(ack.fragment_count(), 3);
+/// assert_eq!(ack.received().to_vec(), vec![1]);
+/// assert_eq!(ack.missing().to_vec(), vec![0, 2]);
+///
+/// let cbor = minicbor::to_vec(&ack).unwrap();
+/// assert_eq!(cbor.len(), 9);
+/// assert_eq!(minicbor::decode::<Ack>(&cbor).unwrap(), ack);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ack {
     checksum: u32,
-    data: Vec<u8>,
+    fragment_count: usize,
+    received: FragmentIndexes,
 }
 
-impl<C> minicbor::Encode<C> for Part {
+impl Ack {
+    /// The UR type of acknowledgments, see [`crate::ur::Decoder::ack`].
+    pub const UR_TYPE: &'static str = "fountain-ack";
+
+    /// Returns the CRC32 checksum of the acknowledged message.
+    ///
+    /// # Examples
+    ///
+    /// See the [`Ack`] documentation for an example.
+    #[must_use]
+    pub const fn checksum(&self) -> u32 {
+        self.checksum
+    }
+
+    /// Returns the number of segments the acknowledged message has been split
+    /// into.
+    ///
+    /// # Examples
+    ///
+    /// See the [`Ack`] documentation for an example.
+    #[must_use]
+    pub const fn fragment_count(&self) -> usize {
+        self.fragment_count
+    }
+
+    /// Returns the indexes of the recovered message segments.
+    ///
+    /// # Examples
+    ///
+    /// See the [`Ack`] documentation for an example.
+    #[must_use]
+    pub const fn received(&self) -> &FragmentIndexes {
+        &self.received
+    }
+
+    /// Returns the indexes of the message segments which are still
+    /// outstanding.
+    ///
+    /// # Examples
+    ///
+    /// See the [`Ack`] documentation for an example.
+    #[must_use]
+    pub fn missing(&self) -> FragmentIndexes {
+        (0..self.fragment_count)
+            .filter(|&idx| !self.received.contains(idx))
+            .collect()
+    }
+}
+
+impl<C> minicbor::Encode<C> for Ack {
     fn encode<W: minicbor::encode::Write>(
         &self,
         e: &mut minicbor::Encoder<W>,
         _ctx: &mut C,
     ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        let mut bitmap = alloc::vec![0; div_ceil(self.fragment_count, 8)];
+        for idx in self.received.iter() {
+            bitmap[idx / 8] |= 1 << (idx % 8);
+        }
         #[allow(clippy::cast_possible_truncation)]
-        e.array(5)?
-            .u32(self.sequence as u32)?
-            .u32(self.sequence_count as u32)?
-            .u32(self.message_length as u32)?
+        e.array(3)?
             .u32(self.checksum)?
-            .bytes(&self.data)?;
+            .u32(self.fragment_count as u32)?
+            .bytes(&bitmap)?;
 
         Ok(())
     }
 }
 
-impl<'b, C> minicbor::Decode<'b, C> for Part {
+impl<'b, C> minicbor::Decode<'b, C> for Ack {
     fn decode(
         d: &mut minicbor::Decoder<'b>,
         _ctx: &mut C,
     ) -> Result<Self, minicbor::decode::Error> {
-        if !matches!(d.array()?, Some(5)) {
+        if d.array()? != Some(3) {
             return Err(minicbor::decode::Error::message(
                 "invalid CBOR array length",
             ));
         }
+        let checksum = d.u32()?;
+        let position = d.position();
+        let fragment_count = d.u32()? as usize;
+        if fragment_count == 0 {
+            return Err(
+                minicbor::decode::Error::message("expected at least one segment").at(position),
+            );
+        }
+        let position = d.position();
+        let bitmap = d.bytes()?;
+        let received: FragmentIndexes = (0..bitmap.len() * 8)
+            .filter(|idx| bitmap[idx / 8] & (1 << (idx % 8)) != 0)
+            .collect();
+        if bitmap.len() != div_ceil(fragment_count, 8)
+            || received.iter().any(|idx| idx >= fragment_count)
+        {
+            return Err(minicbor::decode::Error::message(
+                "bitmap doesn't match the number of segments",
+            )
+            .at(position));
+        }
 
         Ok(Self {
-            sequence: d.u32()? as usize,
-            sequence_count: d.u32()? as usize,
-            message_length: d.u32()? as usize,
-            checksum: d.u32()?,
-            data: d.bytes()?.to_vec(),
+            checksum,
+            fragment_count,
+            received,
         })
     }
 }
 
-impl Part {
-    pub(crate) fn from_cbor(cbor: &[u8]) -> Result<Self, Error> {
-        minicbor::decode(cbor).map_err(Error::from)
+/// Stores the message segments recovered by a [`Decoder`].
+///
+/// The [`HeapStorage`] is used by default. Other implementations can back the
+/// segments by e.g. flash pages or bounded memory pools on constrained devices.
+/// Mixed parts which can't be reduced yet are still buffered on the heap, their
+/// number is bounded by [`Limits::max_buffered_bytes`].
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{Decoder, Encoder, Error, FragmentStorage};
+///
+/// /// Stores up to four segments in place.
+/// #[derive(Default)]
+/// struct Slots([Option<Vec<u8>>; 4]);
+///
+/// impl FragmentStorage for Slots {
+///     fn reset(&mut self, fragment_count: usize, _: usize) -> Result<(), Error> {
+///         if fragment_count > self.0.len() {
+///             return Err(Error::LimitExceeded);
+///         }
+///         self.0 = Default::default();
+///         Ok(())
+///     }
+///
+///     fn get(&self, index: usize) -> Option<&[u8]> {
+///         self.0.get(index)?.as_deref()
+///     }
+///
+///     fn insert(&mut self, index: usize, data: Vec<u8>) -> bool {
+///         self.0[index].replace(data).is_none()
+///     }
+/// }
+///
+/// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+/// let mut decoder = Decoder::default().with_storage(Slots::default());
+/// while !decoder.complete() {
+///     decoder.receive(encoder.next_part()).unwrap();
+/// }
+/// assert_eq!(decoder.message().unwrap().as_deref(), Some(&b"Ten chars!"[..]));
+///
+/// let mut encoder = Encoder::new(b"Ten chars!", 2).unwrap();
+/// assert!(matches!(
+///     decoder.receive(encoder.next_part()),
+///     Ok(false)
+/// ));
+/// decoder.reset();
+/// assert!(matches!(
+///     decoder.receive(encoder.next_part()),
+///     Err(Error::LimitExceeded)
+/// ));
+/// ```
+pub trait FragmentStorage {
+    /// Prepares the storage for a message of `fragment_count` segments of
+    /// `fragment_length` bytes each, discarding all stored segments.
+    ///
+    /// # Errors
+    ///
+    /// If the storage can't hold the segments, [`Error::LimitExceeded`] should
+    /// be returned. Storage for all segments is committed here, such that
+    /// inserting segments can't fail.
+    fn reset(&mut self, fragment_count: usize, fragment_length: usize) -> Result<(), Error>;
+
+    /// Returns the segment with the given index, if it has been stored.
+    fn get(&self, index: usize) -> Option<&[u8]>;
+
+    /// Stores the segment with the given index, which is smaller than the
+    /// `fragment_count` of the last [`reset`]. Returns whether the segment
+    /// hasn't been stored before.
+    ///
+    /// [`reset`]: FragmentStorage::reset
+    fn insert(&mut self, index: usize, data: Vec<u8>) -> bool;
+}
+
+/// The default [`FragmentStorage`], holding each segment in its own heap
+/// allocation.
+#[derive(Debug, Default, Clone)]
+pub struct HeapStorage(Vec<Option<Vec<u8>>>);
+
+impl FragmentStorage for HeapStorage {
+    fn reset(&mut self, fragment_count: usize, _fragment_length: usize) -> Result<(), Error> {
+        self.0.clear();
+        self.0
+            .try_reserve_exact(fragment_count)
+            .map_err(|_| Error::LimitExceeded)?;
+        self.0.resize(fragment_count, None);
+        Ok(())
+    }
+
+    fn get(&self, index: usize) -> Option<&[u8]> {
+        self.0.get(index)?.as_deref()
+    }
+
+    fn insert(&mut self, index: usize, data: Vec<u8>) -> bool {
+        // indexes are within bounds unless a scheduler violates its contract
+        let Some(slot) = self.0.get_mut(index) else {
+            return false;
+        };
+        slot.replace(data).is_none()
+    }
+}
+
+/// A decoder capable of receiving and recombining fountain-encoded transmissions.
+///
+/// # Examples
+///
+/// See the [`crate::fountain`] module documentation for an example.
+pub struct Decoder<S = XoshiroScheduler, T = HeapStorage> {
+    storage: T,
+    decoded_count: usize,
+    received: alloc::collections::btree_set::BTreeSet<FragmentIndexes>,
+    buffer: alloc::collections::btree_map::BTreeMap<usize, (FragmentIndexes, Vec<u8>)>,
+    pending: alloc::collections::btree_map::BTreeMap<usize, Vec<usize>>,
+    queue: Vec<usize>,
+    sequence_count: usize,
+    message_length: usize,
+    checksum: u32,
+    fragment_length: usize,
+    processed_parts_count: usize,
+    stats: Stats,
+    limits: Limits,
+    expected_checksum: Option<u32>,
+    scheduler: S,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self {
+            storage: HeapStorage::default(),
+            decoded_count: 0,
+            received: alloc::collections::btree_set::BTreeSet::default(),
+            buffer: alloc::collections::btree_map::BTreeMap::default(),
+            pending: alloc::collections::btree_map::BTreeMap::default(),
+            queue: Vec::default(),
+            sequence_count: 0,
+            message_length: 0,
+            checksum: 0,
+            fragment_length: 0,
+            processed_parts_count: 0,
+            stats: Stats::default(),
+            limits: Limits::default(),
+            expected_checksum: None,
+            scheduler: XoshiroScheduler,
+        }
     }
+}
 
-    /// Returns the indexes of the message segments that were combined into this part.
+impl<S: PartScheduler, T: FragmentStorage> Decoder<S, T> {
+    /// Replaces the [`PartScheduler`] used to determine which segments were
+    /// combined into the received parts. It has to match the scheduler of the
+    /// sending [`Encoder`].
     ///
     /// # Examples
     ///
-    /// ```
-    /// use ur::fountain::Encoder;
-    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
-    /// assert_eq!(encoder.next_part().indexes(), vec![0]);
-    /// assert_eq!(encoder.next_part().indexes(), vec![1]);
-    /// ```
+    /// See the [`PartScheduler`] documentation for an example.
     #[must_use]
-    pub fn indexes(&self) -> Vec<usize> {
-        choose_fragments(self.sequence, self.sequence_count, self.checksum)
+    pub fn with_scheduler<U: PartScheduler>(self, scheduler: U) -> Decoder<U, T> {
+        Decoder {
+            storage: self.storage,
+            decoded_count: self.decoded_count,
+            received: self.received,
+            buffer: self.buffer,
+            pending: self.pending,
+            queue: self.queue,
+            sequence_count: self.sequence_count,
+            message_length: self.message_length,
+            checksum: self.checksum,
+            fragment_length: self.fragment_length,
+            processed_parts_count: self.processed_parts_count,
+            stats: self.stats,
+            limits: self.limits,
+            expected_checksum: self.expected_checksum,
+            scheduler,
+        }
     }
 
-    /// Indicates whether this part is an original segment of the message, or was obtained by
-    /// combining multiple segments via xor.
+    /// Replaces the [`FragmentStorage`] holding the recovered segments. This
+    /// discards the current transfer, as if [`reset`] was called.
     ///
     /// # Examples
     ///
-    /// The encoding setup follows the `fountain` module example.
+    /// See the [`FragmentStorage`] documentation for an example.
     ///
-    /// ```
-    /// use ur::fountain::Encoder;
-    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
-    /// // The encoder always emits the simple parts covering the message first
-    /// assert!(encoder.next_part().is_simple());
-    /// assert!(encoder.next_part().is_simple());
-    /// assert!(encoder.next_part().is_simple());
-    /// // The encoder then emits segment 3 again
-    /// assert!(encoder.next_part().is_simple());
-    /// // The encoder then emits all 3 segments combined
-    /// assert!(!encoder.next_part().is_simple());
-    /// ```
+    /// [`reset`]: Decoder::reset
     #[must_use]
-    pub fn is_simple(&self) -> bool {
-        self.indexes().len() == 1
-    }
-
-    pub(crate) fn cbor(&self) -> Result<Vec<u8>, Error> {
-        minicbor::to_vec(self).map_err(Error::from)
+    pub fn with_storage<U: FragmentStorage>(self, storage: U) -> Decoder<S, U> {
+        let mut decoder = Decoder {
+            storage,
+            decoded_count: self.decoded_count,
+            received: self.received,
+            buffer: self.buffer,
+            pending: self.pending,
+            queue: self.queue,
+            sequence_count: self.sequence_count,
+            message_length: self.message_length,
+            checksum: self.checksum,
+            fragment_length: self.fragment_length,
+            processed_parts_count: self.processed_parts_count,
+            stats: self.stats,
+            limits: self.limits,
+            expected_checksum: self.expected_checksum,
+            scheduler: self.scheduler,
+        };
+        decoder.reset();
+        decoder
     }
 
+    /// Sets the [`Limits`] on the resources committed to a transfer. Parts
+    /// exceeding them are rejected with [`Error::LimitExceeded`].
+    ///
+    /// # Examples
+    ///
+    /// See the [`Limits`] documentation for an example.
     #[must_use]
-    pub(crate) fn sequence_id(&self) -> alloc::string::String {
-        alloc::format!("{}-{}", self.sequence, self.sequence_count)
+    pub const fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
     }
 
-    /// Returns a slice view onto the underlying data.
+    /// Only accepts parts of the transfer with the given message checksum, and
+    /// silently ignores parts of any other transfer.
     ///
-    /// Note that for non-simple parts this will be the result of
-    /// xoring multiple message segments together.
+    /// This is useful if the sender communicates the checksum out of band before
+    /// starting the transfer, e.g. on screen, so that a receiver doesn't pick up
+    /// parts of another animated QR code in view.
     ///
     /// # Examples
     ///
     /// ```
-    /// let data = String::from("Ten chars!");
-    /// let mut encoder = ur::fountain::Encoder::new(data.as_bytes(), 4).unwrap();
-    /// assert_eq!(encoder.next_part().data(), "Ten ".as_bytes());
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut other = Encoder::new(b"other data", 3).unwrap();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// let checksum = encoder.part_at(1).checksum();
+    /// let mut decoder = Decoder::default().with_expected_checksum(checksum);
+    /// assert!(!decoder.receive(other.next_part()).unwrap());
+    /// assert!(decoder.receive(encoder.next_part()).unwrap());
+    /// assert!(decoder.receive(encoder.next_part()).unwrap());
+    /// assert_eq!(decoder.message().unwrap().as_deref(), Some(&b"data"[..]));
     /// ```
     #[must_use]
-    pub fn data(&self) -> &[u8] {
-        &self.data
-    }
-}
-
-/// Calculates the quotient of `a` and `b`, rounding the results towards
-/// positive infinity.
-///
-/// Note: there's an implementation on the `usize` type of this function,
-/// but it's not stable yet.
-#[must_use]
-const fn div_ceil(a: usize, b: usize) -> usize {
-    let d = a / b;
-    let r = a % b;
-    if r > 0 {
-        d + 1
-    } else {
-        d
+    pub const fn with_expected_checksum(mut self, checksum: u32) -> Self {
+        self.expected_checksum = Some(checksum);
+        self
     }
-}
 
-#[must_use]
-pub(crate) const fn fragment_length(data_length: usize, max_fragment_length: usize) -> usize {
-    let fragment_count = div_ceil(data_length, max_fragment_length);
-    div_ceil(data_length, fragment_count)
+    /// Clears all state of the current transfer, such that the decoder can be
+    /// reused for a new one. The [`Limits`], expected checksum and
+    /// [`PartScheduler`] are retained, as are allocated buffers where possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// decoder.reset();
+    /// assert_eq!(decoder.expected_fragment_count(), None);
+    ///
+    /// let mut encoder = Encoder::new(b"more data", 3).unwrap();
+    /// while !decoder.complete() {
+    ///     decoder.receive(encoder.next_part()).unwrap();
+    /// }
+    /// assert_eq!(decoder.message().unwrap().as_deref(), Some(&b"more data"[..]));
+    /// ```
+    pub fn reset(&mut self) {
+        // discards the segments, but can't fail without committing storage
+        let _ = self.storage.reset(0, 0);
+        self.decoded_count = 0;
+        self.received.clear();
+        self.buffer.clear();
+        self.pending.clear();
+        self.queue.clear();
+        self.sequence_count = 0;
+        self.message_length = 0;
+        self.checksum = 0;
+        self.fragment_length = 0;
+        self.processed_parts_count = 0;
+        self.stats = Stats::default();
+    }
+
+    /// Receives a fountain-encoded part into the decoder.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::fountain`] module documentation for an example.
+    ///
+    /// # Errors
+    ///
+    /// If the part would fail [`validate`] because it is inconsistent
+    /// with previously received parts, an error will be returned.
+    ///
+    /// [`validate`]: Decoder::validate
+    pub fn receive(&mut self, part: Part) -> Result<bool, Error> {
+        let Some(indexes) = self.accept(&part)? else {
+            return Ok(false);
+        };
+        self.process(&indexes, part)?;
+        Ok(true)
+    }
+
+    /// Receives a borrowed fountain-encoded part into the decoder.
+    ///
+    /// This behaves like [`receive`], but only copies the part data if the part
+    /// is not a duplicate of a previously received one. This is useful for callers
+    /// which retain their own archive of received parts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// let parts = vec![encoder.next_part(), encoder.next_part()];
+    /// for part in &parts {
+    ///     assert!(decoder.receive_ref(part).unwrap());
+    /// }
+    /// assert_eq!(decoder.message().unwrap().as_deref(), Some(&b"data"[..]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the part would fail [`validate`] because it is inconsistent
+    /// with previously received parts, an error will be returned.
+    ///
+    /// [`receive`]: Decoder::receive
+    /// [`validate`]: Decoder::validate
+    pub fn receive_ref(&mut self, part: &Part) -> Result<bool, Error> {
+        let Some(indexes) = self.accept(part)? else {
+            return Ok(false);
+        };
+        self.process(&indexes, part.clone())?;
+        Ok(true)
+    }
+
+    /// Receives a fountain-encoded part into the decoder without tracking
+    /// duplicates.
+    ///
+    /// This is a fast path for callers which already de-duplicate parts
+    /// upstream, e.g. a relay server. The part is checked against the transfer
+    /// metadata, the expected checksum and the [`Limits`] as by [`receive`],
+    /// but the set of received parts isn't maintained, so duplicates are
+    /// processed again rather than counted in the [`Stats`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// while !decoder.complete() {
+    ///     decoder.receive_unchecked(encoder.next_part()).unwrap();
+    /// }
+    /// assert_eq!(decoder.message().unwrap().as_deref(), Some(&b"Ten chars!"[..]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the part is invalid or inconsistent with the previously received
+    /// parts, or would exceed the [`Limits`], the error of [`receive`] is
+    /// returned.
+    ///
+    /// [`receive`]: Decoder::receive
+    pub fn receive_unchecked(&mut self, part: Part) -> Result<bool, Error> {
+        if self.received.is_empty() {
+            return self.receive(part);
+        }
+        if self.complete()
+            || matches!(self.expected_checksum, Some(checksum) if checksum != part.checksum)
+        {
+            return Ok(false);
+        }
+        if part.sequence == 0 {
+            return Err(Error::InvalidSequence);
+        }
+        if !self.validate(&part) {
+            return Err(Error::InconsistentPart);
+        }
+        let buffered_bytes = (self.decoded_count + self.buffer.len()) * self.fragment_length;
+        if buffered_bytes + part.data.len() > self.limits.max_buffered_bytes {
+            return Err(Error::LimitExceeded);
+        }
+        self.processed_parts_count += 1;
+        self.stats.bytes_processed += part.data.len();
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("ur_parts_received_total").increment(1);
+            metrics::counter!("ur_bytes_processed_total").increment(part.data.len() as u64);
+        }
+        let indexes = self
+            .scheduler
+            .choose_fragments(part.sequence, part.sequence_count, part.checksum)
+            .into_iter()
+            .collect();
+        self.process(&indexes, part)?;
+        Ok(true)
+    }
+
+    /// Receives parts from an iterator until the decoder is complete,
+    /// returning the decoded message if it was completed.
+    ///
+    /// Parts remaining in the iterator after completion are not consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// let parts: Vec<_> = (0..3).map(|_| encoder.next_part()).collect();
+    /// let mut decoder = Decoder::default();
+    /// assert_eq!(decoder.feed(parts).unwrap().as_deref(), Some(&b"data"[..]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If any part fails to be received, the error of [`receive`] is returned.
+    ///
+    /// [`receive`]: Decoder::receive
+    pub fn feed<I: IntoIterator<Item = Part>>(
+        &mut self,
+        parts: I,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        for part in parts {
+            if self.complete() {
+                break;
+            }
+            self.receive(part)?;
+        }
+        self.message()
+    }
+
+    /// Merges the parts received by another decoder of the same transfer into
+    /// this decoder, e.g. when each of several simultaneously displayed codes
+    /// is scanned by its own decoder.
+    ///
+    /// The segments recovered by the other decoder and its buffered mixed
+    /// parts are combined with the ones of this decoder, which may complete
+    /// the message although neither decoder is complete on its own. Transfers
+    /// with a different checksum than the expected one are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// let mut first = Decoder::default();
+    /// let mut second = Decoder::default();
+    /// first.receive(encoder.next_part()).unwrap();
+    /// second.receive(encoder.next_part()).unwrap();
+    /// second.receive(encoder.next_part()).unwrap();
+    /// first.merge(&second).unwrap();
+    /// assert_eq!(first.message().unwrap().as_deref(), Some(&b"Ten chars!"[..]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the decoders received parts of different transfers, or if merging
+    /// would exceed the [`Limits`] of this decoder, an error will be returned.
+    pub fn merge<U, V: FragmentStorage>(&mut self, other: &Decoder<U, V>) -> Result<(), Error> {
+        if other.received.is_empty()
+            || self.complete()
+            || matches!(self.expected_checksum, Some(checksum) if checksum != other.checksum)
+        {
+            return Ok(());
+        }
+        if self.received.is_empty() {
+            let total = other.sequence_count.checked_mul(other.fragment_length);
+            if other.sequence_count > self.limits.max_fragment_count
+                || !matches!(total, Some(total) if total <= self.limits.max_buffered_bytes)
+            {
+                return Err(Error::LimitExceeded);
+            }
+            self.storage
+                .reset(other.sequence_count, other.fragment_length)?;
+            self.sequence_count = other.sequence_count;
+            self.message_length = other.message_length;
+            self.checksum = other.checksum;
+            self.fragment_length = other.fragment_length;
+        } else if other.sequence_count != self.sequence_count
+            || other.message_length != self.message_length
+            || other.checksum != self.checksum
+            || other.fragment_length != self.fragment_length
+        {
+            return Err(Error::InconsistentPart);
+        }
+        let buffered_parts =
+            self.decoded_count + self.buffer.len() + other.decoded_count + other.buffer.len();
+        if buffered_parts * self.fragment_length > self.limits.max_buffered_bytes {
+            return Err(Error::LimitExceeded);
+        }
+
+        self.processed_parts_count += other.received.len();
+        for indexes in &other.received {
+            if !self.received.insert(indexes.clone()) {
+                self.stats.duplicate_parts += 1;
+            }
+        }
+        for index in 0..self.sequence_count {
+            if let Some(data) = other.storage.get(index) {
+                if !self.is_decoded(index) {
+                    self.process_simple(index, data.to_vec());
+                }
+            }
+        }
+        self.process_queue()?;
+        for (indexes, data) in other.buffer.values() {
+            self.process_complex(indexes, data.clone());
+            self.process_queue()?;
+        }
+        self.compact();
+        Ok(())
+    }
+
+    /// Validates a part against the decoder state and records its indexes.
+    /// Returns the indexes if the part should be processed.
+    fn accept(&mut self, part: &Part) -> Result<Option<FragmentIndexes>, Error> {
+        self.accept_with(part, None, 0)
+    }
+
+    /// Like [`Decoder::accept`], but with optionally precomputed indexes and a
+    /// number of accepted parts which are yet to be buffered.
+    fn accept_with(
+        &mut self,
+        part: &Part,
+        indexes: Option<FragmentIndexes>,
+        unbuffered: usize,
+    ) -> Result<Option<FragmentIndexes>, Error> {
+        if self.complete() {
+            return Ok(None);
+        }
+        if matches!(self.expected_checksum, Some(checksum) if checksum != part.checksum) {
+            return Ok(None);
+        }
+
+        // Only receive parts that will yield data.
+        if part.sequence_count == 0 || part.data.is_empty() || part.message_length == 0 {
+            return Err(Error::EmptyPart);
+        }
+        if part.sequence == 0 {
+            return Err(Error::InvalidSequence);
+        }
+
+        if self.received.is_empty() {
+            let total = part.sequence_count.checked_mul(part.data.len());
+            if matches!(total, Some(total) if total < part.message_length) {
+                return Err(Error::InvalidMessageLength);
+            }
+            // A message whose segments exceed the buffer can never complete,
+            // so it is rejected before any storage is committed to it.
+            if part.sequence_count > self.limits.max_fragment_count
+                || !matches!(total, Some(total) if total <= self.limits.max_buffered_bytes)
+            {
+                return Err(Error::LimitExceeded);
+            }
+            self.storage.reset(part.sequence_count, part.data.len())?;
+            self.sequence_count = part.sequence_count;
+            self.message_length = part.message_length;
+            self.checksum = part.checksum;
+            self.fragment_length = part.data.len();
+        } else if !self.validate(part) {
+            return Err(Error::InconsistentPart);
+        }
+        self.processed_parts_count += 1;
+        self.stats.bytes_processed += part.data.len();
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("ur_parts_received_total").increment(1);
+            metrics::counter!("ur_bytes_processed_total").increment(part.data.len() as u64);
+        }
+        let indexes = indexes.unwrap_or_else(|| {
+            self.scheduler
+                .choose_fragments(part.sequence, part.sequence_count, part.checksum)
+                .into_iter()
+                .collect()
+        });
+        if self.received.contains(&indexes) {
+            self.stats.duplicate_parts += 1;
+            #[cfg(feature = "metrics")]
+            metrics::counter!("ur_duplicate_parts_total").increment(1);
+            return Ok(None);
+        }
+        let buffered_parts = self.decoded_count + self.buffer.len() + unbuffered;
+        let buffered_bytes = buffered_parts * self.fragment_length;
+        if buffered_bytes + part.data.len() > self.limits.max_buffered_bytes {
+            return Err(Error::LimitExceeded);
+        }
+        self.received.insert(indexes.clone());
+        Ok(Some(indexes))
+    }
+
+    fn process(&mut self, indexes: &FragmentIndexes, part: Part) -> Result<(), Error> {
+        if let Some(index) = indexes.single() {
+            self.process_simple(index, part.data);
+        } else {
+            self.process_complex(indexes, part.data);
+        }
+        self.process_queue()?;
+        self.compact();
+        Ok(())
+    }
+
+    /// Stores a recovered segment and queues it for reducing the buffered
+    /// mixed parts. The segment data is only held once, in the storage.
+    fn process_simple(&mut self, index: usize, data: Vec<u8>) {
+        // indexes are within bounds unless a scheduler violates its contract
+        if index >= self.sequence_count {
+            return;
+        }
+        if self.storage.insert(index, data) {
+            self.decoded_count += 1;
+            #[cfg(feature = "metrics")]
+            if self.complete() {
+                metrics::counter!("ur_transfers_completed_total").increment(1);
+                metrics::counter!("ur_bytes_decoded_total").increment(self.message_length as u64);
+            }
+        }
+        self.queue.push(index);
+    }
+
+    fn process_queue(&mut self) -> Result<(), Error> {
+        while let Some(index) = self.queue.pop() {
+            let Some(ids) = self.pending.remove(&index) else {
+                continue;
+            };
+            let simple = self.storage.get(index).ok_or(Error::ExpectedItem)?;
+            let mut recovered = Vec::new();
+            for id in ids {
+                // parts which have been recovered in the meantime are stale
+                let Some((indexes, data)) = self.buffer.get_mut(&id) else {
+                    continue;
+                };
+                if !indexes.contains(index) {
+                    continue;
+                }
+                indexes.remove(index);
+                xor_into(data, simple);
+                self.stats.xor_operations += 1;
+                if let Some(single) = indexes.single() {
+                    recovered.push((single, id));
+                }
+            }
+            for (index, id) in recovered {
+                if let Some((_, data)) = self.buffer.remove(&id) {
+                    self.process_simple(index, data);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn process_complex(&mut self, indexes: &FragmentIndexes, mut data: Vec<u8>) {
+        if indexes.iter().all(|idx| self.is_decoded(idx)) {
+            return;
+        }
+        let (remaining, xors) = reduce(&self.storage, indexes, &mut data);
+        self.stats.xor_operations += xors;
+        self.buffer_or_process(remaining, data);
+    }
+
+    /// Processes a part reduced to a single segment, or buffers it under a new
+    /// identifier which is referenced from each of its segments.
+    fn buffer_or_process(&mut self, indexes: FragmentIndexes, data: Vec<u8>) {
+        if let Some(index) = indexes.single() {
+            self.process_simple(index, data);
+            return;
+        }
+        let id = self.buffer.keys().next_back().map_or(0, |id| id + 1);
+        for index in indexes.iter() {
+            self.pending.entry(index).or_default().push(id);
+        }
+        self.buffer.insert(id, (indexes, data));
+    }
+
+    /// Prunes the buffer once it holds more than twice as many mixed parts as
+    /// there are unknown segments. Pruning more eagerly would discard parts
+    /// which, despite being dependent, speed up reducing the other parts.
+    fn compact(&mut self) {
+        if self.buffer.len() > 2 * (self.sequence_count - self.decoded_count) {
+            self.prune();
+        }
+    }
+
+    /// Removes buffered mixed parts which don't carry information beyond the
+    /// other buffered parts, e.g. parts which were reduced to the same
+    /// segments. Returns the number of removed parts.
+    ///
+    /// The decoder prunes its buffer whenever it holds more than twice as many
+    /// mixed parts as there are unknown segments, such that its memory stays
+    /// proportional to the unknown segments on long lossy transfers. Of
+    /// dependent parts, the ones combining the fewest segments are kept, as
+    /// they are the most likely to be reduced to a single segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder, PartScheduler};
+    ///
+    /// /// Mixes every part, such that the third part combines the first two.
+    /// struct Mixed;
+    ///
+    /// impl PartScheduler for Mixed {
+    ///     fn choose_fragments(&self, sequence: usize, _: usize, _: u32) -> Vec<usize> {
+    ///         match sequence {
+    ///             1 => vec![0, 1],
+    ///             2 => vec![1, 2],
+    ///             _ => vec![0, 2],
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4)
+    ///     .unwrap()
+    ///     .with_scheduler(Mixed);
+    /// let mut decoder = Decoder::default().with_scheduler(Mixed);
+    /// for _ in 0..3 {
+    ///     decoder.receive(encoder.next_part()).unwrap();
+    /// }
+    /// assert_eq!(decoder.stats().buffered_parts, 3);
+    /// assert_eq!(decoder.prune(), 1);
+    /// assert_eq!(decoder.stats().buffered_parts, 2);
+    /// ```
+    pub fn prune(&mut self) -> usize {
+        // the reduced index sets of the kept parts, keyed by their lowest index
+        let mut basis = alloc::collections::btree_map::BTreeMap::<usize, FragmentIndexes>::new();
+        let mut dependent = Vec::new();
+        let mut rows: Vec<_> = self.buffer.iter().collect();
+        rows.sort_by_key(|(_, (indexes, _))| indexes.len());
+        for (&id, (indexes, _)) in rows {
+            let mut reduced = indexes.clone();
+            for (&pivot, row) in &basis {
+                if reduced.contains(pivot) {
+                    reduced.toggle_all(row);
+                }
+            }
+            let Some(pivot) = reduced.iter().next() else {
+                dependent.push(id);
+                continue;
+            };
+            basis.insert(pivot, reduced);
+        }
+        for id in &dependent {
+            let Some((indexes, _)) = self.buffer.remove(id) else {
+                continue;
+            };
+            for index in indexes.iter() {
+                if let Some(ids) = self.pending.get_mut(&index) {
+                    ids.retain(|pending| pending != id);
+                    if ids.is_empty() {
+                        self.pending.remove(&index);
+                    }
+                }
+            }
+        }
+        dependent.len()
+    }
+
+    /// Solves the buffered mixed parts by Gaussian elimination, recovering
+    /// segments which are determined by the received parts but can't be
+    /// reduced one at a time. Returns the number of recovered segments.
+    ///
+    /// Receiving parts reduces them as far as possible, which can stall even
+    /// though the parts received so far would suffice to recombine the message.
+    /// Elimination takes time cubic in the number of segments, so it's up to
+    /// the caller to resort to it, e.g. once at least [`expected_fragment_count`]
+    /// parts have been received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder, PartScheduler};
+    ///
+    /// /// Mixes every part, such that no segment can be recovered directly.
+    /// struct Mixed;
+    ///
+    /// impl PartScheduler for Mixed {
+    ///     fn choose_fragments(&self, sequence: usize, _: usize, _: u32) -> Vec<usize> {
+    ///         match sequence {
+    ///             1 => vec![0, 1],
+    ///             2 => vec![1, 2],
+    ///             _ => vec![0, 1, 2],
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4)
+    ///     .unwrap()
+    ///     .with_scheduler(Mixed);
+    /// let mut decoder = Decoder::default().with_scheduler(Mixed);
+    /// for _ in 0..3 {
+    ///     decoder.receive(encoder.next_part()).unwrap();
+    /// }
+    /// assert!(!decoder.complete());
+    /// assert_eq!(decoder.solve().unwrap(), 3);
+    /// assert_eq!(decoder.message().unwrap().as_deref(), Some(&b"Ten chars!"[..]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the decoder state is inconsistent, an error will be returned.
+    ///
+    /// [`expected_fragment_count`]: Decoder::expected_fragment_count
+    pub fn solve(&mut self) -> Result<usize, Error> {
+        let decoded_count = self.decoded_count;
+        let mut rows: Vec<(FragmentIndexes, Vec<u8>)> = self.buffer.values().cloned().collect();
+        let mut pivot = 0;
+        for index in 0..self.sequence_count {
+            if self.is_decoded(index) {
+                continue;
+            }
+            let Some(row) = (pivot..rows.len()).find(|&row| rows[row].0.contains(index)) else {
+                continue;
+            };
+            rows.swap(pivot, row);
+            let (head, tail) = rows.split_at_mut(pivot);
+            let ((pivot_indexes, pivot_data), tail) =
+                tail.split_first_mut().ok_or(Error::ExpectedItem)?;
+            for (indexes, data) in head.iter_mut().chain(tail) {
+                if indexes.contains(index) {
+                    indexes.toggle_all(pivot_indexes);
+                    xor_into(data, pivot_data);
+                    self.stats.xor_operations += 1;
+                }
+            }
+            pivot += 1;
+        }
+        for (indexes, data) in rows {
+            if let Some(index) = indexes.single() {
+                if !self.is_decoded(index) {
+                    self.process_simple(index, data);
+                }
+            }
+        }
+        self.process_queue()?;
+        Ok(self.decoded_count - decoded_count)
+    }
+
+    fn is_decoded(&self, index: usize) -> bool {
+        self.storage.get(index).is_some()
+    }
+
+    /// Returns [`Stats`] on the parts received so far.
+    ///
+    /// # Examples
+    ///
+    /// See the [`Stats`] documentation for an example.
+    #[must_use]
+    pub fn stats(&self) -> Stats {
+        Stats {
+            parts_received: self.processed_parts_count,
+            buffered_parts: self.buffer.len(),
+            ..self.stats
+        }
+    }
+
+    /// Returns whether the decoder is complete and hence the message available.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::fountain`] module documentation for an example.
+    #[must_use]
+    pub const fn complete(&self) -> bool {
+        self.message_length != 0 && self.decoded_count == self.sequence_count
+    }
+
+    /// Returns the number of message segments which have been recovered so far,
+    /// either from simple parts or by reducing mixed parts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(decoder.decoded_count(), 0);
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert_eq!(decoder.decoded_count(), 1);
+    /// ```
+    #[must_use]
+    pub const fn decoded_count(&self) -> usize {
+        self.decoded_count
+    }
+
+    /// Returns the sorted indexes of the message segments which have been
+    /// recovered so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// encoder.next_part();
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert_eq!(decoder.received_indexes().to_vec(), vec![1]);
+    /// ```
+    #[must_use]
+    pub fn received_indexes(&self) -> FragmentIndexes {
+        (0..self.sequence_count)
+            .filter(|&idx| self.is_decoded(idx))
+            .collect()
+    }
+
+    /// Returns the sorted indexes of the message segments which are still
+    /// outstanding. Before the first part has been received, the number of
+    /// segments is unknown and an empty set is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// assert!(decoder.missing_indexes().is_empty());
+    /// encoder.next_part();
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert_eq!(decoder.missing_indexes().to_vec(), vec![0, 2]);
+    /// ```
+    #[must_use]
+    pub fn missing_indexes(&self) -> FragmentIndexes {
+        (0..self.sequence_count)
+            .filter(|&idx| !self.is_decoded(idx))
+            .collect()
+    }
+
+    /// Returns an [`Ack`] of the message segments recovered so far, to be sent
+    /// back to the encoding side, or `None` if no part has been received yet.
+    ///
+    /// # Examples
+    ///
+    /// See the [`Ack`] documentation for an example.
+    #[must_use]
+    pub fn ack(&self) -> Option<Ack> {
+        (!self.received.is_empty()).then(|| Ack {
+            checksum: self.checksum,
+            fragment_count: self.sequence_count,
+            received: self.received_indexes(),
+        })
+    }
+
+    /// Returns the number of segments the message has been split into, or `None`
+    /// if no part has been received yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(decoder.expected_fragment_count(), None);
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert_eq!(decoder.expected_fragment_count(), Some(2));
+    /// ```
+    #[must_use]
+    pub fn expected_fragment_count(&self) -> Option<usize> {
+        (!self.received.is_empty()).then_some(self.sequence_count)
+    }
+
+    /// Returns the length of the message being decoded, or `None` if no part
+    /// has been received yet.
+    ///
+    /// This allows allocating a buffer or displaying the transfer size as soon
+    /// as the first part is received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// assert_eq!(decoder.message_length(), None);
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert_eq!(decoder.message_length(), Some(10));
+    /// assert_eq!(decoder.sequence_count(), Some(3));
+    /// assert_eq!(decoder.fragment_length(), Some(4));
+    /// assert_eq!(decoder.checksum(), Some(encoder.next_part().checksum()));
+    /// ```
+    #[must_use]
+    pub fn message_length(&self) -> Option<usize> {
+        (!self.received.is_empty()).then_some(self.message_length)
+    }
+
+    /// Returns the number of segments the message has been split into, or `None`
+    /// if no part has been received yet. This equals [`expected_fragment_count`].
+    ///
+    /// # Examples
+    ///
+    /// See [`Decoder::message_length`] for an example.
+    ///
+    /// [`expected_fragment_count`]: Decoder::expected_fragment_count
+    #[must_use]
+    pub fn sequence_count(&self) -> Option<usize> {
+        self.expected_fragment_count()
+    }
+
+    /// Returns the length of the message segments, or `None` if no part has
+    /// been received yet.
+    ///
+    /// # Examples
+    ///
+    /// See [`Decoder::message_length`] for an example.
+    #[must_use]
+    pub fn fragment_length(&self) -> Option<usize> {
+        (!self.received.is_empty()).then_some(self.fragment_length)
+    }
+
+    /// Returns the CRC32 checksum of the message being decoded, or `None` if no
+    /// part has been received yet.
+    ///
+    /// # Examples
+    ///
+    /// See [`Decoder::message_length`] for an example.
+    #[must_use]
+    pub fn checksum(&self) -> Option<u32> {
+        (!self.received.is_empty()).then_some(self.checksum)
+    }
+
+    /// Returns an estimate of the decoding progress as a fraction between
+    /// `0.0` and `1.0`.
+    ///
+    /// Since it is not known in advance how many mixed parts will be needed,
+    /// the estimate follows the reference implementations and assumes that
+    /// 1.75 times the number of segments have to be received. It never exceeds
+    /// `0.99` until the decoder is [`complete`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(decoder.estimated_percent_complete(), 0.0);
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert!(decoder.estimated_percent_complete() > 0.0);
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert_eq!(decoder.estimated_percent_complete(), 1.0);
+    /// ```
+    ///
+    /// [`complete`]: Decoder::complete
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn estimated_percent_complete(&self) -> f64 {
+        if self.complete() {
+            return 1.0;
+        }
+        self.expected_fragment_count().map_or(0.0, |count| {
+            let estimated_input_parts = count as f64 * 1.75;
+            (self.processed_parts_count as f64 / estimated_input_parts).min(0.99)
+        })
+    }
+
+    /// Returns the [`Progress`] of the transfer, combining the recovered and
+    /// total number of segments with the estimated progress.
+    ///
+    /// # Examples
+    ///
+    /// See the [`Progress`] documentation for an example.
+    #[must_use]
+    pub fn progress(&self) -> Progress {
+        let total_fragments = self.expected_fragment_count();
+        let label = total_fragments.map_or_else(
+            || alloc::format!("{}/? fragments", self.decoded_count),
+            |total| alloc::format!("{}/{total} fragments", self.decoded_count),
+        );
+        Progress {
+            decoded_fragments: self.decoded_count,
+            total_fragments,
+            estimated_percent_complete: self.estimated_percent_complete(),
+            label,
+        }
+    }
+
+    /// Returns whether receiving the part would contribute new information,
+    /// i.e. it is consistent with the transfer, not a duplicate of a received
+    /// part and combines at least one segment which hasn't been recovered yet.
+    ///
+    /// This is cheaper than receiving the part, and allows e.g. scanners to
+    /// give feedback on duplicate frames. A part which is not useful can still
+    /// be passed to [`receive`], but won't change the decoder state other than
+    /// its [`Stats`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// let part = encoder.next_part();
+    /// assert!(decoder.is_part_useful(&part));
+    /// decoder.receive(part.clone()).unwrap();
+    /// assert!(!decoder.is_part_useful(&part));
+    /// assert!(decoder.is_part_useful(&encoder.next_part()));
+    /// ```
+    ///
+    /// [`receive`]: Decoder::receive
+    #[must_use]
+    pub fn is_part_useful(&self, part: &Part) -> bool {
+        if self.complete()
+            || part.sequence == 0
+            || part.sequence_count == 0
+            || part.data.is_empty()
+            || part.message_length == 0
+        {
+            return false;
+        }
+        if self.received.is_empty() {
+            return true;
+        }
+        if !self.validate(part) {
+            return false;
+        }
+        let indexes = self
+            .scheduler
+            .choose_fragments(part.sequence, part.sequence_count, part.checksum)
+            .into_iter()
+            .collect();
+        !self.received.contains(&indexes) && indexes.iter().any(|idx| !self.is_decoded(idx))
+    }
+
+    /// Checks whether a [`Part`] is receivable by the decoder.
+    /// This can fail if other parts were previously received whose
+    /// metadata (such as number of segments) is inconsistent with the
+    /// present [`Part`]. Note that a fresh decoder will always return
+    /// false here.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// let part = encoder.next_part();
+    ///
+    /// // a fresh decoder always returns false
+    /// assert!(!decoder.validate(&part));
+    ///
+    /// // parts with the same metadata validate successfully
+    /// decoder.receive(part).unwrap();
+    /// let part = encoder.next_part();
+    /// assert!(decoder.validate(&part));
+    ///
+    /// // parts with the different metadata don't validate
+    /// let mut encoder = Encoder::new(b"more data", 3).unwrap();
+    /// let part = encoder.next_part();
+    /// assert!(!decoder.validate(&part));
+    /// ```
+    #[must_use]
+    pub fn validate(&self, part: &Part) -> bool {
+        if self.received.is_empty() {
+            return false;
+        }
+
+        if part.sequence_count != self.sequence_count
+            || part.message_length != self.message_length
+            || part.checksum != self.checksum
+            || part.data.len() != self.fragment_length
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// If [`complete`], returns the decoded message, `None` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// If an inconsistent internal state is detected, an error will be returned.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::fountain`] module documentation for an example.
+    ///
+    /// [`complete`]: Decoder::complete
+    pub fn message(&self) -> Result<Option<Vec<u8>>, Error> {
+        let mut message = Vec::new();
+        Ok(self.message_into(&mut message)?.then_some(message))
+    }
+
+    /// If [`complete`], appends the decoded message to the given buffer and
+    /// returns `true`. Otherwise, the buffer is left untouched and `false` is
+    /// returned.
+    ///
+    /// This avoids allocating the message if the caller has a buffer to reuse.
+    ///
+    /// # Errors
+    ///
+    /// If an inconsistent internal state is detected, an error will be returned
+    /// and the buffer is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut decoder = Decoder::default();
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// let mut message = b"some ".to_vec();
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert!(!decoder.message_into(&mut message).unwrap());
+    /// decoder.receive(encoder.next_part()).unwrap();
+    /// assert!(decoder.message_into(&mut message).unwrap());
+    /// assert_eq!(message, b"some data");
+    /// ```
+    ///
+    /// [`complete`]: Decoder::complete
+    pub fn message_into(&self, message: &mut Vec<u8>) -> Result<bool, Error> {
+        if !self.complete() {
+            return Ok(false);
+        }
+        let start = message.len();
+        if let Err(e) = self.append_message(message) {
+            message.truncate(start);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Appends the segments of a complete decoder to the buffer, stripping the
+    /// zero padding of the last segment.
+    fn append_message(&self, message: &mut Vec<u8>) -> Result<(), Error> {
+        message.reserve(self.message_length);
+        let mut remaining = self.message_length;
+        for index in 0..self.sequence_count {
+            let data = self.storage.get(index).ok_or(Error::ExpectedItem)?;
+            let (data, padding) = data.split_at(remaining.min(data.len()));
+            if padding.iter().any(|&x| x != 0) {
+                return Err(Error::InvalidPadding);
+            }
+            message.extend_from_slice(data);
+            remaining -= data.len();
+        }
+        if remaining != 0 {
+            return Err(Error::ExpectedItem);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<S: PartScheduler + Sync, T: FragmentStorage + Sync> Decoder<S, T> {
+    /// Receives a batch of parts, distributing the work of determining their
+    /// segments and combining them with the recovered segments across threads.
+    ///
+    /// This is useful when replaying recorded parts, e.g. from a file import or
+    /// a multi-camera capture. Parts received after completion are ignored.
+    /// Returns whether any of the parts was processed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Decoder, Encoder};
+    /// let mut encoder = Encoder::new(&[7; 10_000], 100).unwrap();
+    /// let parts: Vec<_> = (0..150).map(|_| encoder.next_part()).skip(20).collect();
+    /// let mut decoder = Decoder::default();
+    /// assert!(decoder.receive_all_parallel(parts).unwrap());
+    /// assert_eq!(decoder.message().unwrap(), Some(vec![7; 10_000]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If a part fails to be received, the error of [`receive`] is returned.
+    /// The parts preceding it in the batch have been received.
+    ///
+    /// [`receive`]: Decoder::receive
+    pub fn receive_all_parallel<I: IntoIterator<Item = Part>>(
+        &mut self,
+        parts: I,
+    ) -> Result<bool, Error> {
+        use rayon::prelude::*;
+
+        let parts: Vec<Part> = parts.into_iter().collect();
+        let scheduler = &self.scheduler;
+        // invalid parts are rejected when accepting them
+        let indexes: Vec<Option<FragmentIndexes>> = parts
+            .par_iter()
+            .map(|part| {
+                (part.sequence > 0 && part.sequence_count > 0).then(|| {
+                    scheduler
+                        .choose_fragments(part.sequence, part.sequence_count, part.checksum)
+                        .into_iter()
+                        .collect()
+                })
+            })
+            .collect();
+
+        let mut processed = false;
+        let mut mixed = Vec::new();
+        let mut result = Ok(());
+        for (part, indexes) in parts.into_iter().zip(indexes) {
+            let indexes = match self.accept_with(&part, indexes, mixed.len()) {
+                Ok(Some(indexes)) => indexes,
+                Ok(None) => continue,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            };
+            processed = true;
+            if let Some(index) = indexes.single() {
+                self.process_simple(index, part.data);
+            } else {
+                mixed.push((indexes, part.data));
+            }
+        }
+
+        let decoded = &self.storage;
+        let reduced: Vec<_> = mixed
+            .into_par_iter()
+            .map(|(indexes, mut data)| {
+                let (remaining, xors) = reduce(decoded, &indexes, &mut data);
+                (remaining, data, xors)
+            })
+            .collect();
+        for (remaining, data, xors) in reduced {
+            self.stats.xor_operations += xors;
+            // parts may consist of segments recovered within the batch
+            if !remaining.is_empty() {
+                self.buffer_or_process(remaining, data);
+            }
+        }
+        self.process_queue()?;
+        self.compact();
+        result.map(|()| processed)
+    }
+}
+
+/// Receives all parts of the iterator, silently skipping parts which are
+/// rejected by [`Decoder::receive`]. Use [`Decoder::feed`] to handle errors.
+impl<S: PartScheduler, T: FragmentStorage> Extend<Part> for Decoder<S, T> {
+    fn extend<I: IntoIterator<Item = Part>>(&mut self, parts: I) {
+        for part in parts {
+            let _ = self.receive(part);
+        }
+    }
+}
+
+/// A fountain encoder splitting a message into an unbounded stream of [`Part`]s.
+///
+/// Besides the [`Encoder`] of the uniform resource specification, this is
+/// implemented by the encoder of the `raptorq` module, so that transports can
+/// be written once for either fountain code.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{Decoder, Encoder, MessageDecoder, MessageEncoder};
+///
+/// fn transfer(encoder: &mut impl MessageEncoder, decoder: &mut impl MessageDecoder) -> Vec<u8> {
+///     while !decoder.complete() {
+///         decoder.receive(encoder.next_part()).unwrap();
+///     }
+///     decoder.message().unwrap().unwrap()
+/// }
+///
+/// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+/// assert_eq!(transfer(&mut encoder, &mut Decoder::default()), b"Ten chars!");
+/// ```
+pub trait MessageEncoder {
+    /// Returns the next part to be emitted.
+    fn next_part(&mut self) -> Part;
+
+    /// Returns the current count of how many parts have been emitted.
+    fn current_sequence(&self) -> usize;
+
+    /// Returns the number of segments the message has been split up into.
+    fn fragment_count(&self) -> usize;
+
+    /// Returns whether all original segments have been emitted at least once.
+    fn complete(&self) -> bool;
+}
+
+/// A fountain decoder recombining the [`Part`]s of a [`MessageEncoder`].
+///
+/// # Examples
+///
+/// See the [`MessageEncoder`] documentation for an example.
+pub trait MessageDecoder {
+    /// Receives a part into the decoder. Returns whether the part was
+    /// processed, which is not the case once the decoder is complete.
+    ///
+    /// # Errors
+    ///
+    /// If the part is invalid, inconsistent with previously received parts or
+    /// exceeds the limits of the decoder, an error will be returned.
+    fn receive(&mut self, part: Part) -> Result<bool, Error>;
+
+    /// Returns whether the decoder is complete and hence the message available.
+    fn complete(&self) -> bool;
+
+    /// If complete, returns the decoded message, `None` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// If the decoded message is inconsistent, an error will be returned.
+    fn message(&self) -> Result<Option<Vec<u8>>, Error>;
+}
+
+impl<S: PartScheduler, M: AsRef<[u8]>> MessageEncoder for Encoder<S, M> {
+    fn next_part(&mut self) -> Part {
+        self.next_part()
+    }
+
+    fn current_sequence(&self) -> usize {
+        self.current_sequence()
+    }
+
+    fn fragment_count(&self) -> usize {
+        self.fragment_count()
+    }
+
+    fn complete(&self) -> bool {
+        self.complete()
+    }
+}
+
+impl<S: PartScheduler, T: FragmentStorage> MessageDecoder for Decoder<S, T> {
+    fn receive(&mut self, part: Part) -> Result<bool, Error> {
+        self.receive(part)
+    }
+
+    fn complete(&self) -> bool {
+        self.complete()
+    }
+
+    fn message(&self) -> Result<Option<Vec<u8>>, Error> {
+        self.message()
+    }
+}
+
+/// The number of parts [`Encoder::prioritize`] searches for one recovering a
+/// pending missing segment.
+const PRIORITY_SEARCH_LIMIT: usize = 1000;
+
+/// The number of simulated transfers [`plan`] draws its estimates from.
+const PLAN_TRIALS: usize = 100;
+
+/// The multiple of the [expected number of transmitted parts] after which
+/// [`plan`] considers a simulated transfer stalled.
+///
+/// [expected number of transmitted parts]: analysis::expected_parts_transmitted
+const PLAN_MAX_OVERHEAD: f64 = 10.0;
+
+/// A transmission plan for a sender without a back-channel, as returned by
+/// [`plan`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedTransmission {
+    /// The number of parts to emit before looping, such that a receiver
+    /// recovers the message with the target probability.
+    pub parts_to_emit: usize,
+    /// The average number of parts a receiver needs to recover the message.
+    pub expected_receive_count: usize,
+}
+
+/// Plans how many parts a sender should cycle through before looping.
+///
+/// Given the number of segments of the message and the rate of parts lost on
+/// the channel, the plan estimates how many parts a receiver needs to recover
+/// the message with the target probability.
+///
+/// The number of parts needed depends on which parts are lost, so the plan is
+/// estimated from 100 simulated transfers with independently lost parts, using
+/// the default [`XoshiroScheduler`] and a [`Decoder`] which doesn't
+/// [solve](Decoder::solve) the mixed parts. The estimate is deterministic, but
+/// can't resolve target probabilities above 0.99. Use the [`analysis`] module
+/// to estimate the average effort for a decoder which does solve them.
+///
+/// Simulated transfers which need more than ten times the [expected number of
+/// transmitted parts] are abandoned, which bounds the time taken by the
+/// estimate.
+///
+/// # Examples
+///
+/// ```
+/// let lossless = ur::fountain::plan(10, 0.0, 0.99).unwrap();
+/// assert_eq!(lossless.parts_to_emit, 10);
+/// assert_eq!(lossless.expected_receive_count, 10);
+///
+/// let lossy = ur::fountain::plan(10, 0.5, 0.99).unwrap();
+/// assert!(lossy.parts_to_emit > 2 * lossy.expected_receive_count);
+/// ```
+///
+/// # Errors
+///
+/// If the fragment count is zero, the loss rate isn't within `[0, 1)` or the
+/// target probability isn't within `(0, 0.99]`, [`Error::InvalidPlan`] will be
+/// returned. The same error is returned if the simulated transfers needed to
+/// reach the target probability were abandoned.
+///
+/// [expected number of transmitted parts]: analysis::expected_parts_transmitted
+pub fn plan(
+    fragment_count: usize,
+    loss_rate: f64,
+    target_success_prob: f64,
+) -> Result<PlannedTransmission, Error> {
+    use rand_xoshiro::rand_core::RngCore;
+
+    if fragment_count == 0
+        || !(0.0..1.0).contains(&loss_rate)
+        || !(target_success_prob > 0.0 && target_success_prob <= 1.0)
+    {
+        return Err(Error::InvalidPlan);
+    }
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    // the number of trials allowed to need more parts, rounded down but
+    // tolerating the rounding error of e.g. `1.0 - 0.99`
+    let failures = ((1.0 + f64::EPSILON - target_success_prob) * PLAN_TRIALS as f64) as usize;
+    if failures == 0 {
+        return Err(Error::InvalidPlan);
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let max_parts = (PLAN_MAX_OVERHEAD
+        * analysis::expected_parts_transmitted(fragment_count, loss_rate))
+        as usize;
+
+    let mut rng = crate::xoshiro::Xoshiro256::from("plan");
+    let mut emitted = Vec::with_capacity(PLAN_TRIALS);
+    let mut received = 0;
+    let mut completed = 0;
+    for _ in 0..PLAN_TRIALS {
+        // the segments only depend on the segment count and message checksum
+        let mut message = alloc::vec![0; fragment_count];
+        rng.fill_bytes(&mut message);
+        let mut encoder = Encoder::new(&message, 1)?;
+        let mut decoder = Decoder::default();
+        let mut trial_received = 0;
+        while !decoder.complete() && encoder.current_sequence() < max_parts {
+            let part = encoder.next_part();
+            if rng.next_double() >= loss_rate {
+                decoder.receive(part)?;
+                trial_received += 1;
+            }
+        }
+        if decoder.complete() {
+            emitted.push(encoder.current_sequence());
+            received += trial_received;
+            completed += 1;
+        } else {
+            emitted.push(usize::MAX);
+        }
+    }
+    emitted.sort_unstable();
+    let parts_to_emit = emitted[PLAN_TRIALS - 1 - failures.min(PLAN_TRIALS - 1)];
+    if parts_to_emit == usize::MAX {
+        return Err(Error::InvalidPlan);
+    }
+    Ok(PlannedTransmission {
+        parts_to_emit,
+        expected_receive_count: div_ceil(received, completed),
+    })
+}
+
+/// A part emitted by a fountain [`Encoder`].
+///
+/// Most commonly, this is obtained by calling [`next_part`] on the encoder.
+///
+/// Parts compare equal and hash alike if all their attributes are equal, which
+/// allows e.g. deduplicating received parts in a set.
+///
+/// With the `serde` feature, parts serialize as a struct of their attributes,
+/// unlike their CBOR array encoding used within URs.
+///
+/// [`next_part`]: Encoder::next_part
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Part {
+    sequence: usize,
+    sequence_count: usize,
+    message_length: usize,
+    checksum: u32,
+    data: Vec<u8>,
+}
+
+impl<C> minicbor::Encode<C> for Part {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        #[allow(clippy::cast_possible_truncation)]
+        e.array(5)?
+            .u32(self.sequence as u32)?
+            .u32(self.sequence_count as u32)?
+            .u32(self.message_length as u32)?
+            .u32(self.checksum)?
+            .bytes(&self.data)?;
+
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::Decode<'b, C> for Part {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        // other encoders may emit indefinite-length arrays and byte strings
+        let indefinite = match d.array()? {
+            Some(5) => false,
+            None => true,
+            Some(_) => {
+                return Err(minicbor::decode::Error::message(
+                    "invalid CBOR array length",
+                ))
+            }
+        };
+
+        let position = d.position();
+        let sequence = d.u32()? as usize;
+        let sequence_count = d.u32()? as usize;
+        if sequence == 0 || sequence_count == 0 {
+            return Err(
+                minicbor::decode::Error::message("sequence numbers start at one").at(position),
+            );
+        }
+        let message_length = d.u32()? as usize;
+        let checksum = d.u32()?;
+        let mut data = Vec::new();
+        for chunk in d.bytes_iter()? {
+            data.extend_from_slice(chunk?);
+        }
+        if indefinite {
+            if d.datatype()? != minicbor::data::Type::Break {
+                return Err(
+                    minicbor::decode::Error::message("invalid CBOR array length").at(d.position()),
+                );
+            }
+            d.set_position(d.position() + 1);
+        }
+
+        Ok(Self {
+            sequence,
+            sequence_count,
+            message_length,
+            checksum,
+            data,
+        })
+    }
+}
+
+impl Part {
+    /// Constructs a new [`Part`] from its raw attributes.
+    ///
+    /// This is mostly useful for custom transports which don't rely on the
+    /// CBOR serialization of parts. Consistency of the attributes is only
+    /// checked once the part is received by a [`Decoder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Encoder, Part};
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// let part = encoder.next_part();
+    /// let copy = Part::new(
+    ///     part.sequence(),
+    ///     part.sequence_count(),
+    ///     part.message_length(),
+    ///     part.checksum(),
+    ///     part.data().to_vec(),
+    /// );
+    /// assert_eq!(part, copy);
+    /// ```
+    #[must_use]
+    pub const fn new(
+        sequence: usize,
+        sequence_count: usize,
+        message_length: usize,
+        checksum: u32,
+        data: Vec<u8>,
+    ) -> Self {
+        Self {
+            sequence,
+            sequence_count,
+            message_length,
+            checksum,
+            data,
+        }
+    }
+
+    pub(crate) fn from_cbor(cbor: &[u8]) -> Result<Self, Error> {
+        let mut decoder = minicbor::Decoder::new(cbor);
+        let part = decoder.decode()?;
+        // reject trailing bytes, which other implementations may interpret differently
+        if decoder.position() != cbor.len() {
+            return Err(
+                minicbor::decode::Error::message("trailing bytes after CBOR part")
+                    .at(decoder.position())
+                    .into(),
+            );
+        }
+        Ok(part)
+    }
+
+    /// Returns the indexes of the message segments that were combined into this part,
+    /// as selected by the default [`XoshiroScheduler`]. Invalid parts with a zero
+    /// sequence number or count don't combine any segments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(encoder.next_part().indexes().to_vec(), vec![0]);
+    /// assert_eq!(encoder.next_part().indexes().to_vec(), vec![1]);
+    /// ```
+    #[must_use]
+    pub fn indexes(&self) -> FragmentIndexes {
+        choose_fragments(self.sequence, self.sequence_count, self.checksum)
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns the number of message segments that were combined into this part,
+    /// as selected by the default [`XoshiroScheduler`]. This is the length of
+    /// [`indexes`], but doesn't need to select the segments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// for _ in 0..4 {
+    ///     assert_eq!(encoder.next_part().degree(), 1);
+    /// }
+    /// assert_eq!(encoder.next_part().degree(), 3);
+    /// ```
+    ///
+    /// [`indexes`]: Part::indexes
+    #[must_use]
+    pub fn degree(&self) -> usize {
+        if self.sequence == 0 || self.sequence_count == 0 {
+            return 0;
+        }
+        if self.sequence <= self.sequence_count {
+            return 1;
+        }
+        part_rng(self.sequence, self.checksum).choose_degree(self.sequence_count) as usize
+    }
+
+    /// Indicates whether this part is an original segment of the message, or was obtained by
+    /// combining multiple segments via xor.
+    ///
+    /// # Examples
+    ///
+    /// The encoding setup follows the `fountain` module example.
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// // The encoder always emits the simple parts covering the message first
+    /// assert!(encoder.next_part().is_simple());
+    /// assert!(encoder.next_part().is_simple());
+    /// assert!(encoder.next_part().is_simple());
+    /// // The encoder then emits segment 3 again
+    /// assert!(encoder.next_part().is_simple());
+    /// // The encoder then emits all 3 segments combined
+    /// assert!(!encoder.next_part().is_simple());
+    /// ```
+    #[must_use]
+    pub fn is_simple(&self) -> bool {
+        self.degree() == 1
+    }
+
+    #[cfg(test)]
+    pub(crate) fn cbor(&self) -> Result<Vec<u8>, Error> {
+        minicbor::to_vec(self).map_err(Error::from)
+    }
+
+    /// Renders the CBOR encoding of this part in diagnostic notation, which is
+    /// convenient when debugging interoperability with other implementations.
+    /// The checksum is rendered as a hexadecimal number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(
+    ///     encoder.next_part().to_diagnostic(),
+    ///     "[1, 2, 4, 0xadf3f363, h'6461']"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_diagnostic(&self) -> alloc::string::String {
+        use core::fmt::Write;
+        let mut diagnostic = alloc::format!(
+            "[{}, {}, {}, 0x{:08x}, h'",
+            self.sequence,
+            self.sequence_count,
+            self.message_length,
+            self.checksum
+        );
+        for byte in &self.data {
+            // writing to a string can't fail
+            let _ = write!(diagnostic, "{byte:02x}");
+        }
+        diagnostic.push_str("']");
+        diagnostic
+    }
+
+    /// Returns the sequence identifier of this part, as used in the path of
+    /// multi-part URs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(encoder.next_part().sequence_id(), "1-2");
+    /// ```
+    #[must_use]
+    pub fn sequence_id(&self) -> alloc::string::String {
+        alloc::format!("{}-{}", self.sequence, self.sequence_count)
+    }
+
+    /// Returns the sequence identifier of this part like [`Part::sequence_id`],
+    /// but with the sequence number zero-padded to the width of the sequence
+    /// count, such that the identifiers of the first parts have the same length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(&[0; 230], 1).unwrap();
+    /// assert_eq!(encoder.next_part().padded_sequence_id(), "001-230");
+    /// assert_eq!(encoder.part_at(1000).padded_sequence_id(), "1000-230");
+    /// ```
+    #[must_use]
+    pub fn padded_sequence_id(&self) -> alloc::string::String {
+        let mut id = alloc::string::String::new();
+        self.write_sequence_id(&mut id, true);
+        id
+    }
+
+    fn write_sequence_id(&self, id: &mut alloc::string::String, padded: bool) {
+        use core::fmt::Write;
+
+        let width = if padded {
+            crate::ur::decimal_length(self.sequence_count)
+        } else {
+            0
+        };
+        // writing to a string can't fail
+        let _ = write!(id, "{:0width$}-{}", self.sequence, self.sequence_count);
+    }
+
+    /// Renders this part as a multi-part UR of the given type, as emitted by
+    /// [`crate::ur::Encoder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+    /// encoder.next_part();
+    /// assert_eq!(
+    ///     encoder.next_part().to_ur_string(&ur::Type::Bytes).unwrap(),
+    ///     "ur:bytes/2-3/lpaoaxbkcywkwmhfwnfyiaishsjpmkdecasf"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If serialization fails an error will be returned.
+    pub fn to_ur_string(&self, ur_type: &crate::ur::Type) -> Result<alloc::string::String, Error> {
+        let mut ur = alloc::string::String::new();
+        self.write_ur_string(ur_type, &mut Vec::new(), &mut ur, false)?;
+        Ok(ur)
+    }
+
+    /// Appends this part as a multi-part UR of the given type to a string,
+    /// using `cbor` as scratch buffer for the serialized part.
+    pub(crate) fn write_ur_string(
+        &self,
+        ur_type: &crate::ur::Type,
+        cbor: &mut Vec<u8>,
+        ur: &mut alloc::string::String,
+        padded: bool,
+    ) -> Result<(), Error> {
+        cbor.clear();
+        minicbor::encode(self, &mut *cbor)?;
+        ur.push_str("ur:");
+        ur.push_str(ur_type.encoding());
+        ur.push('/');
+        self.write_sequence_id(ur, padded);
+        ur.push('/');
+        ur.extend(crate::bytewords::encode_iter(
+            cbor,
+            crate::bytewords::Style::Minimal,
+        ));
+        Ok(())
+    }
+
+    /// Returns the sequence number of this part. Sequence numbers start at one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(encoder.next_part().sequence(), 1);
+    /// assert_eq!(encoder.next_part().sequence(), 2);
+    /// ```
+    #[must_use]
+    pub const fn sequence(&self) -> usize {
+        self.sequence
+    }
+
+    /// Returns the number of segments the original message has been split into.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(encoder.next_part().sequence_count(), 2);
+    /// ```
+    #[must_use]
+    pub const fn sequence_count(&self) -> usize {
+        self.sequence_count
+    }
+
+    /// Returns the length of the original message, excluding any padding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(encoder.next_part().message_length(), 4);
+    /// ```
+    #[must_use]
+    pub const fn message_length(&self) -> usize {
+        self.message_length
+    }
+
+    /// Returns the CRC32 checksum of the original message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 3).unwrap();
+    /// assert_eq!(encoder.next_part().checksum(), 0xadf3_f363);
+    /// ```
+    #[must_use]
+    pub const fn checksum(&self) -> u32 {
+        self.checksum
+    }
+
+    /// Returns a slice view onto the underlying data.
+    ///
+    /// Note that for non-simple parts this will be the result of
+    /// xoring multiple message segments together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let data = String::from("Ten chars!");
+    /// let mut encoder = ur::fountain::Encoder::new(data.as_bytes(), 4).unwrap();
+    /// assert_eq!(encoder.next_part().data(), "Ten ".as_bytes());
+    /// ```
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Calculates the quotient of `a` and `b`, rounding the results towards
+/// positive infinity.
+///
+/// Note: there's an implementation on the `usize` type of this function,
+/// but it's not stable yet.
+#[must_use]
+pub(crate) const fn div_ceil(a: usize, b: usize) -> usize {
+    let d = a / b;
+    let r = a % b;
+    if r > 0 {
+        d + 1
+    } else {
+        d
+    }
+}
+
+/// Returns the length of the segments a message of `data_length` bytes is split
+/// into by an [`Encoder`] with the given maximum fragment length.
+///
+/// The message is split into as few segments as the maximum fragment length
+/// allows, which are then made as short as possible. This matches the
+/// reference implementations, such that external tooling can predict the
+/// segments without constructing an encoder.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{fragment_length, Encoder};
+/// assert_eq!(fragment_length(10, 4), 4);
+/// assert_eq!(fragment_length(10, 6), 5);
+/// assert_eq!(fragment_length(12345, 1955), 1764);
+/// let encoder = Encoder::new(&[0; 12345], 1955).unwrap();
+/// assert_eq!(encoder.fragment_length(), 1764);
+/// ```
+///
+/// # Panics
+///
+/// This panics if `data_length` or `max_fragment_length` is zero.
+#[must_use]
+pub const fn fragment_length(data_length: usize, max_fragment_length: usize) -> usize {
+    bounded_fragment_length(data_length, 1, max_fragment_length)
+}
+
+#[must_use]
+pub(crate) const fn bounded_fragment_length(
+    data_length: usize,
+    min_fragment_length: usize,
+    max_fragment_length: usize,
+) -> usize {
+    let mut max_fragment_count = data_length / min_fragment_length;
+    if max_fragment_count == 0 {
+        max_fragment_count = 1;
+    }
+    let mut fragment_count = div_ceil(data_length, max_fragment_length);
+    if fragment_count > max_fragment_count {
+        fragment_count = max_fragment_count;
+    }
+    div_ceil(data_length, fragment_count)
+}
+
+/// Splits the data into segments of `fragment_length` bytes, padding the last
+/// segment with zeros.
+///
+/// These are the segments an [`Encoder`] combines into parts, given the length
+/// returned by [`fragment_length`].
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{fragment_length, partition};
+/// let data = b"Ten chars!";
+/// let fragments = partition(data, fragment_length(data.len(), 4));
+/// assert_eq!(fragments, vec![b"Ten ".to_vec(), b"char".to_vec(), b"s!\0\0".to_vec()]);
+/// ```
+///
+/// # Panics
+///
+/// This panics if `fragment_length` is zero.
+#[must_use]
+pub fn partition(data: &[u8], fragment_length: usize) -> Vec<Vec<u8>> {
+    data.chunks(fragment_length)
+        .map(|chunk| {
+            let mut fragment = chunk.to_vec();
+            fragment.resize(fragment_length, 0);
+            fragment
+        })
+        .collect()
+}
+
+#[must_use]
+fn choose_fragments(sequence: usize, fragment_count: usize, checksum: u32) -> Vec<usize> {
+    // invalid parts are rejected by the decoder, but may still be queried
+    if sequence == 0 || fragment_count == 0 {
+        return Vec::new();
+    }
+    if sequence <= fragment_count {
+        return alloc::vec![sequence - 1];
+    }
+
+    let mut xoshiro = part_rng(sequence, checksum);
+    let degree = xoshiro.choose_degree(fragment_count);
+    let indexes = (0..fragment_count).collect();
+    let mut shuffled = xoshiro.shuffled(indexes);
+    shuffled.truncate(degree as usize);
+    shuffled
+}
+
+/// Seeds the RNG which selects the segments of a mixed part.
+fn part_rng(sequence: usize, checksum: u32) -> crate::xoshiro::Xoshiro256 {
+    #[allow(clippy::cast_possible_truncation)]
+    let sequence = sequence as u32;
+
+    let mut seed = [0u8; 8];
+    seed[0..4].copy_from_slice(&sequence.to_be_bytes());
+    seed[4..8].copy_from_slice(&checksum.to_be_bytes());
+
+    crate::xoshiro::Xoshiro256::from(seed.as_slice())
+}
+
+/// Combines the recovered segments into the data of a mixed part, returning the
+/// indexes of the remaining segments and the number of combined segments.
+fn reduce<T: FragmentStorage>(
+    decoded: &T,
+    indexes: &FragmentIndexes,
+    data: &mut [u8],
+) -> (FragmentIndexes, usize) {
+    let mut remaining = FragmentIndexes::default();
+    let mut xors = 0;
+    for idx in indexes.iter() {
+        if let Some(simple) = decoded.get(idx) {
+            xor_into(data, simple);
+            xors += 1;
+        } else {
+            remaining.insert(idx);
+        }
+    }
+    (remaining, xors)
+}
+
+/// A set of message segment indexes, e.g. the segments combined into a [`Part`]
+/// or the segments recovered by a [`Decoder`].
+///
+/// The set is stored as a bitset, which keeps the sets of long messages compact
+/// and makes combining them cheap. Iteration yields the indexes in ascending
+/// order.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::FragmentIndexes;
+/// let first: FragmentIndexes = [0, 2, 5].into_iter().collect();
+/// let second: FragmentIndexes = [2, 3].into_iter().collect();
+/// assert_eq!(first.union(&second).to_vec(), vec![0, 2, 3, 5]);
+/// assert_eq!(first.intersection(&second).to_vec(), vec![2]);
+/// assert_eq!(first.difference(&second).to_vec(), vec![0, 5]);
+/// assert!(first.contains(5));
+/// assert_eq!(first.len(), 3);
+/// assert_eq!(format!("{first:?}"), "{0, 2, 5}");
+/// ```
+// the bitset has no trailing zero words, such that equal sets compare equal
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FragmentIndexes(Vec<u64>);
+
+impl FragmentIndexes {
+    /// Creates an empty set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let indexes = ur::fountain::FragmentIndexes::new();
+    /// assert!(indexes.is_empty());
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Adds an index to the set, returning whether it wasn't contained yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut indexes = ur::fountain::FragmentIndexes::new();
+    /// assert!(indexes.insert(3));
+    /// assert!(!indexes.insert(3));
+    /// assert!(indexes.remove(3));
+    /// assert!(!indexes.remove(3));
+    /// ```
+    pub fn insert(&mut self, index: usize) -> bool {
+        let word = index / 64;
+        if word >= self.0.len() {
+            self.0.resize(word + 1, 0);
+        }
+        let inserted = self.0[word] & (1 << (index % 64)) == 0;
+        self.0[word] |= 1 << (index % 64);
+        inserted
+    }
+
+    /// Removes an index from the set, returning whether it was contained.
+    ///
+    /// # Examples
+    ///
+    /// See [`FragmentIndexes::insert`] for an example.
+    pub fn remove(&mut self, index: usize) -> bool {
+        let removed = self.contains(index);
+        if let Some(word) = self.0.get_mut(index / 64) {
+            *word &= !(1 << (index % 64));
+        }
+        self.trim();
+        removed
+    }
+
+    /// Returns whether the set contains the index.
+    ///
+    /// # Examples
+    ///
+    /// See the [`FragmentIndexes`] documentation for an example.
+    #[must_use]
+    pub fn contains(&self, index: usize) -> bool {
+        matches!(self.0.get(index / 64), Some(word) if word & (1 << (index % 64)) != 0)
+    }
+
+    /// Returns the number of indexes in the set.
+    ///
+    /// # Examples
+    ///
+    /// See the [`FragmentIndexes`] documentation for an example.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Returns whether the set is empty.
+    ///
+    /// # Examples
+    ///
+    /// See [`FragmentIndexes::new`] for an example.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the indexes in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let indexes: ur::fountain::FragmentIndexes = [70, 1].into_iter().collect();
+    /// assert!(indexes.iter().eq([1, 70]));
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(word, &bits)| {
+            (0..64)
+                .filter(move |bit| bits & (1 << bit) != 0)
+                .map(move |bit| word * 64 + bit)
+        })
+    }
+
+    /// Returns the indexes in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// See the [`FragmentIndexes`] documentation for an example.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<usize> {
+        self.iter().collect()
+    }
+
+    /// Returns the indexes contained in either set.
+    ///
+    /// # Examples
+    ///
+    /// See the [`FragmentIndexes`] documentation for an example.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let (mut union, shorter) = if self.0.len() >= other.0.len() {
+            (self.clone(), other)
+        } else {
+            (other.clone(), self)
+        };
+        for (word, &bits) in union.0.iter_mut().zip(&shorter.0) {
+            *word |= bits;
+        }
+        union
+    }
+
+    /// Returns the indexes contained in both sets.
+    ///
+    /// # Examples
+    ///
+    /// See the [`FragmentIndexes`] documentation for an example.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Self(self.0.iter().zip(&other.0).map(|(a, b)| a & b).collect());
+        intersection.trim();
+        intersection
+    }
+
+    /// Returns the indexes contained in this set but not in the other.
+    ///
+    /// # Examples
+    ///
+    /// See the [`FragmentIndexes`] documentation for an example.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for (word, &bits) in difference.0.iter_mut().zip(&other.0) {
+            *word &= !bits;
+        }
+        difference.trim();
+        difference
+    }
+
+    /// Toggles the indexes contained in `other`, i.e. replaces the set with the
+    /// symmetric difference of both sets.
+    fn toggle_all(&mut self, other: &Self) {
+        if other.0.len() > self.0.len() {
+            self.0.resize(other.0.len(), 0);
+        }
+        for (word, &bits) in self.0.iter_mut().zip(&other.0) {
+            *word ^= bits;
+        }
+        self.trim();
+    }
+
+    /// Returns the index if the set consists of exactly one index.
+    fn single(&self) -> Option<usize> {
+        let mut iter = self.iter();
+        match (iter.next(), iter.next()) {
+            (Some(index), None) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Removes trailing zero words.
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+}
+
+impl core::fmt::Debug for FragmentIndexes {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for FragmentIndexes {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{}", self.to_vec().as_slice());
+    }
+}
+
+impl FromIterator<usize> for FragmentIndexes {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut indexes = Self::default();
+        indexes.extend(iter);
+        indexes
+    }
+}
+
+impl Extend<usize> for FragmentIndexes {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            self.insert(index);
+        }
+    }
+}
+
+/// Returns the bytewise xor of two equally long slices, which is how segments
+/// are combined into mixed parts.
+///
+/// # Examples
+///
+/// See the [`crate::fountain`] module documentation for an example.
+///
+/// # Panics
+///
+/// This panics if the slices differ in length.
+#[must_use]
+pub fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut combined = a.to_vec();
+    xor_into(&mut combined, b);
+    combined
+}
+
+/// Combines `source` into `target` by xor-ing them bytewise in place, e.g. to
+/// remove a recovered segment from the data of a mixed part.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{xor_into, Encoder};
+/// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+/// let mixed = encoder.part_at(5);
+/// assert_eq!(mixed.indexes().to_vec(), vec![0, 1, 2]);
+/// let mut data = mixed.data().to_vec();
+/// xor_into(&mut data, encoder.part_at(1).data());
+/// xor_into(&mut data, encoder.part_at(2).data());
+/// assert_eq!(data, encoder.part_at(3).data());
+/// ```
+///
+/// # Panics
+///
+/// This panics if the slices differ in length.
+pub fn xor_into(target: &mut [u8], source: &[u8]) {
+    assert_eq!(
+        target.len(),
+        source.len(),
+        "slices must have the same length"
+    );
+    for (x1, &x2) in target.iter_mut().zip(source) {
+        *x1 ^= x2;
+    }
+}
+
+/// The persisted state of a [`Decoder`]. Only the data of buffered and
+/// decoded parts is relevant, their remaining attributes follow from the
+/// decoder metadata.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DecoderState<
+    Decoded = Vec<(usize, Vec<u8>)>,
+    Buffered = Vec<(Vec<usize>, Vec<u8>)>,
+    Received = alloc::collections::btree_set::BTreeSet<Vec<usize>>,
+> {
+    sequence_count: usize,
+    message_length: usize,
+    checksum: u32,
+    fragment_length: usize,
+    processed_parts_count: usize,
+    decoded: Decoded,
+    buffer: Buffered,
+    received: Received,
+}
+
+/// Serializes the decoder state, so that a partially complete transfer can be
+/// persisted and resumed later. The [`PartScheduler`], [`Limits`] and expected
+/// checksum are not part of the state. Deserialized decoders have the default
+/// [`Limits`], which the state is checked against before allocating any
+/// storage for it.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::{Decoder, Encoder};
+/// let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+/// let mut decoder = Decoder::default();
+/// decoder.receive(encoder.next_part()).unwrap();
+///
+/// let state = serde_json::to_string(&decoder).unwrap();
+/// let mut decoder: Decoder = serde_json::from_str(&state).unwrap();
+/// decoder.receive(encoder.next_part()).unwrap();
+/// decoder.receive(encoder.next_part()).unwrap();
+/// assert_eq!(decoder.message().unwrap().as_deref(), Some(&b"Ten chars!"[..]));
+/// ```
+#[cfg(feature = "serde")]
+impl<S, T: FragmentStorage> serde::Serialize for Decoder<S, T> {
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        DecoderState {
+            sequence_count: self.sequence_count,
+            message_length: self.message_length,
+            checksum: self.checksum,
+            fragment_length: self.fragment_length,
+            processed_parts_count: self.processed_parts_count,
+            decoded: (0..self.sequence_count)
+                .filter_map(|index| Some((index, self.storage.get(index)?)))
+                .collect::<Vec<_>>(),
+            buffer: self
+                .buffer
+                .values()
+                .map(|(indexes, data)| (indexes.iter().collect(), data))
+                .collect::<Vec<(Vec<usize>, _)>>(),
+            received: self
+                .received
+                .iter()
+                .map(FragmentIndexes::to_vec)
+                .collect::<Vec<_>>(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, S: PartScheduler + Default, T: FragmentStorage + Default> serde::Deserialize<'de>
+    for Decoder<S, T>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state: DecoderState = serde::Deserialize::deserialize(deserializer)?;
+        let consistent = state
+            .decoded
+            .iter()
+            .map(|(index, data)| (core::slice::from_ref(index), data))
+            .chain(
+                state
+                    .buffer
+                    .iter()
+                    .map(|(indexes, data)| (indexes.as_slice(), data)),
+            )
+            .all(|(indexes, data)| {
+                data.len() == state.fragment_length
+                    && !indexes.is_empty()
+                    && indexes.iter().all(|&index| index < state.sequence_count)
+            })
+            && state
+                .received
+                .iter()
+                .flatten()
+                .all(|&index| index < state.sequence_count);
+        if !consistent {
+            return Err(serde::de::Error::custom("inconsistent decoder state"));
+        }
+
+        let mut decoder = Decoder::default()
+            .with_scheduler(S::default())
+            .with_storage(T::default());
+        // The limits aren't persisted, so the untrusted state has to satisfy
+        // the default ones before any storage is allocated for it.
+        let limits = decoder.limits;
+        let buffered =
+            (state.decoded.len() + state.buffer.len()).checked_mul(state.fragment_length);
+        if state.sequence_count > limits.max_fragment_count
+            || state.fragment_length > limits.max_buffered_bytes
+            || !matches!(buffered, Some(buffered) if buffered <= limits.max_buffered_bytes)
+        {
+            return Err(serde::de::Error::custom(Error::LimitExceeded));
+        }
+        let total = state.sequence_count.checked_mul(state.fragment_length);
+        if matches!(total, Some(total) if total < state.message_length) {
+            return Err(serde::de::Error::custom(Error::InvalidMessageLength));
+        }
+        decoder
+            .storage
+            .reset(state.sequence_count, state.fragment_length)
+            .map_err(serde::de::Error::custom)?;
+        decoder.sequence_count = state.sequence_count;
+        for (index, data) in state.decoded {
+            if decoder.storage.insert(index, data) {
+                decoder.decoded_count += 1;
+            }
+        }
+        for (indexes, data) in state.buffer {
+            decoder.buffer_or_process(indexes.into_iter().collect(), data);
+        }
+        decoder
+            .process_queue()
+            .map_err(|_| serde::de::Error::custom("inconsistent decoder state"))?;
+        decoder.received = state
+            .received
+            .into_iter()
+            .map(|indexes| indexes.into_iter().collect())
+            .collect();
+        decoder.sequence_count = state.sequence_count;
+        decoder.message_length = state.message_length;
+        decoder.checksum = state.checksum;
+        decoder.fragment_length = state.fragment_length;
+        decoder.processed_parts_count = state.processed_parts_count;
+        Ok(decoder)
+    }
 }
 
-#[must_use]
-pub(crate) fn partition(mut data: Vec<u8>, fragment_length: usize) -> Vec<Vec<u8>> {
-    let mut padding =
-        alloc::vec![0; (fragment_length - (data.len() % fragment_length)) % fragment_length];
-    data.append(&mut padding);
-    data.chunks(fragment_length).map(<[u8]>::to_vec).collect()
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fragment_length() {
+        assert_eq!(fragment_length(12345, 1955), 1764);
+        assert_eq!(fragment_length(12345, 30000), 12345);
+
+        assert_eq!(fragment_length(10, 4), 4);
+        assert_eq!(fragment_length(10, 5), 5);
+        assert_eq!(fragment_length(10, 6), 5);
+        assert_eq!(fragment_length(10, 10), 10);
+    }
+
+    #[test]
+    fn test_bounded_fragment_length() {
+        for (data_length, max) in [(12345, 1955), (12345, 30000), (10, 4), (10, 6)] {
+            assert_eq!(
+                bounded_fragment_length(data_length, 1, max),
+                fragment_length(data_length, max)
+            );
+        }
+        assert_eq!(bounded_fragment_length(1000, 10, 30), 30);
+        assert_eq!(bounded_fragment_length(100, 40, 100), 100);
+        assert_eq!(bounded_fragment_length(25, 10, 12), 13);
+        assert_eq!(bounded_fragment_length(5, 10, 20), 5);
+        assert_eq!(bounded_fragment_length(5, 10, 10), 5);
+    }
+
+    #[test]
+    fn test_partition_and_join() {
+        let join = |data: Vec<Vec<u8>>, message_length: usize| {
+            let mut flattened: Vec<u8> = data.into_iter().flatten().collect();
+            flattened.truncate(message_length);
+            flattened
+        };
+
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let fragment_length = fragment_length(message.len(), 100);
+        let fragments = partition(&message, fragment_length);
+        let expected_fragments = vec![
+            "916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3ccba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a8cde6d0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f965e25ee29039f",
+            "df8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3ec4bbff1b9ffe8a9e7240129377b9d3711ed38d412fbb4442256f1e6f595e0fc57fed451fb0a0101fb76b1fb1e1b88cfdfdaa946294a47de8fff173f021c0e6f65b05c0a494e50791",
+            "270a0050a73ae69b6725505a2ec8a5791457c9876dd34aadd192a53aa0dc66b556c0c215c7ceb8248b717c22951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d41977fa6f78dc07eecd072aae5bc8a852397e06034dba6a0b570",
+            "797c3a89b16673c94838d884923b8186ee2db5c98407cab15e13678d072b43e406ad49477c2e45e85e52ca82a94f6df7bbbe7afbed3a3a830029f29090f25217e48d1f42993a640a67916aa7480177354cc7440215ae41e4d02eae9a1912",
+            "33a6d4922a792c1b7244aa879fefdb4628dc8b0923568869a983b8c661ffab9b2ed2c149e38d41fba090b94155adbed32f8b18142ff0d7de4eeef2b04adf26f2456b46775c6c20b37602df7da179e2332feba8329bbb8d727a138b4ba7a5",
+            "03215eda2ef1e953d89383a382c11d3f2cad37a4ee59a91236a3e56dcf89f6ac81dd4159989c317bd649d9cbc617f73fe10033bd288c60977481a09b343d3f676070e67da757b86de27bfca74392bac2996f7822a7d8f71a489ec6180390",
+            "089ea80a8fcd6526413ec6c9a339115f111d78ef21d456660aa85f790910ffa2dc58d6a5b93705caef1091474938bd312427021ad1eeafbd19e0d916ddb111fabd8dcab5ad6a6ec3a9c6973809580cb2c164e26686b5b98cfb017a337968",
+            "c7daaa14ae5152a067277b1b3902677d979f8e39cc2aafb3bc06fcf69160a853e6869dcc09a11b5009f91e6b89e5b927ab1527a735660faa6012b420dd926d940d742be6a64fb01cdc0cff9faa323f02ba41436871a0eab851e7f5782d10",
+            "fbefde2a7e9ae9dc1e5c2c48f74f6c824ce9ef3c89f68800d44587bedc4ab417cfb3e7447d90e1e417e6e05d30e87239d3a5d1d45993d4461e60a0192831640aa32dedde185a371ded2ae15f8a93dba8809482ce49225daadfbb0fec629e",
+            "23880789bdf9ed73be57fa84d555134630e8d0f7df48349f29869a477c13ccca9cd555ac42ad7f568416c3d61959d0ed568b2b81c7771e9088ad7fd55fd4386bafbf5a528c30f107139249357368ffa980de2c76ddd9ce4191376be0e6b5",
+            "170010067e2e75ebe2d2904aeb1f89d5dc98cd4a6f2faaa8be6d03354c990fd895a97feb54668473e9d942bb99e196d897e8f1b01625cf48a7b78d249bb4985c065aa8cd1402ed2ba1b6f908f63dcd84b66425df00000000000000000000"
+        ];
+        assert_eq!(fragments.len(), expected_fragments.len());
+        for (fragment, expected_fragment) in fragments.iter().zip(expected_fragments) {
+            assert_eq!(hex::encode(fragment), expected_fragment);
+        }
+        let rejoined = join(fragments, message.len());
+        assert_eq!(rejoined, message);
+    }
+
+    #[test]
+    fn test_choose_fragments() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let checksum = crate::CRC32.checksum(&message);
+        let fragment_length = crate::fountain::fragment_length(message.len(), 100);
+        let fragments = crate::fountain::partition(&message, fragment_length);
+        let expected_fragment_indexes = vec![
+            vec![0],
+            vec![1],
+            vec![2],
+            vec![3],
+            vec![4],
+            vec![5],
+            vec![6],
+            vec![7],
+            vec![8],
+            vec![9],
+            vec![10],
+            vec![9],
+            vec![2, 5, 6, 8, 9, 10],
+            vec![8],
+            vec![1, 5],
+            vec![1],
+            vec![0, 2, 4, 5, 8, 10],
+            vec![5],
+            vec![2],
+            vec![2],
+            vec![0, 1, 3, 4, 5, 7, 9, 10],
+            vec![0, 1, 2, 3, 5, 6, 8, 9, 10],
+            vec![0, 2, 4, 5, 7, 8, 9, 10],
+            vec![3, 5],
+            vec![4],
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            vec![0, 1, 3, 4, 5, 6, 7, 9, 10],
+            vec![6],
+            vec![5, 6],
+            vec![7],
+        ];
+        for seq_num in 1..=30 {
+            let mut indexes = crate::fountain::choose_fragments(seq_num, fragments.len(), checksum);
+            indexes.sort_unstable();
+            assert_eq!(indexes, expected_fragment_indexes[seq_num - 1]);
+        }
+    }
+
+    #[test]
+    fn test_xor() {
+        let mut rng = crate::xoshiro::Xoshiro256::from("Wolf");
+
+        let data1 = rng.next_bytes(10);
+        assert_eq!(hex::encode(&data1), "916ec65cf77cadf55cd7");
+
+        let data2 = rng.next_bytes(10);
+        assert_eq!(hex::encode(&data2), "f9cda1a1030026ddd42e");
+
+        let mut data3 = data1.clone();
+        xor_into(&mut data3, &data2);
+        assert_eq!(hex::encode(&data3), "68a367fdf47c8b2888f9");
+
+        xor_into(&mut data3, &data1);
+        assert_eq!(hex::encode(data3), hex::encode(&data2));
+        assert_eq!(xor(&xor(&data1, &data2), &data1), data2);
+    }
+
+    #[test]
+    #[should_panic(expected = "slices must have the same length")]
+    fn test_xor_length_mismatch() {
+        xor_into(&mut [0; 3], &[0; 4]);
+    }
+
+    #[test]
+    fn test_fountain_encoder() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        let expected_parts = [
+            "916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3c",
+            "cba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a",
+            "8cde6d0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f",
+            "965e25ee29039fdf8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3e",
+            "c4bbff1b9ffe8a9e7240129377b9d3711ed38d412fbb4442256f1e6f59",
+            "5e0fc57fed451fb0a0101fb76b1fb1e1b88cfdfdaa946294a47de8fff1",
+            "73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
+            "791457c9876dd34aadd192a53aa0dc66b556c0c215c7ceb8248b717c22",
+            "951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d0000000000",
+            "330f0f33a05eead4f331df229871bee733b50de71afd2e5a79f196de09",
+            "3b205ce5e52d8c24a52cffa34c564fa1af3fdffcd349dc4258ee4ee828",
+            "dd7bf725ea6c16d531b5f03254783803048ca08b87148daacd1cd7a006",
+            "760be7ad1c6187902bbc04f539b9ee5eb8ea6833222edea36031306c01",
+            "5bf4031217d2c3254b088fa7553778b5003632f46e21db129416f65b55",
+            "73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
+            "b8546ebfe2048541348910267331c643133f828afec9337c318f71b7df",
+            "23dedeea74e3a0fb052befabefa13e2f80e4315c9dceed4c8630612e64",
+            "d01a8daee769ce34b6b35d3ca0005302724abddae405bdb419c0a6b208",
+            "3171c5dc365766eff25ae47c6f10e7de48cfb8474e050e5fe997a6dc24",
+            "e055c2433562184fa71b4be94f262e200f01c6f74c284b0dc6fae6673f",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, data)| super::Part {
+            sequence: i + 1,
+            sequence_count: 9,
+            message_length: 256,
+            checksum: 23_570_951,
+            data: hex::decode(data).unwrap(),
+        });
+        for (sequence, e) in expected_parts.into_iter().enumerate() {
+            assert_eq!(encoder.current_sequence(), sequence);
+            assert_eq!(encoder.next_part(), e);
+        }
+    }
+
+    #[test]
+    fn test_fountain_encoder_cbor() {
+        let max_fragment_length = 30;
+        let size = 256;
+        let message = crate::xoshiro::test_utils::make_message("Wolf", size);
+        let mut encoder = Encoder::new(&message, max_fragment_length).unwrap();
+        let expected_parts = vec![
+            "8501091901001a0167aa07581d916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3c",
+            "8502091901001a0167aa07581dcba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a",
+            "8503091901001a0167aa07581d8cde6d0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f",
+            "8504091901001a0167aa07581d965e25ee29039fdf8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3e",
+            "8505091901001a0167aa07581dc4bbff1b9ffe8a9e7240129377b9d3711ed38d412fbb4442256f1e6f59",
+            "8506091901001a0167aa07581d5e0fc57fed451fb0a0101fb76b1fb1e1b88cfdfdaa946294a47de8fff1",
+            "8507091901001a0167aa07581d73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
+            "8508091901001a0167aa07581d791457c9876dd34aadd192a53aa0dc66b556c0c215c7ceb8248b717c22",
+            "8509091901001a0167aa07581d951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d0000000000",
+            "850a091901001a0167aa07581d330f0f33a05eead4f331df229871bee733b50de71afd2e5a79f196de09",
+            "850b091901001a0167aa07581d3b205ce5e52d8c24a52cffa34c564fa1af3fdffcd349dc4258ee4ee828",
+            "850c091901001a0167aa07581ddd7bf725ea6c16d531b5f03254783803048ca08b87148daacd1cd7a006",
+            "850d091901001a0167aa07581d760be7ad1c6187902bbc04f539b9ee5eb8ea6833222edea36031306c01",
+            "850e091901001a0167aa07581d5bf4031217d2c3254b088fa7553778b5003632f46e21db129416f65b55",
+            "850f091901001a0167aa07581d73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
+            "8510091901001a0167aa07581db8546ebfe2048541348910267331c643133f828afec9337c318f71b7df",
+            "8511091901001a0167aa07581d23dedeea74e3a0fb052befabefa13e2f80e4315c9dceed4c8630612e64",
+            "8512091901001a0167aa07581dd01a8daee769ce34b6b35d3ca0005302724abddae405bdb419c0a6b208",
+            "8513091901001a0167aa07581d3171c5dc365766eff25ae47c6f10e7de48cfb8474e050e5fe997a6dc24",
+            "8514091901001a0167aa07581de055c2433562184fa71b4be94f262e200f01c6f74c284b0dc6fae6673f",
+        ];
+        assert_eq!(encoder.fragment_count(), size / max_fragment_length + 1);
+        for e in expected_parts {
+            assert_eq!(hex::encode(encoder.next_part().cbor().unwrap()), e);
+        }
+    }
+
+    #[test]
+    fn test_part_to_diagnostic() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        assert_eq!(
+            encoder.next_part().to_diagnostic(),
+            "[1, 9, 256, 0x0167aa07, h'916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3c']"
+        );
+        let part = Part::new(1, 1, 1, 0, vec![]);
+        assert_eq!(part.to_diagnostic(), "[1, 1, 1, 0x00000000, h'']");
+    }
+
+    #[test]
+    fn test_fountain_encoder_fragments() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let encoder = Encoder::new(&message, 100).unwrap();
+        let fragments = partition(&message, encoder.fragment_length);
+        assert_eq!(encoder.fragment_count(), fragments.len());
+        for (index, fragment) in fragments.iter().enumerate() {
+            let unpadded = encoder.fragment(index);
+            assert_eq!(unpadded, &fragment[..unpadded.len()]);
+            assert!(fragment[unpadded.len()..].iter().all(|&b| b == 0));
+        }
+        assert_eq!(encoder.fragment(10).len(), 84);
+        assert_eq!(encoder.fragment_length(), 94);
+        assert_eq!(encoder.padding_length(), 10);
+        let exact = Encoder::new(&[0; 100], 10).unwrap();
+        assert_eq!(exact.padding_length(), 0);
+    }
+
+    #[test]
+    fn test_fountain_encoder_zero_max_length() {
+        assert!(matches!(
+            Encoder::new(b"foo", 0),
+            Err(Error::InvalidFragmentLen)
+        ));
+    }
+
+    #[test]
+    fn test_fountain_encoder_invalid_bounds() {
+        assert!(matches!(
+            Encoder::new_with_bounds(b"foo", 0, 10),
+            Err(Error::InvalidFragmentLen)
+        ));
+        assert!(matches!(
+            Encoder::new_with_bounds(b"foo", 11, 10),
+            Err(Error::InvalidFragmentLen)
+        ));
+        assert!(matches!(
+            Encoder::new_with_bounds(b"", 1, 10),
+            Err(Error::EmptyMessage)
+        ));
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut bounded = Encoder::new_with_bounds(&message, 10, 30).unwrap();
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        for _ in 0..20 {
+            assert_eq!(bounded.next_part(), encoder.next_part());
+        }
+    }
+
+    #[test]
+    fn test_fountain_encoder_from_source() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let shared: alloc::sync::Arc<[u8]> = message.clone().into();
+        let mut borrowed = Encoder::from_source(message.as_slice(), 30).unwrap();
+        let mut shared = Encoder::from_source(shared, 30).unwrap();
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        for _ in 0..20 {
+            let part = encoder.next_part();
+            assert_eq!(borrowed.next_part(), part);
+            assert_eq!(shared.next_part(), part);
+        }
+        assert!(matches!(
+            Encoder::from_source(&[][..], 30),
+            Err(Error::EmptyMessage)
+        ));
+
+        // a source which shrinks after constructing the encoder
+        struct Shrinking(Vec<u8>, core::cell::Cell<usize>);
+        impl AsRef<[u8]> for Shrinking {
+            fn as_ref(&self) -> &[u8] {
+                &self.0[..self.1.get()]
+            }
+        }
+        let source = Shrinking(message.clone(), core::cell::Cell::new(message.len()));
+        let mut encoder = Encoder::from_source(source, 30).unwrap();
+        encoder.message.1.set(100);
+        for _ in 0..20 {
+            let part = encoder.next_part();
+            assert_eq!(part.data().len(), encoder.fragment_length());
+        }
+    }
+
+    #[test]
+    fn test_fountain_encoder_set_sequence() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        let parts: Vec<Part> = (0..20).map(|_| encoder.next_part()).collect();
+        for sequence in [0, 3, 9, 15] {
+            let mut resumed = Encoder::new(&message, 30).unwrap();
+            resumed.set_sequence(sequence);
+            assert_eq!(resumed.current_sequence(), sequence);
+            assert_eq!(resumed.complete(), sequence >= resumed.fragment_count());
+            for part in &parts[sequence..] {
+                assert_eq!(&resumed.next_part(), part);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fountain_encoder_part_at() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        let parts: Vec<Part> = (1..=20).rev().map(|seq| encoder.part_at(seq)).collect();
+        assert_eq!(encoder.current_sequence(), 0);
+        for part in parts.iter().rev() {
+            assert_eq!(&encoder.next_part(), part);
+        }
+    }
 
-#[must_use]
-fn choose_fragments(sequence: usize, fragment_count: usize, checksum: u32) -> Vec<usize> {
-    if sequence <= fragment_count {
-        return alloc::vec![sequence - 1];
+    #[test]
+    fn test_fountain_encoder_indexes_for() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        for sequence in 1..=50 {
+            let indexes = encoder.indexes_for(sequence);
+            let part = encoder.next_part();
+            assert_eq!(indexes, part.indexes());
+        }
+        assert_eq!(encoder.current_sequence(), 50);
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    let sequence = sequence as u32;
+    #[test]
+    fn test_fountain_encoder_complete_with_redundancy() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        assert_eq!(encoder.fragment_count(), 9);
+        assert!(!encoder.complete_with_redundancy(0.0));
+        assert!(!encoder.complete_with_redundancy(-1.0));
+        encoder.set_sequence(9);
+        assert!(encoder.complete_with_redundancy(0.5));
+        assert!(encoder.complete_with_redundancy(f64::NAN));
+        encoder.set_sequence(13);
+        assert!(!encoder.complete_with_redundancy(1.5));
+        assert!(encoder.complete_with_redundancy(1.0));
+        assert_eq!(encoder.complete(), encoder.complete_with_redundancy(1.0));
+        encoder.next_part();
+        assert!(encoder.complete_with_redundancy(1.5));
+    }
 
-    let mut seed = [0u8; 8];
-    seed[0..4].copy_from_slice(&sequence.to_be_bytes());
-    seed[4..8].copy_from_slice(&checksum.to_be_bytes());
+    #[test]
+    fn test_fountain_encoder_prioritize() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        for _ in 0..encoder.fragment_count() {
+            encoder.next_part();
+        }
+        // out of range and duplicate indexes are ignored
+        encoder.prioritize([7, 2, 100, 7]);
+        let missing: FragmentIndexes = [2, 7].into_iter().collect();
+        let mut recovered = Vec::new();
+        for _ in 0..4 {
+            let hits = encoder.next_part().indexes().intersection(&missing);
+            assert_eq!(hits.len(), 1);
+            recovered.extend(hits.iter());
+        }
+        // cycles through the missing segments
+        recovered[..2].sort_unstable();
+        recovered[2..].sort_unstable();
+        assert_eq!(recovered, [2, 7, 2, 7]);
+        let skipped = encoder.current_sequence();
+        assert!(skipped > encoder.fragment_count() + 4);
 
-    let mut xoshiro = crate::xoshiro::Xoshiro256::from(seed.as_slice());
-    let degree = xoshiro.choose_degree(fragment_count);
-    let indexes = (0..fragment_count).collect();
-    let mut shuffled = xoshiro.shuffled(indexes);
-    shuffled.truncate(degree as usize);
-    shuffled
-}
+        encoder.prioritize([]);
+        let mut regular = Encoder::new(&message, 30).unwrap();
+        regular.set_sequence(skipped);
+        for _ in 0..10 {
+            assert_eq!(encoder.next_part(), regular.next_part());
+        }
 
-fn xor(v1: &mut [u8], v2: &[u8]) {
-    debug_assert_eq!(v1.len(), v2.len());
+        // no part combines exactly one of the missing segments
+        struct All;
+        impl PartScheduler for All {
+            fn choose_fragments(&self, _: usize, fragment_count: usize, _: u32) -> Vec<usize> {
+                (0..fragment_count).collect()
+            }
+        }
+        let mut encoder = Encoder::new(&message, 30).unwrap().with_scheduler(All);
+        encoder.prioritize([2, 7]);
+        assert_eq!(encoder.next_part().sequence(), 1);
+        assert!(encoder.missing.is_empty() && encoder.pending.is_empty());
+        assert_eq!(encoder.next_part().sequence(), 2);
+    }
 
-    for (x1, &x2) in v1.iter_mut().zip(v2.iter()) {
-        *x1 ^= x2;
+    #[test]
+    #[should_panic(expected = "sequence numbers start at one")]
+    fn test_fountain_encoder_part_at_zero() {
+        let _part = Encoder::new(b"data", 3).unwrap().part_at(0);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_fountain_encoder_sequence_wraps() {
+        let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+        encoder.set_sequence(u32::MAX as usize - 1);
+        let last = encoder.next_part();
+        assert_eq!(last.sequence(), u32::MAX as usize);
+        assert_eq!(Part::from_cbor(&last.cbor().unwrap()).unwrap(), last);
+        assert_eq!(encoder.next_part().sequence(), 1);
+        assert_eq!(encoder.current_sequence(), 1);
+    }
 
+    #[cfg(target_pointer_width = "64")]
     #[test]
-    fn test_fragment_length() {
-        assert_eq!(fragment_length(12345, 1955), 1764);
-        assert_eq!(fragment_length(12345, 30000), 12345);
+    #[should_panic(expected = "sequence numbers are limited to 32 bits")]
+    fn test_fountain_encoder_part_at_overflow() {
+        let _part = Encoder::new(b"data", 3)
+            .unwrap()
+            .part_at(u32::MAX as usize + 1);
+    }
 
-        assert_eq!(fragment_length(10, 4), 4);
-        assert_eq!(fragment_length(10, 5), 5);
-        assert_eq!(fragment_length(10, 6), 5);
-        assert_eq!(fragment_length(10, 10), 10);
+    #[test]
+    fn test_fountain_encoder_is_complete() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
+        let mut encoder = Encoder::new(&message, 30).unwrap();
+        for _ in 0..encoder.fragment_count() {
+            encoder.next_part();
+        }
+        assert!(encoder.complete());
     }
 
     #[test]
-    fn test_partition_and_join() {
-        let join = |data: Vec<Vec<u8>>, message_length: usize| {
-            let mut flattened: Vec<u8> = data.into_iter().flatten().collect();
-            flattened.truncate(message_length);
-            flattened
-        };
+    fn test_decoder() {
+        let seed = "Wolf";
+        let message_size = 32767;
+        let max_fragment_length = 1000;
+
+        let message = crate::xoshiro::test_utils::make_message(seed, message_size);
+        let mut encoder = Encoder::new(&message, max_fragment_length).unwrap();
+        let mut decoder = Decoder::default();
+        while !decoder.complete() {
+            assert_eq!(decoder.message().unwrap(), None);
+            let part = encoder.next_part();
+            let _next = decoder.receive(part);
+        }
+        assert_eq!(decoder.message().unwrap(), Some(message));
+    }
+
+    #[test]
+    fn test_decoder_progress() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 32767);
+        let mut encoder = Encoder::new(&message, 1000).unwrap();
+        let mut decoder = Decoder::default();
+        assert_eq!(decoder.decoded_count(), 0);
+        assert_eq!(decoder.expected_fragment_count(), None);
+        let mut progress = decoder.estimated_percent_complete();
+        assert!(progress.abs() < f64::EPSILON);
+        while !decoder.complete() {
+            decoder.receive(encoder.next_part()).unwrap();
+            assert_eq!(decoder.expected_fragment_count(), Some(33));
+            assert!(decoder.estimated_percent_complete() >= progress);
+            progress = decoder.estimated_percent_complete();
+            assert!(progress <= 0.99 || decoder.complete());
+        }
+        assert_eq!(decoder.decoded_count(), 33);
+        assert!((decoder.estimated_percent_complete() - 1.0).abs() < f64::EPSILON);
+        assert_eq!(
+            decoder.progress(),
+            Progress {
+                decoded_fragments: 33,
+                total_fragments: Some(33),
+                estimated_percent_complete: 1.0,
+                label: "33/33 fragments".into(),
+            }
+        );
+        decoder.reset();
+        assert_eq!(decoder.progress().label, "0/? fragments");
+    }
+
+    #[test]
+    fn test_decoder_metadata() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let mut decoder = Decoder::default();
+        assert_eq!(decoder.message_length(), None);
+        assert_eq!(decoder.sequence_count(), None);
+        assert_eq!(decoder.fragment_length(), None);
+        assert_eq!(decoder.checksum(), None);
+        decoder.receive(encoder.next_part()).unwrap();
+        assert_eq!(decoder.message_length(), Some(1024));
+        assert_eq!(decoder.sequence_count(), Some(encoder.fragment_count()));
+        assert_eq!(decoder.fragment_length(), Some(94));
+        assert_eq!(decoder.checksum(), Some(crate::CRC32.checksum(&message)));
+        decoder.reset();
+        assert_eq!(decoder.message_length(), None);
+        assert_eq!(decoder.checksum(), None);
+    }
+
+    #[test]
+    fn test_decoder_expected_checksum() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut other = Encoder::new(&message[1..], 100).unwrap();
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let checksum = crate::CRC32.checksum(&message);
+        let mut decoder = Decoder::default().with_expected_checksum(checksum);
+        assert!(!decoder.receive(other.next_part()).unwrap());
+        assert_eq!(decoder.checksum(), None);
+        assert_eq!(decoder.stats(), Stats::default());
+        while !decoder.complete() {
+            assert!(!decoder.receive(other.next_part()).unwrap());
+            decoder.receive(encoder.next_part()).unwrap();
+        }
+        assert_eq!(decoder.message().unwrap(), Some(message.clone()));
+
+        decoder.reset();
+        assert!(!decoder.receive(other.next_part()).unwrap());
+        assert_eq!(decoder.checksum(), None);
+    }
+
+    #[test]
+    fn test_decoder_received_and_missing_indexes() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let mut decoder = Decoder::default();
+        while !decoder.complete() {
+            let part = encoder.next_part();
+            // drop every other simple part
+            if encoder.current_sequence() % 2 == 1 || !part.is_simple() {
+                decoder.receive(part).unwrap();
+            }
+            let received = decoder.received_indexes();
+            let missing = decoder.missing_indexes();
+            assert_eq!(received.len() + missing.len(), 11);
+            assert!(received.iter().all(|idx| !missing.contains(idx)));
+        }
+        assert_eq!(
+            decoder.received_indexes().to_vec(),
+            (0..11).collect::<Vec<_>>()
+        );
+        assert!(decoder.missing_indexes().is_empty());
+    }
+
+    #[test]
+    fn test_custom_scheduler() {
+        struct Pairs;
+
+        impl PartScheduler for Pairs {
+            fn choose_fragments(
+                &self,
+                sequence: usize,
+                fragment_count: usize,
+                _checksum: u32,
+            ) -> Vec<usize> {
+                match (sequence - 1) % fragment_count {
+                    0 => vec![0],
+                    index => vec![index - 1, index],
+                }
+            }
+        }
+
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap().with_scheduler(Pairs);
+        let mut decoder = Decoder::default().with_scheduler(Pairs);
+        decoder.receive(encoder.next_part()).unwrap();
+        let part = encoder.next_part();
+        assert_eq!(part.data()[0], message[0] ^ message[94]);
+        decoder.receive(part).unwrap();
+        while !decoder.complete() {
+            decoder.receive(encoder.next_part()).unwrap();
+        }
+        assert_eq!(encoder.current_sequence(), 11);
+        assert_eq!(decoder.message().unwrap(), Some(message));
+    }
+
+    #[test]
+    fn test_plan() {
+        assert_eq!(
+            plan(20, 0.0, 0.99).unwrap(),
+            PlannedTransmission {
+                parts_to_emit: 20,
+                expected_receive_count: 20,
+            }
+        );
+        let likely = plan(20, 0.3, 0.5).unwrap();
+        let certain = plan(20, 0.3, 0.99).unwrap();
+        assert_eq!(
+            likely.expected_receive_count,
+            certain.expected_receive_count
+        );
+        assert!(likely.expected_receive_count > 20);
+        assert!(likely.parts_to_emit > likely.expected_receive_count);
+        assert!(certain.parts_to_emit > likely.parts_to_emit);
+        assert!(plan(40, 0.6, 0.5).unwrap().parts_to_emit > likely.parts_to_emit);
+
+        for (fragment_count, loss_rate, target) in [
+            (0, 0.3, 0.9),
+            (20, 1.0, 0.9),
+            (20, -0.1, 0.9),
+            (20, f64::NAN, 0.9),
+            (20, 0.3, 0.0),
+            (20, 0.3, 1.1),
+            (20, 0.3, f64::NAN),
+            // beyond the resolution of the estimate
+            (20, 0.3, 0.995),
+            (20, 0.3, 1.0),
+        ] {
+            assert!(matches!(
+                plan(fragment_count, loss_rate, target),
+                Err(Error::InvalidPlan)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_decoder_solve() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 25_000);
+        let mut encoder = Encoder::new(&message, 500).unwrap();
+        let fragment_count = encoder.fragment_count();
+        let mut decoder = Decoder::default();
+        let mut peeling = Decoder::default();
+        // skip the simple parts, such that peeling needs plenty of mixed parts
+        encoder.set_sequence(fragment_count);
+        let mut received = 0;
+        while !decoder.complete() {
+            let part = encoder.next_part();
+            peeling.receive_ref(&part).unwrap();
+            decoder.receive(part).unwrap();
+            received += 1;
+            if received >= fragment_count {
+                decoder.solve().unwrap();
+            }
+        }
+        assert!(received < fragment_count + 10);
+        assert!(!peeling.complete());
+        assert_eq!(decoder.message().unwrap(), Some(message));
+        assert_eq!(decoder.solve().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_decoder_solve_underdetermined() {
+        struct Dependent;
+
+        impl PartScheduler for Dependent {
+            fn choose_fragments(&self, sequence: usize, _: usize, _: u32) -> Vec<usize> {
+                match sequence % 3 {
+                    0 => vec![0, 1],
+                    1 => vec![1, 2],
+                    _ => vec![0, 2],
+                }
+            }
+        }
+
+        let mut encoder = Encoder::new(b"Ten chars!", 4)
+            .unwrap()
+            .with_scheduler(Dependent);
+        let mut decoder = Decoder::default().with_scheduler(Dependent);
+        assert_eq!(decoder.solve().unwrap(), 0);
+        for _ in 0..3 {
+            decoder.receive(encoder.next_part()).unwrap();
+        }
+        assert_eq!(decoder.solve().unwrap(), 0);
+        assert_eq!(decoder.stats().buffered_parts, 3);
+        assert!(!decoder.complete());
+    }
+
+    #[test]
+    fn test_decoder_prune() {
+        /// Combines an even number of segments, such that no segment can ever
+        /// be recovered from the parts.
+        struct Even;
+
+        impl PartScheduler for Even {
+            fn choose_fragments(&self, sequence: usize, _: usize, _: u32) -> Vec<usize> {
+                let mask = (0..=u8::MAX)
+                    .filter(|mask| mask.count_ones() % 2 == 0 && *mask != 0)
+                    .nth(sequence - 1)
+                    .unwrap();
+                (0..8).filter(|bit| mask & (1 << bit) != 0).collect()
+            }
+        }
+
+        let mut encoder = Encoder::new(&[7; 16], 2).unwrap().with_scheduler(Even);
+        let mut decoder = Decoder::default().with_scheduler(Even);
+        for _ in 0..127 {
+            decoder.receive(encoder.next_part()).unwrap();
+            assert!(decoder.stats().buffered_parts <= 16);
+        }
+        assert_eq!(decoder.stats().duplicate_parts, 0);
+        assert_eq!(decoder.decoded_count(), 0);
+        // the parts determine the combination of any two segments
+        let buffered = decoder.stats().buffered_parts;
+        assert_eq!(decoder.prune(), buffered - 7);
+        assert_eq!(decoder.stats().buffered_parts, 7);
+        assert_eq!(decoder.solve().unwrap(), 0);
+        assert!(decoder
+            .pending
+            .values()
+            .flatten()
+            .all(|id| decoder.buffer.contains_key(id)));
+    }
+
+    #[test]
+    fn test_decoder_is_part_useful() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let mut decoder = Decoder::default();
+        let mut other = Encoder::new(b"other", 3).unwrap();
+        assert!(!decoder.is_part_useful(&Part::new(1, 0, 4, 0, vec![0])));
+        while !decoder.complete() {
+            let part = encoder.next_part();
+            let useful = decoder.is_part_useful(&part);
+            let decoded_count = decoder.decoded_count();
+            let buffered_parts = decoder.stats().buffered_parts;
+            decoder.receive(part).unwrap();
+            assert!(!decoder.is_part_useful(&other.next_part()));
+            if !useful {
+                assert_eq!(decoder.decoded_count(), decoded_count);
+                assert_eq!(decoder.stats().buffered_parts, buffered_parts);
+            }
+        }
+        assert!(!decoder.is_part_useful(&encoder.next_part()));
+    }
+
+    #[test]
+    fn test_invalid_part_indexes() {
+        for part in [
+            Part::new(0, 1, 1, 0, vec![1]),
+            Part::new(2, 0, 1, 0, vec![1]),
+        ] {
+            assert!(part.indexes().is_empty());
+            assert_eq!(part.degree(), 0);
+            assert!(!part.is_simple());
+        }
+    }
+
+    #[test]
+    fn test_part_degree() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        for _ in 0..100 {
+            let part = encoder.next_part();
+            assert_eq!(part.degree(), part.indexes().len());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_part_hash() {
+        let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+        let parts: Vec<_> = (0..10).map(|_| encoder.next_part()).collect();
+        let mut set: std::collections::HashSet<_> = parts.iter().cloned().collect();
+        assert_eq!(set.len(), 10);
+        assert!(!set.insert(encoder.part_at(3)));
+        let mut other = parts[0].clone();
+        other.checksum += 1;
+        assert!(set.insert(other));
+    }
+
+    #[test]
+    fn test_fragment_indexes_toggle_all() {
+        let mut indexes: FragmentIndexes = [1, 70].into_iter().collect();
+        indexes.toggle_all(&[1, 2].into_iter().collect());
+        assert_eq!(indexes.iter().collect::<Vec<_>>(), vec![2, 70]);
+        indexes.toggle_all(&[70].into_iter().collect());
+        assert_eq!(indexes, [2].into_iter().collect());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_part_serde() {
+        let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+        let part = encoder.next_part();
+        let value = serde_json::to_value(&part).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "sequence": 1,
+                "sequence_count": 3,
+                "message_length": 10,
+                "checksum": 0xf4eb_56f1_u32,
+                "data": [84, 101, 110, 32],
+            })
+        );
+        assert_eq!(serde_json::from_value::<Part>(value).unwrap(), part);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_decoder_serde() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 32767);
+        let mut encoder = Encoder::new(&message, 1000).unwrap();
+        let mut decoder = Decoder::default();
+        let mut skip = false;
+        while !decoder.complete() {
+            let part = encoder.next_part();
+            if !skip {
+                let state = serde_json::to_string(&decoder).unwrap();
+                decoder = serde_json::from_str(&state).unwrap();
+                decoder.receive(part).unwrap();
+            }
+            skip = !skip;
+        }
+        assert_eq!(decoder.message().unwrap(), Some(message));
+
+        let state = serde_json::to_value(&decoder).unwrap();
+        let mut inconsistent = state.clone();
+        inconsistent["fragment_length"] = 1.into();
+        assert!(serde_json::from_value::<Decoder>(inconsistent).is_err());
+        let mut inconsistent = state.clone();
+        inconsistent["sequence_count"] = 1.into();
+        assert!(serde_json::from_value::<Decoder>(inconsistent).is_err());
+        let mut inconsistent = state.clone();
+        inconsistent["message_length"] = 1_000_000.into();
+        assert!(serde_json::from_value::<Decoder>(inconsistent).is_err());
+        let mut hostile = state;
+        hostile["sequence_count"] = u64::MAX.into();
+        assert!(serde_json::from_value::<Decoder>(hostile).is_err());
+    }
+
+    #[test]
+    fn test_decoder_limits() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let mut decoder = Decoder::default().with_limits(Limits {
+            max_fragment_count: 10,
+            ..Limits::default()
+        });
+        assert!(matches!(
+            decoder.receive(encoder.next_part()),
+            Err(Error::LimitExceeded)
+        ));
+        assert_eq!(decoder.expected_fragment_count(), None);
+
+        // a message which doesn't fit the buffer can never complete
+        let mut decoder = Decoder::default().with_limits(Limits {
+            max_buffered_bytes: 5 * 94,
+            ..Limits::default()
+        });
+        assert!(matches!(
+            decoder.receive(encoder.part_at(1)),
+            Err(Error::LimitExceeded)
+        ));
+        assert_eq!(decoder.expected_fragment_count(), None);
+
+        let mut decoder = Decoder::default().with_limits(Limits {
+            max_fragment_count: 11,
+            max_buffered_bytes: 11 * 94,
+        });
+        for sequence in 1..=5 {
+            assert!(decoder.receive(encoder.part_at(sequence)).unwrap());
+        }
+        // duplicates are still accepted
+        assert!(!decoder.receive(encoder.part_at(3)).unwrap());
+        assert_eq!(decoder.decoded_count(), 5);
+
+        // mixed parts which can't be reduced yet are held in the buffer too
+        let mut decoder = Decoder::default().with_limits(Limits {
+            max_buffered_bytes: 11 * 94,
+            ..Limits::default()
+        });
+        let error = (12..100)
+            .find_map(|sequence| decoder.receive(encoder.part_at(sequence)).err())
+            .unwrap();
+        assert!(matches!(error, Error::LimitExceeded));
+        assert!(!decoder.complete());
+        assert!(decoder.decoded_count() < 11);
+    }
+
+    #[test]
+    fn test_decoder_limits_hostile_fragment_count() {
+        // Segment slots are only committed once the declared message fits the
+        // buffer, so tiny parts can't make the decoder allocate and schedule
+        // millions of segments.
+        let mut decoder = Decoder::default().with_limits(Limits {
+            max_buffered_bytes: 1024,
+            ..Limits::default()
+        });
+        let count = 1 << 26;
+        for sequence in [1, count + 1] {
+            assert!(matches!(
+                decoder.receive(Part::new(sequence, count, count, 0, vec![0])),
+                Err(Error::LimitExceeded)
+            ));
+        }
+        assert_eq!(decoder.expected_fragment_count(), None);
+        assert!(decoder.received.is_empty());
+    }
+
+    #[test]
+    fn test_decoder_reset() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let mut decoder = Decoder::default();
+        while !decoder.complete() {
+            decoder.receive(encoder.next_part()).unwrap();
+        }
+        decoder.reset();
+        assert!(!decoder.complete());
+        assert_eq!(decoder.message().unwrap(), None);
+        assert_eq!(decoder.decoded_count(), 0);
+        assert!(decoder.missing_indexes().is_empty());
+        assert!(decoder.estimated_percent_complete().abs() < f64::EPSILON);
+
+        let message = crate::xoshiro::test_utils::make_message("Fox", 2000);
+        let mut encoder = Encoder::new(&message, 300).unwrap();
+        while !decoder.complete() {
+            decoder.receive(encoder.next_part()).unwrap();
+        }
+        assert_eq!(decoder.message().unwrap(), Some(message));
+    }
+
+    #[test]
+    fn test_decoder_feed_and_extend() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let parts: Vec<Part> = (0..30).map(|_| encoder.next_part()).collect();
+
+        let mut decoder = Decoder::default();
+        assert_eq!(decoder.feed(parts[..5].to_vec()).unwrap(), None);
+        assert_eq!(decoder.feed(parts.clone()).unwrap(), Some(message.clone()));
+
+        let mut other = Encoder::new(b"other", 2).unwrap();
+        let mut decoder = Decoder::default();
+        let noisy = parts[..1]
+            .iter()
+            .cloned()
+            .chain(core::iter::once(other.next_part()))
+            .chain(parts[1..].iter().cloned());
+        decoder.extend(noisy);
+        assert!(decoder.complete());
+        assert_eq!(decoder.feed(Vec::new()).unwrap(), Some(message));
+    }
 
+    #[test]
+    fn test_decoder_merge() {
         let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
-        let fragment_length = fragment_length(message.len(), 100);
-        let fragments = partition(message.clone(), fragment_length);
-        let expected_fragments = vec![
-            "916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3ccba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a8cde6d0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f965e25ee29039f",
-            "df8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3ec4bbff1b9ffe8a9e7240129377b9d3711ed38d412fbb4442256f1e6f595e0fc57fed451fb0a0101fb76b1fb1e1b88cfdfdaa946294a47de8fff173f021c0e6f65b05c0a494e50791",
-            "270a0050a73ae69b6725505a2ec8a5791457c9876dd34aadd192a53aa0dc66b556c0c215c7ceb8248b717c22951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d41977fa6f78dc07eecd072aae5bc8a852397e06034dba6a0b570",
-            "797c3a89b16673c94838d884923b8186ee2db5c98407cab15e13678d072b43e406ad49477c2e45e85e52ca82a94f6df7bbbe7afbed3a3a830029f29090f25217e48d1f42993a640a67916aa7480177354cc7440215ae41e4d02eae9a1912",
-            "33a6d4922a792c1b7244aa879fefdb4628dc8b0923568869a983b8c661ffab9b2ed2c149e38d41fba090b94155adbed32f8b18142ff0d7de4eeef2b04adf26f2456b46775c6c20b37602df7da179e2332feba8329bbb8d727a138b4ba7a5",
-            "03215eda2ef1e953d89383a382c11d3f2cad37a4ee59a91236a3e56dcf89f6ac81dd4159989c317bd649d9cbc617f73fe10033bd288c60977481a09b343d3f676070e67da757b86de27bfca74392bac2996f7822a7d8f71a489ec6180390",
-            "089ea80a8fcd6526413ec6c9a339115f111d78ef21d456660aa85f790910ffa2dc58d6a5b93705caef1091474938bd312427021ad1eeafbd19e0d916ddb111fabd8dcab5ad6a6ec3a9c6973809580cb2c164e26686b5b98cfb017a337968",
-            "c7daaa14ae5152a067277b1b3902677d979f8e39cc2aafb3bc06fcf69160a853e6869dcc09a11b5009f91e6b89e5b927ab1527a735660faa6012b420dd926d940d742be6a64fb01cdc0cff9faa323f02ba41436871a0eab851e7f5782d10",
-            "fbefde2a7e9ae9dc1e5c2c48f74f6c824ce9ef3c89f68800d44587bedc4ab417cfb3e7447d90e1e417e6e05d30e87239d3a5d1d45993d4461e60a0192831640aa32dedde185a371ded2ae15f8a93dba8809482ce49225daadfbb0fec629e",
-            "23880789bdf9ed73be57fa84d555134630e8d0f7df48349f29869a477c13ccca9cd555ac42ad7f568416c3d61959d0ed568b2b81c7771e9088ad7fd55fd4386bafbf5a528c30f107139249357368ffa980de2c76ddd9ce4191376be0e6b5",
-            "170010067e2e75ebe2d2904aeb1f89d5dc98cd4a6f2faaa8be6d03354c990fd895a97feb54668473e9d942bb99e196d897e8f1b01625cf48a7b78d249bb4985c065aa8cd1402ed2ba1b6f908f63dcd84b66425df00000000000000000000"
-        ];
-        assert_eq!(fragments.len(), expected_fragments.len());
-        for (fragment, expected_fragment) in fragments.iter().zip(expected_fragments) {
-            assert_eq!(hex::encode(fragment), expected_fragment);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        encoder.set_sequence(encoder.fragment_count());
+        let mut decoders = [Decoder::default(), Decoder::default()];
+        let mut single = Decoder::default();
+        let mut merged = Decoder::default();
+        while !single.complete() {
+            for decoder in &mut decoders {
+                let part = encoder.next_part();
+                single.receive_ref(&part).unwrap();
+                decoder.receive(part).unwrap();
+            }
+            merged = Decoder::default();
+            merged.merge(&decoders[0]).unwrap();
+            merged.merge(&decoders[1]).unwrap();
+            assert_eq!(merged.complete(), single.complete());
         }
-        let rejoined = join(fragments, message.len());
-        assert_eq!(rejoined, message);
+        assert!(!decoders[0].complete() && !decoders[1].complete());
+        assert_eq!(merged.message().unwrap(), Some(message));
+        let dealt = decoders[0].stats().parts_received + decoders[1].stats().parts_received;
+        assert_eq!(merged.stats().parts_received, dealt);
+
+        // merging a decoder into itself only records duplicates
+        let mut copy = Decoder::default();
+        copy.merge(&decoders[0]).unwrap();
+        copy.merge(&decoders[0]).unwrap();
+        assert_eq!(copy.decoded_count(), decoders[0].decoded_count());
+        assert_eq!(copy.stats().duplicate_parts, decoders[0].received.len());
+
+        let mut other = Decoder::default();
+        other
+            .receive(Encoder::new(b"other", 2).unwrap().next_part())
+            .unwrap();
+        assert!(matches!(copy.merge(&other), Err(Error::InconsistentPart)));
+        let mut expecting = Decoder::default().with_expected_checksum(0);
+        expecting.merge(&other).unwrap();
+        assert!(expecting.received.is_empty());
+        let mut limited = Decoder::default().with_limits(Limits {
+            max_buffered_bytes: 100,
+            ..Limits::default()
+        });
+        assert!(matches!(
+            limited.merge(&decoders[0]),
+            Err(Error::LimitExceeded)
+        ));
     }
 
     #[test]
-    fn test_choose_fragments() {
+    fn test_decoder_stats() {
         let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
-        let checksum = crate::crc32().checksum(&message);
-        let fragment_length = crate::fountain::fragment_length(message.len(), 100);
-        let fragments = crate::fountain::partition(message, fragment_length);
-        let expected_fragment_indexes = vec![
-            vec![0],
-            vec![1],
-            vec![2],
-            vec![3],
-            vec![4],
-            vec![5],
-            vec![6],
-            vec![7],
-            vec![8],
-            vec![9],
-            vec![10],
-            vec![9],
-            vec![2, 5, 6, 8, 9, 10],
-            vec![8],
-            vec![1, 5],
-            vec![1],
-            vec![0, 2, 4, 5, 8, 10],
-            vec![5],
-            vec![2],
-            vec![2],
-            vec![0, 1, 3, 4, 5, 7, 9, 10],
-            vec![0, 1, 2, 3, 5, 6, 8, 9, 10],
-            vec![0, 2, 4, 5, 7, 8, 9, 10],
-            vec![3, 5],
-            vec![4],
-            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
-            vec![0, 1, 3, 4, 5, 6, 7, 9, 10],
-            vec![6],
-            vec![5, 6],
-            vec![7],
-        ];
-        for seq_num in 1..=30 {
-            let mut indexes = crate::fountain::choose_fragments(seq_num, fragments.len(), checksum);
-            indexes.sort_unstable();
-            assert_eq!(indexes, expected_fragment_indexes[seq_num - 1]);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let mut decoder = Decoder::default();
+        // skip the simple parts so that only mixed parts are received
+        encoder.set_sequence(11);
+        let mut parts = 0;
+        while !decoder.complete() {
+            let part = encoder.next_part();
+            decoder.receive_ref(&part).unwrap();
+            decoder.receive(part).unwrap();
+            parts += 1;
         }
+        // the duplicate of the last part is ignored by the complete decoder, and
+        // distinct parts may also duplicate the segments of previous ones
+        let stats = decoder.stats();
+        assert_eq!(stats.parts_received, 2 * parts - 1);
+        assert_eq!(
+            stats.duplicate_parts,
+            stats.parts_received - decoder.received.len()
+        );
+        assert!(stats.duplicate_parts >= parts - 1);
+        assert_eq!(stats.bytes_processed, (2 * parts - 1) * 94);
+        assert!(stats.xor_operations > 0);
+        assert!(stats.buffered_parts < parts);
+
+        decoder.reset();
+        assert_eq!(decoder.stats(), Stats::default());
     }
 
+    #[cfg(feature = "metrics")]
     #[test]
-    fn test_xor() {
-        let mut rng = crate::xoshiro::Xoshiro256::from("Wolf");
+    fn test_metrics() {
+        use std::collections::BTreeMap;
+        use std::sync::{atomic::AtomicU64, atomic::Ordering, Arc, Mutex};
 
-        let data1 = rng.next_bytes(10);
-        assert_eq!(hex::encode(&data1), "916ec65cf77cadf55cd7");
+        #[derive(Default)]
+        struct Counters(Mutex<BTreeMap<String, Arc<AtomicU64>>>);
 
-        let data2 = rng.next_bytes(10);
-        assert_eq!(hex::encode(&data2), "f9cda1a1030026ddd42e");
+        impl Counters {
+            fn get(&self, name: &str) -> u64 {
+                self.0.lock().unwrap()[name].load(Ordering::Relaxed)
+            }
+        }
 
-        let mut data3 = data1.clone();
-        xor(&mut data3, &data2);
-        assert_eq!(hex::encode(&data3), "68a367fdf47c8b2888f9");
+        impl metrics::Recorder for Counters {
+            fn describe_counter(
+                &self,
+                _: metrics::KeyName,
+                _: Option<metrics::Unit>,
+                _: metrics::SharedString,
+            ) {
+            }
+            fn describe_gauge(
+                &self,
+                _: metrics::KeyName,
+                _: Option<metrics::Unit>,
+                _: metrics::SharedString,
+            ) {
+            }
+            fn describe_histogram(
+                &self,
+                _: metrics::KeyName,
+                _: Option<metrics::Unit>,
+                _: metrics::SharedString,
+            ) {
+            }
+
+            fn register_counter(
+                &self,
+                key: &metrics::Key,
+                _: &metrics::Metadata<'_>,
+            ) -> metrics::Counter {
+                let mut counters = self.0.lock().unwrap();
+                let counter = counters.entry(key.name().into()).or_default();
+                metrics::Counter::from_arc(counter.clone())
+            }
 
-        xor(&mut data3, &data1);
-        assert_eq!(hex::encode(data3), hex::encode(data2));
+            fn register_gauge(
+                &self,
+                _: &metrics::Key,
+                _: &metrics::Metadata<'_>,
+            ) -> metrics::Gauge {
+                metrics::Gauge::noop()
+            }
+
+            fn register_histogram(
+                &self,
+                _: &metrics::Key,
+                _: &metrics::Metadata<'_>,
+            ) -> metrics::Histogram {
+                metrics::Histogram::noop()
+            }
+        }
+
+        let counters = Counters::default();
+        metrics::with_local_recorder(&counters, || {
+            let mut encoder = Encoder::new(b"Ten chars!", 4).unwrap();
+            let mut decoder = Decoder::default();
+            let part = encoder.next_part();
+            decoder.receive(part.clone()).unwrap();
+            decoder.receive(part).unwrap();
+            decoder.receive(encoder.next_part()).unwrap();
+            decoder.receive(encoder.next_part()).unwrap();
+        });
+        assert_eq!(counters.get("ur_parts_received_total"), 4);
+        assert_eq!(counters.get("ur_duplicate_parts_total"), 1);
+        assert_eq!(counters.get("ur_bytes_processed_total"), 16);
+        assert_eq!(counters.get("ur_bytes_decoded_total"), 10);
+        assert_eq!(counters.get("ur_transfers_completed_total"), 1);
     }
 
     #[test]
-    fn test_fountain_encoder() {
-        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
-        let mut encoder = Encoder::new(&message, 30).unwrap();
-        let expected_parts = [
-            "916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3c",
-            "cba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a",
-            "8cde6d0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f",
-            "965e25ee29039fdf8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3e",
-            "c4bbff1b9ffe8a9e7240129377b9d3711ed38d412fbb4442256f1e6f59",
-            "5e0fc57fed451fb0a0101fb76b1fb1e1b88cfdfdaa946294a47de8fff1",
-            "73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
-            "791457c9876dd34aadd192a53aa0dc66b556c0c215c7ceb8248b717c22",
-            "951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d0000000000",
-            "330f0f33a05eead4f331df229871bee733b50de71afd2e5a79f196de09",
-            "3b205ce5e52d8c24a52cffa34c564fa1af3fdffcd349dc4258ee4ee828",
-            "dd7bf725ea6c16d531b5f03254783803048ca08b87148daacd1cd7a006",
-            "760be7ad1c6187902bbc04f539b9ee5eb8ea6833222edea36031306c01",
-            "5bf4031217d2c3254b088fa7553778b5003632f46e21db129416f65b55",
-            "73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
-            "b8546ebfe2048541348910267331c643133f828afec9337c318f71b7df",
-            "23dedeea74e3a0fb052befabefa13e2f80e4315c9dceed4c8630612e64",
-            "d01a8daee769ce34b6b35d3ca0005302724abddae405bdb419c0a6b208",
-            "3171c5dc365766eff25ae47c6f10e7de48cfb8474e050e5fe997a6dc24",
-            "e055c2433562184fa71b4be94f262e200f01c6f74c284b0dc6fae6673f",
-        ]
-        .iter()
-        .enumerate()
-        .map(|(i, data)| super::Part {
-            sequence: i + 1,
-            sequence_count: 9,
-            message_length: 256,
-            checksum: 23_570_951,
-            data: hex::decode(data).unwrap(),
-        });
-        for (sequence, e) in expected_parts.into_iter().enumerate() {
-            assert_eq!(encoder.current_sequence(), sequence);
-            assert_eq!(encoder.next_part(), e);
+    fn test_decoder_message_into() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let mut decoder = Decoder::default();
+        while !decoder.complete() {
+            decoder.receive(encoder.next_part()).unwrap();
         }
+        let mut buffer = b"prefix".to_vec();
+        assert!(decoder.message_into(&mut buffer).unwrap());
+        assert_eq!(&buffer[..6], b"prefix");
+        assert_eq!(&buffer[6..], &message[..]);
     }
 
     #[test]
-    fn test_fountain_encoder_cbor() {
-        let max_fragment_length = 30;
-        let size = 256;
-        let message = crate::xoshiro::test_utils::make_message("Wolf", size);
-        let mut encoder = Encoder::new(&message, max_fragment_length).unwrap();
-        let expected_parts = vec![
-            "8501091901001a0167aa07581d916ec65cf77cadf55cd7f9cda1a1030026ddd42e905b77adc36e4f2d3c",
-            "8502091901001a0167aa07581dcba44f7f04f2de44f42d84c374a0e149136f25b01852545961d55f7f7a",
-            "8503091901001a0167aa07581d8cde6d0e2ec43f3b2dcb644a2209e8c9e34af5c4747984a5e873c9cf5f",
-            "8504091901001a0167aa07581d965e25ee29039fdf8ca74f1c769fc07eb7ebaec46e0695aea6cbd60b3e",
-            "8505091901001a0167aa07581dc4bbff1b9ffe8a9e7240129377b9d3711ed38d412fbb4442256f1e6f59",
-            "8506091901001a0167aa07581d5e0fc57fed451fb0a0101fb76b1fb1e1b88cfdfdaa946294a47de8fff1",
-            "8507091901001a0167aa07581d73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
-            "8508091901001a0167aa07581d791457c9876dd34aadd192a53aa0dc66b556c0c215c7ceb8248b717c22",
-            "8509091901001a0167aa07581d951e65305b56a3706e3e86eb01c803bbf915d80edcd64d4d0000000000",
-            "850a091901001a0167aa07581d330f0f33a05eead4f331df229871bee733b50de71afd2e5a79f196de09",
-            "850b091901001a0167aa07581d3b205ce5e52d8c24a52cffa34c564fa1af3fdffcd349dc4258ee4ee828",
-            "850c091901001a0167aa07581ddd7bf725ea6c16d531b5f03254783803048ca08b87148daacd1cd7a006",
-            "850d091901001a0167aa07581d760be7ad1c6187902bbc04f539b9ee5eb8ea6833222edea36031306c01",
-            "850e091901001a0167aa07581d5bf4031217d2c3254b088fa7553778b5003632f46e21db129416f65b55",
-            "850f091901001a0167aa07581d73f021c0e6f65b05c0a494e50791270a0050a73ae69b6725505a2ec8a5",
-            "8510091901001a0167aa07581db8546ebfe2048541348910267331c643133f828afec9337c318f71b7df",
-            "8511091901001a0167aa07581d23dedeea74e3a0fb052befabefa13e2f80e4315c9dceed4c8630612e64",
-            "8512091901001a0167aa07581dd01a8daee769ce34b6b35d3ca0005302724abddae405bdb419c0a6b208",
-            "8513091901001a0167aa07581d3171c5dc365766eff25ae47c6f10e7de48cfb8474e050e5fe997a6dc24",
-            "8514091901001a0167aa07581de055c2433562184fa71b4be94f262e200f01c6f74c284b0dc6fae6673f",
-        ];
-        assert_eq!(encoder.fragment_count(), size / max_fragment_length + 1);
-        for e in expected_parts {
-            assert_eq!(hex::encode(encoder.next_part().cbor().unwrap()), e);
+    fn test_decoder_invalid_padding() {
+        let mut decoder = Decoder::default();
+        for (sequence, data) in [b"Ten ", b"char", b"s!\0x"].into_iter().enumerate() {
+            let part = Part::new(sequence + 1, 3, 10, 0, data.to_vec());
+            decoder.receive(part).unwrap();
         }
+        assert!(decoder.complete());
+        let mut buffer = b"prefix".to_vec();
+        assert!(matches!(
+            decoder.message_into(&mut buffer),
+            Err(Error::InvalidPadding)
+        ));
+        assert_eq!(buffer, b"prefix");
+        assert!(matches!(decoder.message(), Err(Error::InvalidPadding)));
     }
 
     #[test]
-    fn test_fountain_encoder_zero_max_length() {
+    fn test_decoder_invalid_message_length() {
+        let mut decoder = Decoder::default();
+        let part = Part::new(1, 3, 13, 0, b"Ten ".to_vec());
         assert!(matches!(
-            Encoder::new(b"foo", 0),
-            Err(Error::InvalidFragmentLen)
+            decoder.receive(part),
+            Err(Error::InvalidMessageLength)
         ));
+        assert_eq!(decoder.expected_fragment_count(), None);
+
+        // segments may be padded, but need to cover the message
+        let part = Part::new(1, 3, 12, 0, b"Ten ".to_vec());
+        assert!(decoder.receive(part).unwrap());
     }
 
     #[test]
-    fn test_fountain_encoder_is_complete() {
-        let message = crate::xoshiro::test_utils::make_message("Wolf", 256);
-        let mut encoder = Encoder::new(&message, 30).unwrap();
-        for _ in 0..encoder.parts.len() {
-            encoder.next_part();
-        }
-        assert!(encoder.complete());
+    fn test_fragment_indexes() {
+        let mut indexes: FragmentIndexes = [3, 200, 64].into_iter().collect();
+        assert_eq!(indexes.iter().collect::<Vec<_>>(), vec![3, 64, 200]);
+        assert_eq!(indexes.single(), None);
+        assert!(indexes.contains(200) && !indexes.contains(4) && !indexes.contains(1000));
+        indexes.remove(200);
+        indexes.remove(3);
+        assert_eq!(indexes.single(), Some(64));
+        assert_eq!(indexes, core::iter::once(64).collect());
+        indexes.remove(64);
+        assert_eq!(indexes, FragmentIndexes::default());
+        assert_eq!(indexes.single(), None);
     }
 
     #[test]
-    fn test_decoder() {
-        let seed = "Wolf";
-        let message_size = 32767;
-        let max_fragment_length = 1000;
+    fn test_fragment_indexes_set_operations() {
+        let long: FragmentIndexes = [1, 64, 130].into_iter().collect();
+        let short: FragmentIndexes = [1, 2].into_iter().collect();
+        assert_eq!(long.union(&short).to_vec(), vec![1, 2, 64, 130]);
+        assert_eq!(short.union(&long), long.union(&short));
+        // equal sets compare equal regardless of their trailing words
+        assert_eq!(long.intersection(&short), core::iter::once(1).collect());
+        assert_eq!(short.intersection(&long), long.intersection(&short));
+        assert_eq!(long.difference(&short).to_vec(), vec![64, 130]);
+        assert_eq!(short.difference(&long).to_vec(), vec![2]);
+        assert!(long.difference(&long).is_empty());
+        assert_eq!(long.difference(&long), FragmentIndexes::new());
+    }
 
-        let message = crate::xoshiro::test_utils::make_message(seed, message_size);
-        let mut encoder = Encoder::new(&message, max_fragment_length).unwrap();
+    #[test]
+    fn test_decoder_mixed_parts_only() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 25_000);
+        let mut encoder = Encoder::new(&message, 50).unwrap();
+        encoder.set_sequence(encoder.fragment_count());
         let mut decoder = Decoder::default();
         while !decoder.complete() {
-            assert_eq!(decoder.message().unwrap(), None);
-            let part = encoder.next_part();
-            let _next = decoder.receive(part);
+            decoder.receive(encoder.next_part()).unwrap();
+        }
+        assert_eq!(decoder.message().unwrap(), Some(message));
+        assert!(decoder.buffer.is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_decoder_receive_all_parallel() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 32767);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let parts: Vec<Part> = (0..600).map(|_| encoder.next_part()).collect();
+
+        let mut sequential = Decoder::default();
+        for part in parts.iter().step_by(3) {
+            sequential.receive_ref(part).unwrap();
         }
+        let mut parallel = Decoder::default();
+        parallel
+            .receive_all_parallel(parts.iter().step_by(3).cloned())
+            .unwrap();
+        assert_eq!(parallel.decoded_count(), sequential.decoded_count());
+        assert_eq!(parallel.complete(), sequential.complete());
+        assert_eq!(parallel.message().unwrap(), sequential.message().unwrap());
+
+        // a second batch completes a partially decoded message
+        let mut decoder = Decoder::default();
+        decoder.receive_all_parallel(parts[..200].to_vec()).unwrap();
+        assert!(!decoder.complete());
+        decoder.receive_all_parallel(parts[150..].to_vec()).unwrap();
         assert_eq!(decoder.message().unwrap(), Some(message));
+        assert!(!decoder.receive_all_parallel(parts).unwrap());
+
+        let invalid = Part::new(0, 1, 1, 0, vec![1]);
+        assert!(matches!(
+            Decoder::default().receive_all_parallel([invalid]),
+            Err(Error::InvalidSequence)
+        ));
     }
 
     #[test]
     fn test_empty_encoder() {
-        assert!(Encoder::new(&[], 1).is_err());
+        assert!(matches!(Encoder::new(&[], 1), Err(Error::EmptyMessage)));
     }
 
     #[test]
@@ -945,6 +4918,78 @@ mod tests {
         assert!(!decoder.receive(part).unwrap());
     }
 
+    #[test]
+    fn test_decoder_receive_ref() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1000);
+        let mut encoder = Encoder::new(&message, 10).unwrap();
+        let parts: Vec<Part> = (0..150).map(|_| encoder.next_part()).collect();
+        let mut decoder = Decoder::default();
+        let mut received = Decoder::default();
+        for part in &parts {
+            assert_eq!(
+                decoder.receive_ref(part).unwrap(),
+                received.receive(part.clone()).unwrap()
+            );
+            if decoder.complete() {
+                break;
+            }
+        }
+        assert!(decoder.complete());
+        assert!(!decoder.receive_ref(&parts[0]).unwrap());
+        assert_eq!(decoder.message().unwrap(), Some(message));
+    }
+
+    #[test]
+    fn test_decoder_receive_unchecked() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1000);
+        let mut encoder = Encoder::new(&message, 10).unwrap();
+        let mut decoder = Decoder::default();
+        let mut checked = Decoder::default();
+        // the first part is validated
+        assert!(matches!(
+            decoder.receive_unchecked(Part::new(0, 100, 1000, 0, alloc::vec![0; 10])),
+            Err(Error::InvalidSequence)
+        ));
+        let mut parts = 0;
+        while !decoder.complete() {
+            let part = encoder.next_part();
+            assert!(decoder.receive_unchecked(part.clone()).unwrap());
+            // duplicates are processed again until the decoder is complete
+            let processed = decoder.receive_unchecked(part.clone()).unwrap();
+            assert_eq!(processed, !decoder.complete());
+            parts += 1 + usize::from(processed);
+            checked.receive(part).unwrap();
+        }
+        assert!(checked.complete());
+        assert!(!decoder.receive_unchecked(encoder.next_part()).unwrap());
+        assert_eq!(decoder.stats().parts_received, parts);
+        assert_eq!(decoder.stats().duplicate_parts, 0);
+        assert_eq!(decoder.decoded_count(), checked.decoded_count());
+        assert_eq!(decoder.message().unwrap(), Some(message));
+
+        // subsequent parts are checked against the transfer metadata
+        let mut encoder = Encoder::new(&[7; 100], 10).unwrap();
+        let mut decoder = Decoder::default().with_limits(Limits {
+            max_buffered_bytes: 100,
+            ..Limits::default()
+        });
+        decoder.receive_unchecked(encoder.next_part()).unwrap();
+        assert!(matches!(
+            decoder.receive_unchecked(Part::new(2, 10, 100, encoder.checksum(), vec![0; 5])),
+            Err(Error::InconsistentPart)
+        ));
+        assert!(matches!(
+            decoder.receive_unchecked(Part::new(0, 10, 100, encoder.checksum(), vec![0; 10])),
+            Err(Error::InvalidSequence)
+        ));
+        // mixed parts are buffered until the limit is reached
+        let error = (12..100)
+            .find_map(|sequence| decoder.receive_unchecked(encoder.part_at(sequence)).err())
+            .unwrap();
+        assert!(matches!(error, Error::LimitExceeded));
+        assert!(!decoder.complete());
+    }
+
     #[test]
     fn test_decoder_part_validation() {
         let mut encoder = Encoder::new(b"foo", 2).unwrap();
@@ -1000,6 +5045,15 @@ mod tests {
         ));
         part.data = vec![1, 5, 3, 3, 5];
 
+        // Check sequence.
+        part.sequence = 0;
+        assert!(matches!(
+            decoder.receive(part.clone()),
+            Err(Error::InvalidSequence)
+        ));
+        assert!(!decoder.is_part_useful(&part));
+        part.sequence = 12;
+
         // Should not validate as there aren't any previous parts received.
         assert!(!decoder.validate(&part));
     }
@@ -1054,7 +5108,60 @@ mod tests {
         // the fifth item must be byte string
         assert!(matches!(
             Part::from_cbor(&[0x85, 0x1, 0x2, 0x3, 0x4, 0x5]),
-            Err(Error::CborDecode(e)) if e.to_string() == "unexpected type u8 at position 5: expected bytes (definite length)"
+            Err(Error::CborDecode(e)) if e.to_string() == "unexpected type u8 at position 5: expected bytes"
+        ));
+        // sequence numbers and counts start at one
+        assert!(matches!(
+            Part::from_cbor(&[0x85, 0x0, 0x2, 0x3, 0x4, 0x41, 0x5]),
+            Err(Error::CborDecode(e)) if e.to_string() == "decode error at position 1: sequence numbers start at one"
+        ));
+        assert!(matches!(
+            Part::from_cbor(&[0x85, 0x1, 0x0, 0x3, 0x4, 0x41, 0x5]),
+            Err(Error::CborDecode(e)) if e.to_string() == "decode error at position 1: sequence numbers start at one"
+        ));
+        // no bytes may follow the array
+        assert!(matches!(
+            Part::from_cbor(&[0x85, 0x1, 0x2, 0x3, 0x4, 0x41, 0x5, 0x0]),
+            Err(Error::CborDecode(e)) if e.to_string() == "decode error at position 7: trailing bytes after CBOR part"
+        ));
+    }
+
+    #[test]
+    fn test_part_from_cbor_indefinite_length() {
+        let part = Part::new(1, 2, 3, 4, vec![5, 6, 7]);
+        // indefinite-length array
+        assert_eq!(
+            Part::from_cbor(&[0x9f, 0x1, 0x2, 0x3, 0x4, 0x43, 0x5, 0x6, 0x7, 0xff]).unwrap(),
+            part
+        );
+        // indefinite-length byte string
+        assert_eq!(
+            Part::from_cbor(&[0x85, 0x1, 0x2, 0x3, 0x4, 0x5f, 0x41, 0x5, 0x42, 0x6, 0x7, 0xff])
+                .unwrap(),
+            part
+        );
+        assert_eq!(
+            Part::from_cbor(&[0x9f, 0x1, 0x2, 0x3, 0x4, 0x5f, 0x43, 0x5, 0x6, 0x7, 0xff, 0xff])
+                .unwrap(),
+            part
+        );
+        // the array must still hold five items
+        assert!(matches!(
+            Part::from_cbor(&[0x9f, 0x1, 0x2, 0x3, 0x4, 0x43, 0x5, 0x6, 0x7, 0x8, 0xff]),
+            Err(Error::CborDecode(e)) if e.to_string() == "decode error at position 9: invalid CBOR array length"
+        ));
+        assert!(matches!(
+            Part::from_cbor(&[0x9f, 0x1, 0x2, 0x3, 0x43, 0x5, 0x6, 0x7, 0xff]),
+            Err(Error::CborDecode(_))
+        ));
+        assert!(matches!(
+            Part::from_cbor(&[0x9f, 0x1, 0x2, 0x3, 0x4, 0x43, 0x5, 0x6, 0x7]),
+            Err(Error::CborDecode(_))
+        ));
+        // the chunks of a byte string must be definite-length byte strings
+        assert!(matches!(
+            Part::from_cbor(&[0x85, 0x1, 0x2, 0x3, 0x4, 0x5f, 0x1, 0xff]),
+            Err(Error::CborDecode(_))
         ));
     }
 
@@ -0,0 +1,264 @@
+//! Estimate the transmission effort of fountain-encoded messages.
+//!
+//! The functions in this module model a lossy channel on which every part is
+//! lost independently with a fixed probability, as is the case for a scanner
+//! which misses frames of an animated QR code. They allow sizing frame rates
+//! and redundancy analytically instead of by trial and error.
+//!
+//! The model assumes that the transmission starts at the first part, and that
+//! the mixed parts are chosen by the [`XoshiroScheduler`](super::XoshiroScheduler).
+//! It further assumes that the decoder [solves](super::Decoder::solve) the
+//! mixed parts it receives. A decoder which only reduces them by the decoded
+//! segments needs considerably more parts when many segments are lost. Since
+//! mixed parts combining few segments are more likely to be redundant than
+//! the model accounts for, actual transmissions need a few percent more parts.
+//!
+//! ```
+//! use ur::fountain::analysis;
+//!
+//! // without loss, every segment is transmitted exactly once
+//! assert_eq!(analysis::expected_parts_received(10, 0.0), 10.0);
+//! // losing every fifth part requires around 15 transmitted parts for 10 segments
+//! let sent = analysis::expected_parts_transmitted(10, 0.2);
+//! assert!(14.0 < sent && sent < 16.0);
+//! ```
+
+/// The number of lost segments beyond which the contribution to the overhead
+/// of recovering from losses is below floating point precision.
+const MAX_OVERHEAD_TERMS: usize = 64;
+
+/// Returns the expected number of parts which have to be received to decode a
+/// message consisting of `fragment_count` segments, if each part is lost with
+/// probability `loss`.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::analysis::expected_parts_received;
+/// assert_eq!(expected_parts_received(100, 0.0), 100.0);
+/// let received = expected_parts_received(100, 0.5);
+/// assert!(108.0 < received && received < 112.0);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `loss` is not a probability between zero and one.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn expected_parts_received(fragment_count: usize, loss: f64) -> f64 {
+    assert!((0.0..=1.0).contains(&loss), "loss must be a probability");
+    let (missing, mixed) = expected_recovery(fragment_count, loss);
+    fragment_count as f64 - missing + mixed
+}
+
+/// Returns the expected number of parts which have to be transmitted to decode
+/// a message consisting of `fragment_count` segments, if each part is lost with
+/// probability `loss`.
+///
+/// If every part is lost, the message can never be decoded and infinity is
+/// returned.
+///
+/// # Examples
+///
+/// ```
+/// use ur::fountain::analysis::expected_parts_transmitted;
+/// assert_eq!(expected_parts_transmitted(100, 0.0), 100.0);
+/// let sent = expected_parts_transmitted(100, 0.5);
+/// assert!(210.0 < sent && sent < 230.0);
+/// assert!(expected_parts_transmitted(100, 1.0).is_infinite());
+/// ```
+///
+/// # Panics
+///
+/// Panics if `loss` is not a probability between zero and one.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn expected_parts_transmitted(fragment_count: usize, loss: f64) -> f64 {
+    assert!((0.0..=1.0).contains(&loss), "loss must be a probability");
+    if loss >= 1.0 {
+        return f64::INFINITY;
+    }
+    // The first `fragment_count` parts are transmitted in any case. The lost
+    // segments are then recovered from mixed parts, each of which only arrives
+    // with probability `1 - loss`.
+    let (_, mixed) = expected_recovery(fragment_count, loss);
+    fragment_count as f64 + mixed / (1.0 - loss)
+}
+
+/// Returns the expected number of segments lost among the first
+/// `fragment_count` parts, and the expected number of mixed parts which have to
+/// be received to recover them.
+///
+/// The number of lost segments follows a binomial distribution, which is
+/// summed outwards from its mode so that it doesn't underflow for long
+/// messages.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn expected_recovery(fragment_count: usize, loss: f64) -> (f64, f64) {
+    if loss <= 0.0 || fragment_count == 0 {
+        return (0.0, 0.0);
+    }
+    let n = fragment_count;
+    let harmonic = (1..=n).map(|d| 1.0 / d as f64).sum::<f64>();
+    if loss >= 1.0 {
+        return (n as f64, recovery_parts(n, n, harmonic));
+    }
+    let ratio = loss / (1.0 - loss);
+    let mode = (((n + 1) as f64 * loss) as usize).min(n);
+    let (mut total, mut missing, mut mixed) = (0.0, 0.0, 0.0);
+    let mut add = |k: usize, weight: f64| {
+        total += weight;
+        missing += weight * k as f64;
+        mixed += weight * recovery_parts(n, k, harmonic);
+    };
+    // the weights are relative to the probability of the mode and decrease
+    // monotonically away from it
+    let mut weight = 1.0;
+    add(mode, weight);
+    for k in mode + 1..=n {
+        weight *= ratio * (n - k + 1) as f64 / k as f64;
+        if weight < f64::EPSILON * f64::EPSILON {
+            break;
+        }
+        add(k, weight);
+    }
+    weight = 1.0;
+    for k in (0..mode).rev() {
+        weight *= (k + 1) as f64 / ((n - k) as f64 * ratio);
+        if weight < f64::EPSILON * f64::EPSILON {
+            break;
+        }
+        add(k, weight);
+    }
+    (missing / total, mixed / total)
+}
+
+/// Returns the expected number of mixed parts which have to be received to
+/// recover `lost` of `fragment_count` segments.
+///
+/// A mixed part only helps if it combines at least one of the lost segments,
+/// which depends on the degree distribution of the
+/// [`XoshiroScheduler`](super::XoshiroScheduler). Of the parts which do, the
+/// `i`-th last needed one is independent of the previous ones with probability
+/// about `1 - 2^-i`.
+#[allow(clippy::cast_precision_loss)]
+fn recovery_parts(fragment_count: usize, lost: usize, harmonic: f64) -> f64 {
+    if lost == 0 {
+        return 0.0;
+    }
+    let hit = hit_probability(fragment_count, lost, harmonic);
+    let mut power = 1.0;
+    let mut redundant = 0.0;
+    for _ in 0..lost.min(MAX_OVERHEAD_TERMS) {
+        power *= 2.0;
+        redundant += 1.0 / (power - 1.0);
+    }
+    (1.0 - powi(0.5, lost)) * (lost as f64 + redundant) / hit
+}
+
+/// Returns the probability that a mixed part combines at least one of `lost`
+/// segments, given that its degree `d` is chosen with a weight of `1 / d`.
+#[allow(clippy::cast_precision_loss)]
+fn hit_probability(fragment_count: usize, lost: usize, harmonic: f64) -> f64 {
+    // the probability that all `d` combined segments have been received
+    let mut none = 1.0;
+    let mut miss = 0.0;
+    for d in 1..=fragment_count - lost {
+        none *= (fragment_count - lost - d + 1) as f64 / (fragment_count - d + 1) as f64;
+        if none < f64::EPSILON * f64::EPSILON {
+            break;
+        }
+        miss += none / d as f64;
+    }
+    1.0 - miss / harmonic
+}
+
+/// Computes `base^exponent` by repeated squaring, which is not available in
+/// `core`.
+fn powi(mut base: f64, mut exponent: usize) -> f64 {
+    let mut result = 1.0;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result *= base;
+        }
+        base *= base;
+        exponent >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_powi() {
+        assert!((powi(0.5, 10) - 1.0 / 1024.0).abs() < f64::EPSILON);
+        assert!((powi(3.0, 0) - 1.0).abs() < f64::EPSILON);
+        assert!(powi(0.9, 100_000) >= 0.0);
+    }
+
+    #[test]
+    fn test_single_segment() {
+        // a single segment is always recovered from the first part received
+        assert!((expected_parts_received(1, 0.5) - 1.0).abs() < 1e-12);
+        assert!((expected_parts_transmitted(1, 0.5) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_hit_probability() {
+        let harmonic = 1.0 + 1.0 / 2.0 + 1.0 / 3.0;
+        // with one of three segments lost, a degree of one hits it with
+        // probability 1/3, a degree of two with 2/3 and a degree of three always
+        let expected = (1.0 / 3.0 + 2.0 / 3.0 / 2.0 + 1.0 / 3.0) / harmonic;
+        assert!((hit_probability(3, 1, harmonic) - expected).abs() < 1e-12);
+        assert!((hit_probability(3, 3, harmonic) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_monotonic_in_loss() {
+        let mut previous = 0.0;
+        for step in 0..20 {
+            let sent = expected_parts_transmitted(50, f64::from(step) / 20.0);
+            assert!(sent > previous);
+            previous = sent;
+        }
+    }
+
+    #[test]
+    fn test_long_messages() {
+        for count in [1000, 100_000] {
+            for loss in [0.001, 0.5, 0.999] {
+                let received = expected_parts_received(count, loss);
+                assert!(received.is_finite());
+                assert!(received > expected_parts_received(count, 0.0));
+            }
+        }
+    }
+
+    #[cfg(feature = "sim")]
+    #[test]
+    fn test_simulation() {
+        use crate::sim::{Channel, Simulation};
+        for (message_length, count) in [(300, 3), (1000, 10), (3000, 30)] {
+            for loss in [0.2, 0.5] {
+                let simulation = Simulation {
+                    message_length,
+                    channel: Channel {
+                        drop_rate: loss,
+                        ..Channel::default()
+                    },
+                    trials: 500,
+                    solve: true,
+                    ..Simulation::default()
+                };
+                let simulated = simulation.run().unwrap().mean_parts_received().unwrap();
+                let expected = expected_parts_received(count, loss);
+                assert!((simulated - expected).abs() < expected * 0.06);
+            }
+        }
+    }
+}
@@ -0,0 +1,545 @@
+//! A fountain coder based on RaptorQ, as specified in RFC 6330.
+//!
+//! RaptorQ codes recover a message from barely more parts than it has segments,
+//! even when large parts of a transmission are lost. They are however not part
+//! of the uniform resource specification, so parts emitted by this [`Encoder`]
+//! can only be recombined by the [`Decoder`] of this module. Use it for
+//! deployments which control both ends of a transmission of large payloads.
+//!
+//! The emitted [`Part`]s carry a serialized RaptorQ packet as their data, while
+//! their remaining attributes are interpreted as for the spec-compliant coder.
+//! Both coders implement [`MessageEncoder`] and [`MessageDecoder`].
+//!
+//! ```
+//! use ur::fountain::raptorq::{Decoder, Encoder};
+//! let message = b"Some payload to be transmitted over a lossy channel".repeat(100);
+//! let mut encoder = Encoder::new(&message, 200).unwrap();
+//! let mut decoder = Decoder::default();
+//! // every other part is lost
+//! while !decoder.complete() {
+//!     encoder.next_part();
+//!     decoder.receive(encoder.next_part()).unwrap();
+//! }
+//! assert_eq!(decoder.message(), Some(message));
+//! ```
+
+use super::{Error, Limits, MessageDecoder, MessageEncoder, Part};
+use alloc::vec::Vec;
+
+/// The length of the RaptorQ payload identifier preceding the symbol in the
+/// data of each part.
+const PAYLOAD_ID_LENGTH: usize = 4;
+
+/// RaptorQ symbols are aligned to multiples of eight bytes.
+const SYMBOL_ALIGNMENT: usize = 8;
+
+/// The maximum number of symbols per source block. This is well below the limit
+/// of RFC 6330, since the decoding effort grows superlinearly with the block size.
+const MAX_BLOCK_SYMBOLS: usize = 1024;
+
+/// An encoder emitting RaptorQ-encoded parts.
+///
+/// # Examples
+///
+/// See the [`crate::fountain::raptorq`] module documentation for an example.
+#[derive(Debug)]
+pub struct Encoder {
+    blocks: Vec<::raptorq::SourceBlockEncoder>,
+    source_packets: Vec<Vec<::raptorq::EncodingPacket>>,
+    message_length: usize,
+    checksum: u32,
+    current_sequence: usize,
+}
+
+impl Encoder {
+    /// Constructs a new [`Encoder`], given a message and a maximum fragment
+    /// length. The symbols of RaptorQ are a multiple of eight bytes and are
+    /// transmitted together with a four byte identifier, so the maximum
+    /// fragment length has to be at least twelve bytes.
+    ///
+    /// The message is split into at most 255 blocks of 1024 segments, which
+    /// limits the message length to about 260 thousand times the fragment length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::raptorq::Encoder;
+    /// let encoder = Encoder::new(&[0; 1000], 100).unwrap();
+    /// assert_eq!(encoder.fragment_count(), 11);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an empty message or a maximum fragment length shorter than twelve
+    /// bytes is passed, or the message is too long for the fragment length, an
+    /// error will be returned.
+    pub fn new(message: &[u8], max_fragment_length: usize) -> Result<Self, Error> {
+        if message.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+        let config = symbol_size(max_fragment_length)
+            .and_then(|symbol_size| transmission_information(message.len(), symbol_size))
+            .ok_or(Error::InvalidFragmentLen)?;
+        let encoder = ::raptorq::Encoder::new(message, config);
+        let blocks = encoder.get_block_encoders().clone();
+        let source_packets = blocks
+            .iter()
+            .map(::raptorq::SourceBlockEncoder::source_packets)
+            .collect();
+        Ok(Self {
+            blocks,
+            source_packets,
+            message_length: message.len(),
+            checksum: crate::CRC32.checksum(message),
+            current_sequence: 0,
+        })
+    }
+
+    /// Returns the current count of how many parts have been emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::raptorq::Encoder;
+    /// let mut encoder = Encoder::new(b"data", 12).unwrap();
+    /// encoder.next_part();
+    /// assert_eq!(encoder.current_sequence(), 1);
+    /// ```
+    #[must_use]
+    pub const fn current_sequence(&self) -> usize {
+        self.current_sequence
+    }
+
+    /// Returns the number of segments the message has been split up into.
+    ///
+    /// # Examples
+    ///
+    /// See [`Encoder::new`] for an example.
+    #[must_use]
+    pub fn fragment_count(&self) -> usize {
+        self.source_packets.iter().map(Vec::len).sum()
+    }
+
+    /// Returns whether all original segments have been emitted at least once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::raptorq::Encoder;
+    /// let mut encoder = Encoder::new(&[1; 20], 12).unwrap();
+    /// encoder.next_part();
+    /// assert!(!encoder.complete());
+    /// encoder.next_part();
+    /// encoder.next_part();
+    /// assert!(encoder.complete());
+    /// ```
+    #[must_use]
+    pub fn complete(&self) -> bool {
+        self.current_sequence >= self.fragment_count()
+    }
+
+    /// Returns the next part to be emitted. The source blocks of the message
+    /// take turns, and the original segments of each block are emitted before
+    /// its repair segments.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::fountain::raptorq`] module documentation for an example.
+    pub fn next_part(&mut self) -> Part {
+        self.current_sequence = match u32::try_from(self.current_sequence) {
+            Ok(sequence) if sequence < u32::MAX => self.current_sequence + 1,
+            _ => 1,
+        };
+        self.part_at(self.current_sequence)
+    }
+
+    /// Returns the part with the given sequence number, without affecting the
+    /// state of the encoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::raptorq::Encoder;
+    /// let mut encoder = Encoder::new(&[1; 100], 20).unwrap();
+    /// let tenth = encoder.part_at(10);
+    /// for _ in 0..9 {
+    ///     encoder.next_part();
+    /// }
+    /// assert_eq!(encoder.next_part(), tenth);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Sequence numbers start at one, so this panics if `sequence` is zero.
+    #[must_use]
+    pub fn part_at(&self, sequence: usize) -> Part {
+        assert!(sequence > 0, "sequence numbers start at one");
+        let block = (sequence - 1) % self.blocks.len();
+        let symbol = (sequence - 1) / self.blocks.len();
+        let source_packets = &self.source_packets[block];
+        let packet = source_packets.get(symbol).cloned().unwrap_or_else(|| {
+            #[allow(clippy::cast_possible_truncation)]
+            let repair = (symbol - source_packets.len()) as u32;
+            self.blocks[block].repair_packets(repair, 1).remove(0)
+        });
+        Part::new(
+            sequence,
+            self.fragment_count(),
+            self.message_length,
+            self.checksum,
+            packet.serialize(),
+        )
+    }
+}
+
+/// A decoder recombining RaptorQ-encoded parts emitted by an [`Encoder`].
+///
+/// # Examples
+///
+/// See the [`crate::fountain::raptorq`] module documentation for an example.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    inner: Option<::raptorq::Decoder>,
+    source_blocks: usize,
+    sequence_count: usize,
+    message_length: usize,
+    checksum: u32,
+    fragment_length: usize,
+    limits: Limits,
+    message: Option<Vec<u8>>,
+}
+
+impl Decoder {
+    /// Sets the [`Limits`] on the resources committed to a transfer. The
+    /// decoder allocates room for all segments of the message when receiving
+    /// the first part, which is rejected with [`Error::LimitExceeded`] if they
+    /// exceed the limits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::raptorq::{Decoder, Encoder};
+    /// use ur::fountain::{Error, Limits};
+    /// let mut decoder = Decoder::default().with_limits(Limits {
+    ///     max_buffered_bytes: 1000,
+    ///     ..Limits::default()
+    /// });
+    /// let mut encoder = Encoder::new(&[0; 2000], 100).unwrap();
+    /// assert!(matches!(
+    ///     decoder.receive(encoder.next_part()),
+    ///     Err(Error::LimitExceeded)
+    /// ));
+    /// ```
+    #[must_use]
+    pub const fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Receives a RaptorQ-encoded part into the decoder. Returns whether the
+    /// part was processed, which is not the case once the decoder is complete.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::fountain::raptorq`] module documentation for an example.
+    ///
+    /// # Errors
+    ///
+    /// If the part is inconsistent with previously received parts, doesn't
+    /// hold a valid RaptorQ packet or exceeds the [`Limits`], an error will be
+    /// returned. If the decoded message doesn't match the checksum of the
+    /// parts, an error is returned as well.
+    // takes the part by value like the decoder of the specification
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn receive(&mut self, part: Part) -> Result<bool, Error> {
+        self.receive_ref(&part)
+    }
+
+    /// Receives a RaptorQ-encoded part into the decoder by reference, like
+    /// [`Decoder::receive`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::raptorq::{Decoder, Encoder};
+    /// let mut encoder = Encoder::new(b"data", 12).unwrap();
+    /// let part = encoder.next_part();
+    /// let mut decoder = Decoder::default();
+    /// assert!(decoder.receive_ref(&part).unwrap());
+    /// assert_eq!(decoder.message().as_deref(), Some(&b"data"[..]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`Decoder::receive`].
+    pub fn receive_ref(&mut self, part: &Part) -> Result<bool, Error> {
+        if self.complete() {
+            return Ok(false);
+        }
+        if part.sequence_count == 0
+            || part.data.len() <= PAYLOAD_ID_LENGTH
+            || part.message_length == 0
+        {
+            return Err(Error::EmptyPart);
+        }
+        let decoder = if let Some(decoder) = &mut self.inner {
+            if part.sequence_count != self.sequence_count
+                || part.message_length != self.message_length
+                || part.checksum != self.checksum
+                || part.data.len() != self.fragment_length
+            {
+                return Err(Error::InconsistentPart);
+            }
+            decoder
+        } else {
+            let symbol_size = part.data.len() - PAYLOAD_ID_LENGTH;
+            let config = transmission_information(part.message_length, symbol_size)
+                .filter(|_| {
+                    part.sequence_count == super::div_ceil(part.message_length, symbol_size)
+                })
+                .ok_or(Error::InconsistentPart)?;
+            let total = part.sequence_count.checked_mul(part.data.len());
+            if part.sequence_count > self.limits.max_fragment_count
+                || !matches!(total, Some(total) if total <= self.limits.max_buffered_bytes)
+            {
+                return Err(Error::LimitExceeded);
+            }
+            self.source_blocks = usize::from(config.source_blocks());
+            self.sequence_count = part.sequence_count;
+            self.message_length = part.message_length;
+            self.checksum = part.checksum;
+            self.fragment_length = part.data.len();
+            self.inner.insert(::raptorq::Decoder::new(config))
+        };
+        let packet = ::raptorq::EncodingPacket::deserialize(&part.data);
+        if usize::from(packet.payload_id().source_block_number()) >= self.source_blocks {
+            return Err(Error::InconsistentPart);
+        }
+        if let Some(message) = decoder.decode(packet) {
+            if crate::CRC32.checksum(&message) != self.checksum {
+                return Err(Error::InconsistentPart);
+            }
+            self.message = Some(message);
+        }
+        Ok(true)
+    }
+
+    /// Returns whether the decoder is complete and hence the message available.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::fountain::raptorq`] module documentation for an example.
+    #[must_use]
+    pub const fn complete(&self) -> bool {
+        self.message.is_some()
+    }
+
+    /// If [`complete`], returns the decoded message, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::fountain::raptorq`] module documentation for an example.
+    ///
+    /// [`complete`]: Decoder::complete
+    #[must_use]
+    pub fn message(&self) -> Option<Vec<u8>> {
+        self.message.clone()
+    }
+}
+
+impl MessageEncoder for Encoder {
+    fn next_part(&mut self) -> Part {
+        self.next_part()
+    }
+
+    fn current_sequence(&self) -> usize {
+        self.current_sequence()
+    }
+
+    fn fragment_count(&self) -> usize {
+        self.fragment_count()
+    }
+
+    fn complete(&self) -> bool {
+        self.complete()
+    }
+}
+
+impl MessageDecoder for Decoder {
+    fn receive(&mut self, part: Part) -> Result<bool, Error> {
+        self.receive(part)
+    }
+
+    fn complete(&self) -> bool {
+        self.complete()
+    }
+
+    fn message(&self) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self.message())
+    }
+}
+
+/// Returns the largest aligned symbol size fitting into a fragment together
+/// with the payload identifier.
+fn symbol_size(max_fragment_length: usize) -> Option<usize> {
+    let symbol_size = max_fragment_length
+        .checked_sub(PAYLOAD_ID_LENGTH)?
+        .min(usize::from(u16::MAX));
+    let symbol_size = symbol_size - symbol_size % SYMBOL_ALIGNMENT;
+    (symbol_size > 0).then_some(symbol_size)
+}
+
+/// Derives the RaptorQ parameters from the message length and symbol size, so
+/// that the decoder can reconstruct them from the received parts. Returns `None`
+/// if the parameters are out of the supported range.
+fn transmission_information(
+    message_length: usize,
+    symbol_size: usize,
+) -> Option<::raptorq::ObjectTransmissionInformation> {
+    // the alignment is a power of two
+    if symbol_size == 0 || symbol_size & (SYMBOL_ALIGNMENT - 1) != 0 {
+        return None;
+    }
+    let symbols = super::div_ceil(message_length, symbol_size);
+    let blocks = u8::try_from(super::div_ceil(symbols, MAX_BLOCK_SYMBOLS)).ok()?;
+    #[allow(clippy::cast_possible_truncation)]
+    Some(::raptorq::ObjectTransmissionInformation::new(
+        u64::try_from(message_length).ok()?,
+        u16::try_from(symbol_size).ok()?,
+        blocks,
+        1,
+        SYMBOL_ALIGNMENT as u8,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_size() {
+        assert_eq!(symbol_size(11), None);
+        assert_eq!(symbol_size(12), Some(8));
+        assert_eq!(symbol_size(100), Some(96));
+        assert_eq!(symbol_size(usize::MAX), Some(65528));
+    }
+
+    #[test]
+    fn test_transmission_information() {
+        let config = transmission_information(10_000, 8).unwrap();
+        assert_eq!(config.source_blocks(), 2);
+        assert_eq!(config.symbol_size(), 8);
+        assert!(transmission_information(100, 12).is_none());
+        assert!(transmission_information(255 * 1024 * 8, 8).is_some());
+        assert!(transmission_information(255 * 1024 * 8 + 1, 8).is_none());
+    }
+
+    #[test]
+    fn test_raptorq_invalid_encoder() {
+        assert!(matches!(Encoder::new(&[], 100), Err(Error::EmptyMessage)));
+        assert!(matches!(
+            Encoder::new(b"data", 11),
+            Err(Error::InvalidFragmentLen)
+        ));
+    }
+
+    #[test]
+    fn test_raptorq_lossy_transmission() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 32767);
+        let mut encoder = Encoder::new(&message, 1000).unwrap();
+        let mut decoder = Decoder::default();
+        let mut received = 0;
+        for sequence in 1.. {
+            let part = encoder.next_part();
+            assert!(part.data().len() <= 1000);
+            // lose two out of three parts
+            if sequence % 3 == 0 {
+                decoder.receive(part).unwrap();
+                received += 1;
+            }
+            if decoder.complete() {
+                break;
+            }
+        }
+        assert!(received <= encoder.fragment_count() + 2);
+        assert_eq!(decoder.message(), Some(message));
+    }
+
+    #[test]
+    fn test_raptorq_multiple_blocks() {
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 10_000);
+        let mut encoder = Encoder::new(&message, 12).unwrap();
+        let mut decoder = Decoder::default();
+        while !decoder.complete() {
+            decoder.receive(encoder.next_part()).unwrap();
+        }
+        assert_eq!(decoder.message(), Some(message));
+    }
+
+    #[test]
+    fn test_raptorq_inconsistent_parts() {
+        let mut encoder = Encoder::new(&[1; 100], 20).unwrap();
+        let mut decoder = Decoder::default();
+        decoder.receive(encoder.next_part()).unwrap();
+        let mut other = Encoder::new(&[1; 101], 20).unwrap();
+        assert!(matches!(
+            decoder.receive(other.next_part()),
+            Err(Error::InconsistentPart)
+        ));
+        let part = Part::new(1, 1, 4, 0, vec![0, 0, 0]);
+        assert!(matches!(
+            Decoder::default().receive(part),
+            Err(Error::EmptyPart)
+        ));
+        // the source block number exceeds the number of blocks
+        let part = Part::new(1, 1, 4, 0, vec![1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(matches!(
+            Decoder::default().receive(part),
+            Err(Error::InconsistentPart)
+        ));
+    }
+
+    #[test]
+    fn test_raptorq_limits() {
+        let mut encoder = Encoder::new(&[1; 100], 20).unwrap();
+        let mut decoder = Decoder::default().with_limits(Limits {
+            max_fragment_count: 5,
+            ..Limits::default()
+        });
+        assert!(matches!(
+            decoder.receive(encoder.next_part()),
+            Err(Error::LimitExceeded)
+        ));
+        // a hostile first part may not make the decoder allocate the message
+        let part = Part::new(1, 200_000, 200_000 * 1024, 0, vec![0; 1028]);
+        let mut decoder = Decoder::default().with_limits(Limits {
+            max_buffered_bytes: 1 << 20,
+            ..Limits::default()
+        });
+        assert!(matches!(decoder.receive(part), Err(Error::LimitExceeded)));
+        assert!(decoder.inner.is_none());
+    }
+
+    #[test]
+    fn test_raptorq_message_coder() {
+        fn transfer(encoder: &mut impl MessageEncoder, decoder: &mut impl MessageDecoder) {
+            while !decoder.complete() {
+                encoder.next_part();
+                decoder.receive(encoder.next_part()).unwrap();
+            }
+        }
+        let message = crate::xoshiro::test_utils::make_message("Wolf", 1000);
+        let mut encoder = Encoder::new(&message, 100).unwrap();
+        let mut decoder = Decoder::default();
+        transfer(&mut encoder, &mut decoder);
+        assert_eq!(
+            MessageDecoder::message(&decoder).unwrap(),
+            Some(message.clone())
+        );
+
+        let mut encoder = super::super::Encoder::new(&message, 100).unwrap();
+        let mut decoder = super::super::Decoder::default();
+        transfer(&mut encoder, &mut decoder);
+        assert_eq!(decoder.message().unwrap(), Some(message));
+    }
+}
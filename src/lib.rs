@@ -26,13 +26,20 @@
 //! ```
 //!
 //! The following useful building blocks are also part of the public API:
-//!  - The [`crate::bytewords`](crate::bytewords) module contains functionality
+//!  - The [`crate::bytewords`] module contains functionality
 //!    to encode byte payloads into a suitable alphabet, achieving hexadecimal
 //!    byte-per-character efficiency.
-//!  - The [`crate::fountain`](crate::fountain) module provides an implementation
+//!  - The [`crate::fountain`] module provides an implementation
 //!    of a fountain encoder, which splits up a byte payload into multiple segments
 //!    and emits an unbounded stream of parts which can be recombined at the receiving
 //!    decoder side.
+//!  - The [`crate::xoshiro`] module contains the random number
+//!    generator of the fountain encoder, for implementations which need
+//!    bit-identical random streams.
+//!  - The [`crate::sampler`] module contains the weighted
+//!    sampler choosing the degree of fountain parts.
+//!  - The [`crate::registry`] module contains types of the
+//!    UR registry which are embedded into the payloads of other URs.
 
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
@@ -41,20 +48,28 @@
 extern crate alloc;
 
 pub mod bytewords;
+#[cfg(feature = "conformance")]
+pub mod conformance;
 pub mod fountain;
+pub mod registry;
+pub mod sampler;
+pub mod scan;
+#[cfg(feature = "sim")]
+pub mod sim;
 pub mod ur;
+pub mod xoshiro;
 
 mod constants;
-mod sampler;
-mod xoshiro;
+mod sha256;
 
+pub use self::scan::ScanStats;
 pub use self::ur::decode;
+pub use self::ur::diagnose;
 pub use self::ur::encode;
 pub use self::ur::Decoder;
 pub use self::ur::Encoder;
+pub use self::ur::Error;
 pub use self::ur::Type;
 
-#[must_use]
-pub(crate) const fn crc32() -> crc::Crc<u32> {
-    crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC)
-}
+/// The CRC32 checksum algorithm, whose table is computed at compile time.
+pub(crate) static CRC32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
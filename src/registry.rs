@@ -0,0 +1,575 @@
+//! Types of the UR registry which are exchanged within other URs.
+//!
+//! The types serialize to and from their CBOR representation with
+//! [`minicbor`], so they can be embedded into the CBOR payload of a UR. The
+//! [`PsbtSignRequest`] and [`PsbtSignResponse`] form the payloads of the
+//! `crypto-request` and `crypto-response` URs of an air-gapped signing flow.
+//!
+//! # Examples
+//!
+//! ```
+//! use ur::registry::SeedDigest;
+//! let digest = SeedDigest::from_seed(b"seed");
+//! let cbor = minicbor::to_vec(digest).unwrap();
+//! assert_eq!(&cbor[..3], [0xd9, 0x02, 0x58]);
+//! assert_eq!(minicbor::decode::<SeedDigest>(&cbor).unwrap(), digest);
+//! ```
+
+extern crate alloc;
+use alloc::vec::Vec;
+use minicbor::data::Tag;
+
+/// The CBOR tag of a [`SeedDigest`].
+pub const SEED_DIGEST_TAG: u64 = 600;
+
+/// The CBOR tag of the request identifiers, which are UUIDs.
+pub const UUID_TAG: u64 = 37;
+
+/// The CBOR tag of a `crypto-keypath`, see [`Keypath`].
+pub const KEYPATH_TAG: u64 = 304;
+
+/// The CBOR tag of a `crypto-psbt`, a partially signed bitcoin transaction.
+pub const PSBT_TAG: u64 = 310;
+
+/// The CBOR tag of the body of a [`PsbtSignRequest`].
+pub const PSBT_SIGNATURE_REQUEST_TAG: u64 = 502;
+
+/// The `crypto-seed-digest`, identifying a seed by its SHA-256 digest, e.g. in
+/// requests asking a wallet to derive keys from a particular seed.
+///
+/// Digests compare in constant time, such that comparing a requested digest
+/// against the digests of stored seeds doesn't leak how many leading bytes
+/// matched.
+///
+/// # Examples
+///
+/// ```
+/// use ur::registry::SeedDigest;
+/// let digest = SeedDigest::from_seed(b"abc");
+/// assert_eq!(
+///     digest.as_bytes()[..4],
+///     [0xba, 0x78, 0x16, 0xbf]
+/// );
+/// assert_eq!(SeedDigest::from_digest(*digest.as_bytes()), digest);
+/// ```
+#[derive(Clone, Copy, Eq)]
+pub struct SeedDigest([u8; 32]);
+
+impl SeedDigest {
+    /// Computes the digest of the given seed.
+    ///
+    /// # Examples
+    ///
+    /// See the [`SeedDigest`] documentation for an example.
+    #[must_use]
+    pub fn from_seed(seed: &[u8]) -> Self {
+        Self(crate::sha256::hash(seed))
+    }
+
+    /// Wraps an already computed SHA-256 digest of a seed.
+    ///
+    /// # Examples
+    ///
+    /// See the [`SeedDigest`] documentation for an example.
+    #[must_use]
+    pub const fn from_digest(digest: [u8; 32]) -> Self {
+        Self(digest)
+    }
+
+    /// Returns the bytes of the digest.
+    ///
+    /// # Examples
+    ///
+    /// See the [`SeedDigest`] documentation for an example.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// Compares all bytes regardless of the position of the first difference.
+impl PartialEq for SeedDigest {
+    fn eq(&self, other: &Self) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+    }
+}
+
+impl core::fmt::Debug for SeedDigest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SeedDigest(")?;
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<C> minicbor::Encode<C> for SeedDigest {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.tag(Tag::Unassigned(SEED_DIGEST_TAG))?.bytes(&self.0)?;
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::Decode<'b, C> for SeedDigest {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        expect_tag(d, SEED_DIGEST_TAG)?;
+        let position = d.position();
+        let digest = d
+            .bytes()?
+            .try_into()
+            .map_err(|_| minicbor::decode::Error::message("expected 32 bytes").at(position))?;
+        Ok(Self(digest))
+    }
+}
+
+/// A component of a [`Keypath`], deriving the child key with the given index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathComponent {
+    /// The child index, without the hardened flag.
+    pub index: u32,
+    /// Whether the child key is derived with hardened derivation.
+    pub hardened: bool,
+}
+
+/// The `crypto-keypath`, a BIP-32 derivation path originating from a master
+/// key with the given fingerprint.
+///
+/// Only paths of fixed child indexes are supported, not the wildcards and
+/// ranges of output descriptors.
+///
+/// # Examples
+///
+/// ```
+/// use ur::registry::{Keypath, PathComponent};
+/// let path = Keypath {
+///     components: vec![PathComponent { index: 84, hardened: true }],
+///     source_fingerprint: Some(0x37b5_eed4),
+///     depth: None,
+/// };
+/// let cbor = minicbor::to_vec(&path).unwrap();
+/// assert_eq!(minicbor::decode::<Keypath>(&cbor).unwrap(), path);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Keypath {
+    /// The derivation steps from the source key.
+    pub components: Vec<PathComponent>,
+    /// The fingerprint of the master key the path originates from.
+    pub source_fingerprint: Option<u32>,
+    /// The depth of the derived key, if it differs from the number of components.
+    pub depth: Option<u8>,
+}
+
+impl<C> minicbor::Encode<C> for Keypath {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        let entries =
+            1 + u64::from(self.source_fingerprint.is_some()) + u64::from(self.depth.is_some());
+        e.tag(Tag::Unassigned(KEYPATH_TAG))?.map(entries)?;
+        e.u8(1)?.array(2 * self.components.len() as u64)?;
+        for component in &self.components {
+            e.u32(component.index)?.bool(component.hardened)?;
+        }
+        if let Some(fingerprint) = self.source_fingerprint {
+            e.u8(2)?.u32(fingerprint)?;
+        }
+        if let Some(depth) = self.depth {
+            e.u8(3)?.u8(depth)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::Decode<'b, C> for Keypath {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        expect_tag(d, KEYPATH_TAG)?;
+        let mut path = Self::default();
+        let mut components = None;
+        decode_map(d, |key, d| {
+            match key {
+                1 => components = Some(decode_components(d)?),
+                2 => path.source_fingerprint = Some(d.u32()?),
+                3 => path.depth = Some(d.u8()?),
+                _ => d.skip()?,
+            }
+            Ok(())
+        })?;
+        path.components = components
+            .ok_or_else(|| minicbor::decode::Error::message("missing path components"))?;
+        Ok(path)
+    }
+}
+
+/// Decodes the flat array of child indexes and hardened flags of a keypath.
+fn decode_components(
+    d: &mut minicbor::Decoder<'_>,
+) -> Result<Vec<PathComponent>, minicbor::decode::Error> {
+    let position = d.position();
+    let Some(length) = d.array()? else {
+        return Err(
+            minicbor::decode::Error::message("expected definite path components").at(position),
+        );
+    };
+    if length % 2 == 1 {
+        return Err(minicbor::decode::Error::message("unpaired path component").at(position));
+    }
+    (0..length / 2)
+        .map(|_| {
+            let position = d.position();
+            let index = d.u32().map_err(|_| {
+                minicbor::decode::Error::message("unsupported path component").at(position)
+            })?;
+            Ok(PathComponent {
+                index,
+                hardened: d.bool()?,
+            })
+        })
+        .collect()
+}
+
+/// A request to sign a partially signed bitcoin transaction (PSBT), which is
+/// sent from a coordinator to a signer as a `crypto-request` UR.
+///
+/// The signer answers with a [`PsbtSignResponse`] carrying the same request
+/// identifier. The optional derivation origin tells the signer which key the
+/// coordinator expects it to sign with.
+///
+/// # Examples
+///
+/// ```
+/// use ur::registry::{PsbtSignRequest, PsbtSignResponse};
+/// let request = PsbtSignRequest {
+///     request_id: [7; 16],
+///     psbt: b"psbt\xff".to_vec(),
+///     origin: None,
+/// };
+/// let cbor = minicbor::to_vec(&request).unwrap();
+/// let ur = ur::encode(&cbor, &ur::Type::Custom(PsbtSignRequest::UR_TYPE));
+/// assert!(ur.starts_with("ur:crypto-request/"));
+///
+/// // the signer decodes the request and responds with the signed transaction
+/// let parsed = ur::decode(&ur).unwrap();
+/// assert_eq!(parsed.ur_type(), PsbtSignRequest::UR_TYPE);
+/// let request: PsbtSignRequest = minicbor::decode(parsed.payload()).unwrap();
+/// let response = PsbtSignResponse {
+///     request_id: request.request_id,
+///     psbt: request.psbt,
+/// };
+/// let cbor = minicbor::to_vec(&response).unwrap();
+/// assert_eq!(minicbor::decode::<PsbtSignResponse>(&cbor).unwrap(), response);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PsbtSignRequest {
+    /// The identifier of the request, a UUID.
+    pub request_id: [u8; 16],
+    /// The serialized PSBT to sign.
+    pub psbt: Vec<u8>,
+    /// The derivation path of the key expected to sign.
+    pub origin: Option<Keypath>,
+}
+
+impl PsbtSignRequest {
+    /// The type of the URs carrying the request.
+    pub const UR_TYPE: &'static str = "crypto-request";
+}
+
+impl<C> minicbor::Encode<C> for PsbtSignRequest {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.map(2)?
+            .u8(1)?
+            .tag(Tag::Unassigned(UUID_TAG))?
+            .bytes(&self.request_id)?;
+        e.u8(2)?
+            .tag(Tag::Unassigned(PSBT_SIGNATURE_REQUEST_TAG))?
+            .map(1 + u64::from(self.origin.is_some()))?;
+        e.u8(1)?.tag(Tag::Unassigned(PSBT_TAG))?.bytes(&self.psbt)?;
+        if let Some(origin) = &self.origin {
+            e.u8(2)?;
+            origin.encode(e, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::Decode<'b, C> for PsbtSignRequest {
+    fn decode(d: &mut minicbor::Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        let mut request_id = None;
+        let mut psbt = None;
+        let mut origin = None;
+        decode_map(d, |key, d| {
+            match key {
+                1 => request_id = Some(decode_uuid(d)?),
+                2 => {
+                    expect_tag(d, PSBT_SIGNATURE_REQUEST_TAG)?;
+                    decode_map(d, |key, d| {
+                        match key {
+                            1 => psbt = Some(decode_psbt(d)?),
+                            2 => origin = Some(Keypath::decode(d, ctx)?),
+                            _ => d.skip()?,
+                        }
+                        Ok(())
+                    })?;
+                }
+                _ => d.skip()?,
+            }
+            Ok(())
+        })?;
+        Ok(Self {
+            request_id: request_id
+                .ok_or_else(|| minicbor::decode::Error::message("missing request id"))?,
+            psbt: psbt.ok_or_else(|| minicbor::decode::Error::message("missing psbt"))?,
+            origin,
+        })
+    }
+}
+
+/// The response of a signer to a [`PsbtSignRequest`], carrying the signed
+/// PSBT as a `crypto-response` UR.
+///
+/// # Examples
+///
+/// See the [`PsbtSignRequest`] documentation for an example.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PsbtSignResponse {
+    /// The identifier of the answered request.
+    pub request_id: [u8; 16],
+    /// The serialized signed PSBT.
+    pub psbt: Vec<u8>,
+}
+
+impl PsbtSignResponse {
+    /// The type of the URs carrying the response.
+    pub const UR_TYPE: &'static str = "crypto-response";
+}
+
+impl<C> minicbor::Encode<C> for PsbtSignResponse {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.map(2)?
+            .u8(1)?
+            .tag(Tag::Unassigned(UUID_TAG))?
+            .bytes(&self.request_id)?;
+        e.u8(2)?.tag(Tag::Unassigned(PSBT_TAG))?.bytes(&self.psbt)?;
+        Ok(())
+    }
+}
+
+impl<'b, C> minicbor::Decode<'b, C> for PsbtSignResponse {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        let mut request_id = None;
+        let mut psbt = None;
+        decode_map(d, |key, d| {
+            match key {
+                1 => request_id = Some(decode_uuid(d)?),
+                2 => psbt = Some(decode_psbt(d)?),
+                _ => d.skip()?,
+            }
+            Ok(())
+        })?;
+        Ok(Self {
+            request_id: request_id
+                .ok_or_else(|| minicbor::decode::Error::message("missing request id"))?,
+            psbt: psbt.ok_or_else(|| minicbor::decode::Error::message("missing psbt"))?,
+        })
+    }
+}
+
+/// Consumes the given tag, or fails if the next item has another or no tag.
+fn expect_tag(d: &mut minicbor::Decoder<'_>, tag: u64) -> Result<(), minicbor::decode::Error> {
+    let position = d.position();
+    if d.tag()? == Tag::Unassigned(tag) {
+        Ok(())
+    } else {
+        Err(minicbor::decode::Error::message("unexpected tag").at(position))
+    }
+}
+
+fn decode_uuid(d: &mut minicbor::Decoder<'_>) -> Result<[u8; 16], minicbor::decode::Error> {
+    expect_tag(d, UUID_TAG)?;
+    let position = d.position();
+    d.bytes()?
+        .try_into()
+        .map_err(|_| minicbor::decode::Error::message("expected 16 bytes").at(position))
+}
+
+fn decode_psbt(d: &mut minicbor::Decoder<'_>) -> Result<Vec<u8>, minicbor::decode::Error> {
+    expect_tag(d, PSBT_TAG)?;
+    Ok(d.bytes()?.to_vec())
+}
+
+/// Decodes the entries of a definite or indefinite map with integer keys,
+/// leaving the decoding of the values to the callback.
+fn decode_map<'b>(
+    d: &mut minicbor::Decoder<'b>,
+    mut entry: impl FnMut(u64, &mut minicbor::Decoder<'b>) -> Result<(), minicbor::decode::Error>,
+) -> Result<(), minicbor::decode::Error> {
+    if let Some(length) = d.map()? {
+        for _ in 0..length {
+            let key = d.u64()?;
+            entry(key, d)?;
+        }
+    } else {
+        while d.datatype()? != minicbor::data::Type::Break {
+            let key = d.u64()?;
+            entry(key, d)?;
+        }
+        d.set_position(d.position() + 1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_digest_cbor() {
+        // https://github.com/BlockchainCommons/crypto-commons/blob/67ea252f4a7f295bb347cb046796d5b445b3ad3c/Docs/ur-99-request-response.md#the-seed-request
+        let bytes = hex::decode("E824467CAFFEAF3BBC3E0CA095E660A9BAD80DDB6A919433A37161908B9A3986")
+            .unwrap();
+        let digest = SeedDigest::from_digest(bytes.clone().try_into().unwrap());
+        let cbor = minicbor::to_vec(digest).unwrap();
+        assert_eq!(cbor, [&[0xd9, 0x02, 0x58, 0x58, 0x20][..], &bytes].concat());
+        assert_eq!(minicbor::decode::<SeedDigest>(&cbor).unwrap(), digest);
+        assert_eq!(
+            format!("{digest:?}"),
+            "SeedDigest(e824467caffeaf3bbc3e0ca095e660a9bad80ddb6a919433a37161908b9a3986)"
+        );
+
+        // untagged, wrongly tagged and truncated digests
+        assert!(minicbor::decode::<SeedDigest>(&cbor[3..]).is_err());
+        let mut tagged = cbor.clone();
+        tagged[2] = 0x59;
+        assert!(minicbor::decode::<SeedDigest>(&tagged).is_err());
+        let mut truncated = cbor[..cbor.len() - 1].to_vec();
+        truncated[4] = 0x1f;
+        assert!(minicbor::decode::<SeedDigest>(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_seed_digest_eq() {
+        let digest = SeedDigest::from_seed(b"seed");
+        assert_eq!(
+            digest,
+            SeedDigest::from_digest(crate::sha256::hash(b"seed"))
+        );
+        for index in [0, 17, 31] {
+            let mut bytes = *digest.as_bytes();
+            bytes[index] ^= 1;
+            assert_ne!(digest, SeedDigest::from_digest(bytes));
+        }
+    }
+
+    #[test]
+    fn test_keypath_cbor() {
+        let path = Keypath {
+            components: vec![
+                PathComponent {
+                    index: 84,
+                    hardened: true,
+                },
+                PathComponent {
+                    index: 0,
+                    hardened: false,
+                },
+            ],
+            source_fingerprint: Some(0x37b5_eed4),
+            depth: None,
+        };
+        let cbor = minicbor::to_vec(&path).unwrap();
+        assert_eq!(hex::encode(&cbor), "d90130a201841854f500f4021a37b5eed4");
+        assert_eq!(minicbor::decode::<Keypath>(&cbor).unwrap(), path);
+
+        // wildcards and odd component arrays aren't supported
+        assert!(minicbor::decode::<Keypath>(&hex::decode("d90130a10182a0f4").unwrap()).is_err());
+        assert!(minicbor::decode::<Keypath>(&hex::decode("d90130a1018100").unwrap()).is_err());
+        // the components are required
+        assert!(minicbor::decode::<Keypath>(&hex::decode("d90130a10302").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_psbt_sign_request_cbor() {
+        let mut request = PsbtSignRequest {
+            request_id: [0x11; 16],
+            psbt: vec![0x70, 0x73, 0x62, 0x74],
+            origin: None,
+        };
+        let cbor = minicbor::to_vec(&request).unwrap();
+        assert_eq!(
+            hex::encode(&cbor),
+            "a201d825501111111111111111111111111111111102d901f6a101d901364470736274"
+        );
+        assert_eq!(minicbor::decode::<PsbtSignRequest>(&cbor).unwrap(), request);
+
+        request.origin = Some(Keypath {
+            source_fingerprint: Some(1),
+            ..Keypath::default()
+        });
+        let cbor = minicbor::to_vec(&request).unwrap();
+        assert_eq!(minicbor::decode::<PsbtSignRequest>(&cbor).unwrap(), request);
+
+        // indefinite maps with unknown entries
+        let cbor = hex::decode(
+            "bf03f601d825501111111111111111111111111111111102d901f6bf01d901364470736274ffff",
+        )
+        .unwrap();
+        let decoded = minicbor::decode::<PsbtSignRequest>(&cbor).unwrap();
+        assert_eq!(decoded.psbt, b"psbt");
+
+        // a request for another body type
+        let cbor =
+            hex::decode("a201d825501111111111111111111111111111111102d901f4a101d90258582000")
+                .unwrap();
+        assert!(minicbor::decode::<PsbtSignRequest>(&cbor).is_err());
+    }
+
+    #[test]
+    fn test_psbt_sign_response_cbor() {
+        let response = PsbtSignResponse {
+            request_id: [0x22; 16],
+            psbt: b"signed".to_vec(),
+        };
+        let cbor = minicbor::to_vec(&response).unwrap();
+        assert_eq!(
+            hex::encode(&cbor),
+            "a201d825502222222222222222222222222222222202d90136467369676e6564"
+        );
+        assert_eq!(
+            minicbor::decode::<PsbtSignResponse>(&cbor).unwrap(),
+            response
+        );
+
+        // a missing psbt and a short request id
+        let cbor = hex::decode("a101d825502222222222222222222222222222222222").unwrap();
+        assert!(minicbor::decode::<PsbtSignResponse>(&cbor).is_err());
+        let cbor = hex::decode("a201d8254f22222222222222222222222222222202d9013640").unwrap();
+        assert!(minicbor::decode::<PsbtSignResponse>(&cbor).is_err());
+    }
+}
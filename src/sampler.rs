@@ -1,7 +1,49 @@
+//! Weighted random sampling as used by the fountain encoder to choose the
+//! degree of parts.
+//!
+//! # Examples
+//!
+//! ```
+//! use ur::sampler::Weighted;
+//! use ur::xoshiro::Xoshiro256;
+//! let sampler = Weighted::new(vec![1.0, 2.0, 4.0, 8.0]).unwrap();
+//! let mut rng = Xoshiro256::from("Wolf");
+//! assert_eq!(sampler.next(&mut rng), 3);
+//! ```
+
 extern crate alloc;
 use alloc::vec::Vec;
 
-#[derive(Debug)]
+/// Errors that can happen when constructing a [`Weighted`] sampler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// A weight is negative or not finite.
+    InvalidWeight,
+    /// The weights don't sum to a positive finite value, e.g. because there
+    /// are none.
+    InvalidTotalWeight,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidWeight => write!(f, "negative or non-finite weight encountered"),
+            Self::InvalidTotalWeight => write!(f, "weights don't sum to a positive value"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// A sampler of indexes according to their relative weights, implemented with
+/// [Vose's alias method](https://en.wikipedia.org/wiki/Alias_method).
+///
+/// The construction takes linear time, after which each sample takes constant
+/// time and consumes two numbers of the random stream. The sampling is
+/// bit-identical to the reference implementation of the fountain encoder.
+#[derive(Debug, Clone)]
 pub struct Weighted {
     aliases: Vec<u32>,
     probs: Vec<f64>,
@@ -10,13 +52,25 @@ pub struct Weighted {
 #[allow(clippy::cast_possible_truncation)]
 #[allow(clippy::cast_precision_loss)]
 impl Weighted {
-    pub fn new(mut weights: Vec<f64>) -> Self {
-        assert!(
-            !weights.iter().any(|&p| p < 0.0),
-            "negative probability encountered"
-        );
+    /// Creates a sampler for the indexes of the given weights. The weights
+    /// don't need to be normalized.
+    ///
+    /// # Examples
+    ///
+    /// See the [module documentation](self) for an example.
+    ///
+    /// # Errors
+    ///
+    /// If a weight is negative or not finite, or if the weights don't sum to a
+    /// positive finite value, an error will be returned.
+    pub fn new(mut weights: Vec<f64>) -> Result<Self, Error> {
+        if weights.iter().any(|&p| p < 0.0 || !p.is_finite()) {
+            return Err(Error::InvalidWeight);
+        }
         let summed = weights.iter().sum::<f64>();
-        assert!(summed > 0.0, "probabilities don't sum to a positive value");
+        if !(summed > 0.0 && summed.is_finite()) {
+            return Err(Error::InvalidTotalWeight);
+        }
         let count = weights.len();
         for w in &mut weights {
             *w *= count as f64 / summed;
@@ -51,9 +105,10 @@ impl Weighted {
             probs[a] = 1.0;
         }
 
-        Self { aliases, probs }
+        Ok(Self { aliases, probs })
     }
 
+    /// Samples the next index using the given random number generator.
     #[allow(clippy::cast_sign_loss)]
     pub fn next(&self, xoshiro: &mut crate::xoshiro::Xoshiro256) -> u32 {
         let r1 = xoshiro.next_double();
@@ -76,7 +131,7 @@ mod tests {
     fn test_sampler() {
         let weights = vec![1.0, 2.0, 4.0, 8.0];
         let mut xoshiro = crate::xoshiro::Xoshiro256::from("Wolf");
-        let sampler = Weighted::new(weights);
+        let sampler = Weighted::new(weights).unwrap();
 
         let expected_samples = vec![
             3, 3, 3, 3, 3, 3, 3, 0, 2, 3, 3, 3, 3, 1, 2, 2, 1, 3, 3, 2, 3, 3, 1, 1, 2, 1, 1, 3, 1,
@@ -107,7 +162,7 @@ mod tests {
     fn test_choose_degree() {
         let message = crate::xoshiro::test_utils::make_message("Wolf", 1024);
         let fragment_length = crate::fountain::fragment_length(message.len(), 100);
-        let fragments = crate::fountain::partition(message, fragment_length);
+        let fragments = crate::fountain::partition(&message, fragment_length);
         let expected_degrees = vec![
             11, 3, 6, 5, 2, 1, 2, 11, 1, 3, 9, 10, 10, 4, 2, 1, 1, 2, 1, 1, 5, 2, 4, 10, 3, 2, 1,
             1, 3, 11, 2, 6, 2, 9, 9, 2, 6, 7, 2, 5, 2, 4, 3, 1, 6, 11, 2, 11, 3, 1, 6, 3, 1, 4, 5,
@@ -128,14 +183,34 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "negative probability encountered")]
-    fn test_negative_weights() {
-        Weighted::new(vec![2.0, -1.0]);
-    }
-
-    #[test]
-    #[should_panic(expected = "probabilities don't sum to a positive value")]
-    fn test_zero_weights() {
-        Weighted::new(vec![0.0]);
+    fn test_invalid_weights() {
+        assert_eq!(
+            Weighted::new(vec![2.0, -1.0]).unwrap_err(),
+            Error::InvalidWeight
+        );
+        assert_eq!(
+            Weighted::new(vec![2.0, f64::NAN]).unwrap_err(),
+            Error::InvalidWeight
+        );
+        assert_eq!(
+            Weighted::new(vec![f64::INFINITY]).unwrap_err(),
+            Error::InvalidWeight
+        );
+        assert_eq!(
+            Weighted::new(vec![f64::MAX, f64::MAX]).unwrap_err(),
+            Error::InvalidTotalWeight
+        );
+        assert_eq!(
+            Weighted::new(vec![0.0]).unwrap_err(),
+            Error::InvalidTotalWeight
+        );
+        assert_eq!(
+            Weighted::new(vec![]).unwrap_err(),
+            Error::InvalidTotalWeight
+        );
+        assert_eq!(
+            Error::InvalidWeight.to_string(),
+            "negative or non-finite weight encountered"
+        );
     }
 }
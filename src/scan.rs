@@ -0,0 +1,403 @@
+//! Helpers for decoding URs scanned from animated QR codes with a camera.
+//!
+//! Cameras deliver the same QR code many times in a row, and often faster than
+//! an application wants to process them. The [`ScanSession`] wraps a
+//! [`Decoder`] to skip repeated frames, rate-limits the processing and tracks
+//! how efficiently the scan progresses in its [`ScanStats`].
+//!
+//! # Examples
+//!
+//! ```
+//! use core::time::Duration;
+//! use ur::scan::{Scan, ScanSession};
+//! let mut encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+//! let mut session = ScanSession::default();
+//! let mut now = Duration::ZERO;
+//! while !session.decoder().complete() {
+//!     let part = encoder.next_part().unwrap();
+//!     // the camera captures every code three times
+//!     for _ in 0..3 {
+//!         now += Duration::from_millis(20);
+//!         let scan = session.receive(&part, now).unwrap();
+//!         assert!(matches!(scan, Scan::NewPart | Scan::Repeated));
+//!     }
+//! }
+//! assert_eq!(session.frames_per_second(), Some(50.0));
+//! assert_eq!(session.new_part_ratio(), Some(1.0));
+//! assert_eq!(session.stats().frames_seen, 9);
+//! ```
+
+extern crate alloc;
+use crate::ur::{Decoder, Error};
+use alloc::string::String;
+use core::time::Duration;
+
+/// The outcome of a frame passed to a [`ScanSession`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scan {
+    /// The frame repeats the previous one and was skipped.
+    Repeated,
+    /// The frame arrived before the minimum interval elapsed and was skipped.
+    RateLimited,
+    /// The frame was decoded, but its part didn't carry new information.
+    Duplicate,
+    /// The frame was decoded and its part was new to the decoder.
+    NewPart,
+}
+
+/// Statistics on the frames of a transfer passed to a [`ScanSession`], e.g. to
+/// compare the scanning performance of devices.
+///
+/// Every frame is counted exactly once as skipped, as a parse failure, as a
+/// duplicate or as a useful part.
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+/// let mut session = ur::scan::ScanSession::default();
+/// let part = "ur:bytes/1-2/lpadaoaacypmwfwfiafwiehsdpcynbem";
+/// session.receive("ur:bytes/invalid", Duration::ZERO).ok();
+/// session.receive(part, Duration::from_millis(100)).unwrap();
+/// session.receive(part, Duration::from_millis(200)).unwrap();
+/// let stats = session.stats();
+/// assert_eq!(stats.frames_seen, 3);
+/// assert_eq!(stats.skipped_frames, 1);
+/// assert_eq!(stats.parse_failures, 1);
+/// assert_eq!(stats.useful_parts, 1);
+/// assert_eq!(stats.elapsed, Duration::from_millis(200));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScanStats {
+    /// The number of frames passed to the session.
+    pub frames_seen: usize,
+    /// The number of frames skipped as repetitions or by rate-limiting.
+    pub skipped_frames: usize,
+    /// The number of decoded frames which were rejected, e.g. because they
+    /// don't contain a well-formed UR or belong to another transfer.
+    pub parse_failures: usize,
+    /// The number of decoded frames whose part was already known.
+    pub duplicates: usize,
+    /// The number of decoded frames whose part was new to the decoder.
+    pub useful_parts: usize,
+    /// The time between the first and the last frame.
+    pub elapsed: Duration,
+}
+
+impl ScanStats {
+    /// Returns the number of frames which were decoded.
+    ///
+    /// # Examples
+    ///
+    /// See the [`ScanStats`] documentation for an example.
+    #[must_use]
+    pub const fn decoded_frames(&self) -> usize {
+        self.parse_failures + self.duplicates + self.useful_parts
+    }
+}
+
+/// A [`Decoder`] fed with camera frames, which skips repeated frames and
+/// rate-limits the processing.
+///
+/// The timestamps of the frames are passed by the caller, measured from an
+/// arbitrary but fixed point in time, such that the session also works on
+/// platforms without a clock.
+///
+/// # Examples
+///
+/// See the [`crate::scan`] module documentation for an example.
+#[derive(Default)]
+pub struct ScanSession {
+    decoder: Decoder,
+    ignored_repeats: Option<usize>,
+    min_interval: Duration,
+    last_frame: String,
+    repeats: usize,
+    last_processed: Option<Duration>,
+    first_seen: Option<Duration>,
+    stats: ScanStats,
+}
+
+impl ScanSession {
+    /// Creates a session decoding the frames with the given decoder, e.g. one
+    /// configured with [`Decoder::with_limits`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let decoder = ur::Decoder::default().with_expected_checksum(0);
+    /// let session = ur::scan::ScanSession::new(decoder);
+    /// ```
+    #[must_use]
+    pub fn new(decoder: Decoder) -> Self {
+        Self {
+            decoder,
+            ..Self::default()
+        }
+    }
+
+    /// Only skips the given number of consecutive repetitions of a frame, and
+    /// decodes the following repetition again. By default all repetitions are
+    /// skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::time::Duration;
+    /// use ur::scan::{Scan, ScanSession};
+    /// let mut session = ScanSession::default().with_ignored_repeats(1);
+    /// let part = "ur:bytes/1-2/lpadaoaacypmwfwfiafwiehsdpcynbem";
+    /// assert_eq!(session.receive(part, Duration::ZERO).unwrap(), Scan::NewPart);
+    /// assert_eq!(session.receive(part, Duration::ZERO).unwrap(), Scan::Repeated);
+    /// assert_eq!(session.receive(part, Duration::ZERO).unwrap(), Scan::Duplicate);
+    /// ```
+    #[must_use]
+    pub const fn with_ignored_repeats(mut self, repeats: usize) -> Self {
+        self.ignored_repeats = Some(repeats);
+        self
+    }
+
+    /// Skips frames arriving less than the given interval after the last
+    /// decoded frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::time::Duration;
+    /// use ur::scan::{Scan, ScanSession};
+    /// let mut session = ScanSession::default().with_min_interval(Duration::from_millis(100));
+    /// let mut encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// let first = encoder.next_part().unwrap();
+    /// let second = encoder.next_part().unwrap();
+    /// assert_eq!(session.receive(&first, Duration::ZERO).unwrap(), Scan::NewPart);
+    /// let now = Duration::from_millis(50);
+    /// assert_eq!(session.receive(&second, now).unwrap(), Scan::RateLimited);
+    /// let now = Duration::from_millis(100);
+    /// assert_eq!(session.receive(&second, now).unwrap(), Scan::NewPart);
+    /// ```
+    #[must_use]
+    pub const fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Passes a scanned frame captured at the given time to the session, which
+    /// decodes it unless it is skipped.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::scan`] module documentation for an example.
+    ///
+    /// # Errors
+    ///
+    /// If the frame is decoded, the errors of [`Decoder::receive`] are
+    /// returned. Repetitions of an invalid frame are skipped like any other.
+    pub fn receive(&mut self, frame: &str, now: Duration) -> Result<Scan, Error> {
+        self.stats.frames_seen += 1;
+        let first_seen = *self.first_seen.get_or_insert(now);
+        self.stats.elapsed = now.saturating_sub(first_seen);
+
+        if self.stats.decoded_frames() > 0 && frame == self.last_frame {
+            if matches!(self.ignored_repeats, Some(ignored) if self.repeats >= ignored) {
+                self.repeats = 0;
+            } else {
+                self.repeats += 1;
+                self.stats.skipped_frames += 1;
+                return Ok(Scan::Repeated);
+            }
+        } else {
+            self.repeats = 0;
+        }
+        let next = self
+            .last_processed
+            .and_then(|last| last.checked_add(self.min_interval));
+        if matches!(next, Some(next) if now < next) {
+            self.stats.skipped_frames += 1;
+            return Ok(Scan::RateLimited);
+        }
+
+        self.last_frame.clear();
+        self.last_frame.push_str(frame);
+        self.last_processed = Some(now);
+        let stats = self.decoder.stats();
+        let new_parts = stats.parts_received - stats.duplicate_parts;
+        if let Err(e) = self.decoder.receive(frame) {
+            self.stats.parse_failures += 1;
+            return Err(e);
+        }
+        let stats = self.decoder.stats();
+        if stats.parts_received - stats.duplicate_parts > new_parts {
+            self.stats.useful_parts += 1;
+            Ok(Scan::NewPart)
+        } else {
+            self.stats.duplicates += 1;
+            Ok(Scan::Duplicate)
+        }
+    }
+
+    /// Returns the [`ScanStats`] of the current transfer.
+    ///
+    /// # Examples
+    ///
+    /// See the [`ScanStats`] documentation for an example.
+    #[must_use]
+    pub const fn stats(&self) -> ScanStats {
+        self.stats
+    }
+
+    /// Returns the wrapped decoder, e.g. to check whether it is complete.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::scan`] module documentation for an example.
+    #[must_use]
+    pub const fn decoder(&self) -> &Decoder {
+        &self.decoder
+    }
+
+    /// Consumes the session, returning the wrapped decoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let session = ur::scan::ScanSession::default();
+    /// assert_eq!(session.into_decoder().message().unwrap(), None);
+    /// ```
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn into_decoder(self) -> Decoder {
+        self.decoder
+    }
+
+    /// Returns the rate at which frames arrived, or `None` if not enough
+    /// frames arrived to measure it.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::scan`] module documentation for an example.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn frames_per_second(&self) -> Option<f64> {
+        let stats = &self.stats;
+        // the first frame marks the start of the measurement
+        (stats.elapsed > Duration::ZERO)
+            .then(|| (stats.frames_seen - 1) as f64 / stats.elapsed.as_secs_f64())
+    }
+
+    /// Returns the fraction of decoded frames which carried a new part, or
+    /// `None` if no frame was decoded yet.
+    ///
+    /// A low ratio means that the camera mostly captures parts the decoder has
+    /// already seen, e.g. because the animation loops too slowly.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::scan`] module documentation for an example.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new_part_ratio(&self) -> Option<f64> {
+        let decoded = self.stats.decoded_frames();
+        (decoded > 0).then(|| self.stats.useful_parts as f64 / decoded as f64)
+    }
+
+    /// Clears the decoder and all statistics, such that the session can be
+    /// reused for a new transfer. The configuration is retained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::time::Duration;
+    /// let mut session = ur::scan::ScanSession::default();
+    /// let part = "ur:bytes/1-2/lpadaoaacypmwfwfiafwiehsdpcynbem";
+    /// session.receive(part, Duration::ZERO).unwrap();
+    /// session.reset();
+    /// assert_eq!(session.new_part_ratio(), None);
+    /// ```
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+        self.last_frame.clear();
+        self.repeats = 0;
+        self.last_processed = None;
+        self.first_seen = None;
+        self.stats = ScanStats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_session() {
+        let mut encoder = crate::Encoder::bytes(&[7; 100], 10).unwrap();
+        let parts: alloc::vec::Vec<_> = (0..10).map(|_| encoder.next_part().unwrap()).collect();
+        let mut session = ScanSession::default().with_min_interval(Duration::from_millis(50));
+        let mut now = Duration::ZERO;
+        let mut scans = alloc::vec::Vec::new();
+        // every part is captured twice, every 20ms
+        for part in parts.iter().chain(&parts[..2]).flat_map(|p| [p, p]) {
+            scans.push(session.receive(part, now).unwrap());
+            now += Duration::from_millis(20);
+        }
+        let count = |scan| scans.iter().filter(|s| **s == scan).count();
+        assert_eq!(count(Scan::Repeated), 4);
+        assert_eq!(count(Scan::RateLimited), 12);
+        assert_eq!(count(Scan::Duplicate), 1);
+        assert_eq!(count(Scan::NewPart), 7);
+        assert_eq!(session.frames_per_second(), Some(50.0));
+        assert_eq!(session.new_part_ratio(), Some(7.0 / 8.0));
+        let stats = session.stats();
+        assert_eq!(stats.frames_seen, 24);
+        assert_eq!(stats.skipped_frames, 16);
+        assert_eq!(stats.decoded_frames(), 8);
+        assert_eq!(stats.elapsed, Duration::from_millis(460));
+        assert!(!session.decoder().complete());
+
+        // the skipped parts complete the transfer, the others are duplicates
+        for (index, part) in parts.iter().enumerate() {
+            now += Duration::from_millis(50);
+            let scan = session.receive(part, now).unwrap();
+            assert_eq!(scan == Scan::NewPart, index % 3 == 2);
+        }
+        assert!(session.decoder().complete());
+        // the first part repeats the last decoded frame
+        assert_eq!(session.new_part_ratio(), Some(10.0 / 17.0));
+    }
+
+    #[test]
+    fn test_scan_session_interval_overflow() {
+        let mut encoder = crate::Encoder::bytes(&[7; 100], 10).unwrap();
+        let mut session = ScanSession::default().with_min_interval(Duration::MAX);
+        let now = Duration::from_secs(1);
+        let part = encoder.next_part().unwrap();
+        assert_eq!(session.receive(&part, now).unwrap(), Scan::NewPart);
+        let part = encoder.next_part().unwrap();
+        assert_eq!(session.receive(&part, now).unwrap(), Scan::NewPart);
+    }
+
+    #[test]
+    fn test_scan_session_errors() {
+        let mut session = ScanSession::default();
+        assert!(matches!(
+            session.receive("ur:bytes/aeadaolazmjendeoti", Duration::ZERO),
+            Err(Error::NotMultiPart)
+        ));
+        assert_eq!(
+            session
+                .receive("ur:bytes/aeadaolazmjendeoti", Duration::ZERO)
+                .unwrap(),
+            Scan::Repeated
+        );
+        assert_eq!(session.new_part_ratio(), Some(0.0));
+        assert_eq!(session.frames_per_second(), None);
+        assert_eq!(session.stats().parse_failures, 1);
+
+        session.reset();
+        assert_eq!(session.new_part_ratio(), None);
+        assert_eq!(session.stats(), ScanStats::default());
+        assert!(matches!(
+            session.receive("ur:bytes/aeadaolazmjendeoti", Duration::ZERO),
+            Err(Error::NotMultiPart)
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+//! The SHA-256 digest used to seed the random number generator.
+//!
+//! With the `bitcoin_hashes` feature, the digest is computed by the
+//! [`bitcoin_hashes`](https://docs.rs/bitcoin_hashes) crate. Otherwise, a
+//! portable implementation is used, which avoids the dependency in minimal
+//! builds. Both produce identical digests.
+
+/// Computes the SHA-256 digest of the data.
+#[cfg(feature = "bitcoin_hashes")]
+#[must_use]
+pub fn hash(data: &[u8]) -> [u8; 32] {
+    use bitcoin_hashes::Hash;
+    bitcoin_hashes::sha256::Hash::hash(data).to_byte_array()
+}
+
+/// Computes the SHA-256 digest of the data.
+#[cfg(not(feature = "bitcoin_hashes"))]
+#[must_use]
+pub fn hash(data: &[u8]) -> [u8; 32] {
+    portable(data)
+}
+
+#[cfg(any(test, not(feature = "bitcoin_hashes")))]
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a_2f98,
+    0x7137_4491,
+    0xb5c0_fbcf,
+    0xe9b5_dba5,
+    0x3956_c25b,
+    0x59f1_11f1,
+    0x923f_82a4,
+    0xab1c_5ed5,
+    0xd807_aa98,
+    0x1283_5b01,
+    0x2431_85be,
+    0x550c_7dc3,
+    0x72be_5d74,
+    0x80de_b1fe,
+    0x9bdc_06a7,
+    0xc19b_f174,
+    0xe49b_69c1,
+    0xefbe_4786,
+    0x0fc1_9dc6,
+    0x240c_a1cc,
+    0x2de9_2c6f,
+    0x4a74_84aa,
+    0x5cb0_a9dc,
+    0x76f9_88da,
+    0x983e_5152,
+    0xa831_c66d,
+    0xb003_27c8,
+    0xbf59_7fc7,
+    0xc6e0_0bf3,
+    0xd5a7_9147,
+    0x06ca_6351,
+    0x1429_2967,
+    0x27b7_0a85,
+    0x2e1b_2138,
+    0x4d2c_6dfc,
+    0x5338_0d13,
+    0x650a_7354,
+    0x766a_0abb,
+    0x81c2_c92e,
+    0x9272_2c85,
+    0xa2bf_e8a1,
+    0xa81a_664b,
+    0xc24b_8b70,
+    0xc76c_51a3,
+    0xd192_e819,
+    0xd699_0624,
+    0xf40e_3585,
+    0x106a_a070,
+    0x19a4_c116,
+    0x1e37_6c08,
+    0x2748_774c,
+    0x34b0_bcb5,
+    0x391c_0cb3,
+    0x4ed8_aa4a,
+    0x5b9c_ca4f,
+    0x682e_6ff3,
+    0x748f_82ee,
+    0x78a5_636f,
+    0x84c8_7814,
+    0x8cc7_0208,
+    0x90be_fffa,
+    0xa450_6ceb,
+    0xbef9_a3f7,
+    0xc671_78f2,
+];
+
+#[cfg(any(test, not(feature = "bitcoin_hashes")))]
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+#[cfg(any(test, not(feature = "bitcoin_hashes")))]
+fn portable(data: &[u8]) -> [u8; 32] {
+    let mut state = INITIAL_STATE;
+    let mut blocks = data.chunks_exact(64);
+    for block in &mut blocks {
+        compress(&mut state, block);
+    }
+
+    // the message is padded with a one bit, zeros and its length in bits
+    let remainder = blocks.remainder();
+    let mut padding = [0; 128];
+    padding[..remainder.len()].copy_from_slice(remainder);
+    padding[remainder.len()] = 0x80;
+    let padded_length = if remainder.len() < 56 { 64 } else { 128 };
+    let bit_length = (data.len() as u64).wrapping_mul(8);
+    padding[padded_length - 8..padded_length].copy_from_slice(&bit_length.to_be_bytes());
+    for block in padding[..padded_length].chunks_exact(64) {
+        compress(&mut state, block);
+    }
+
+    let mut digest = [0; 32];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(any(test, not(feature = "bitcoin_hashes")))]
+#[allow(clippy::many_single_char_names)]
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut schedule = [0_u32; 64];
+    for (word, bytes) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    for i in 16..64 {
+        let (w15, w2) = (schedule[i - 15], schedule[i - 2]);
+        let s0 = w15.rotate_right(7) ^ w15.rotate_right(18) ^ (w15 >> 3);
+        let s1 = w2.rotate_right(17) ^ w2.rotate_right(19) ^ (w2 >> 10);
+        schedule[i] = schedule[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(schedule[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (k, w) in ROUND_CONSTANTS.iter().zip(schedule) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(*k)
+            .wrapping_add(w);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable() {
+        let vectors = [
+            (
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (data, digest) in vectors {
+            assert_eq!(hex::encode(portable(data.as_bytes())), digest);
+            assert_eq!(hex::encode(hash(data.as_bytes())), digest);
+        }
+    }
+
+    #[test]
+    fn test_portable_padding() {
+        // the lengths around the block boundary need one or two padding blocks
+        let vectors = [
+            (
+                55,
+                "463eb28e72f82e0a96c0a4cc53690c571281131f672aa229e0d45ae59b598b59",
+            ),
+            (
+                56,
+                "da2ae4d6b36748f2a318f23e7ab1dfdf45acdc9d049bd80e59de82a60895f562",
+            ),
+            (
+                63,
+                "29af2686fd53374a36b0846694cc342177e428d1647515f078784d69cdb9e488",
+            ),
+            (
+                64,
+                "fdeab9acf3710362bd2658cdc9a29e8f9c757fcf9811603a8c447cd1d9151108",
+            ),
+            (
+                65,
+                "4bfd2c8b6f1eec7a2afeb48b934ee4b2694182027e6d0fc075074f2fabb31781",
+            ),
+            (
+                119,
+                "da18797ed7c3a777f0847f429724a2d8cd5138e6ed2895c3fa1a6d39d18f7ec6",
+            ),
+            (
+                120,
+                "f52b23db1fbb6ded89ef42a23ce0c8922c45f25c50b568a93bf1c075420bbb7c",
+            ),
+            (
+                128,
+                "471fb943aa23c511f6f72f8d1652d9c880cfa392ad80503120547703e56a2be5",
+            ),
+        ];
+        let data: Vec<u8> = (0..=u8::MAX).collect();
+        for (length, digest) in vectors {
+            assert_eq!(hex::encode(portable(&data[..length])), digest);
+        }
+    }
+
+    #[cfg(feature = "bitcoin_hashes")]
+    #[test]
+    fn test_portable_lengths() {
+        // covers all padding cases and multiple blocks
+        let data: Vec<u8> = (0..=u8::MAX).collect();
+        for length in 0..data.len() {
+            assert_eq!(portable(&data[..length]), hash(&data[..length]));
+        }
+    }
+}
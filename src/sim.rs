@@ -0,0 +1,361 @@
+//! Simulated transfers of fountain-encoded messages over lossy channels.
+//!
+//! A [`Simulation`] wires a fountain [`Encoder`] to a [`Decoder`] through a
+//! [`Channel`] which drops, duplicates, reorders and corrupts parts, and reports
+//! the number of parts each transfer needed. This is useful to tune fragment
+//! lengths and animation loops, and to check that changes to the decoder don't
+//! regress its efficiency. Simulations are deterministic.
+//!
+//! # Examples
+//!
+//! ```
+//! use ur::sim::{Channel, Simulation};
+//! let simulation = Simulation {
+//!     channel: Channel {
+//!         drop_rate: 0.3,
+//!         reorder_window: 4,
+//!         ..Channel::default()
+//!     },
+//!     ..Simulation::default()
+//! };
+//! let report = simulation.run().unwrap();
+//! assert_eq!(report.success_rate(), 1.0);
+//! let median = report.parts_received(0.5).unwrap();
+//! assert!(median > 10 && median <= report.parts_received(0.9).unwrap());
+//! ```
+
+extern crate alloc;
+use crate::fountain::{Decoder, Encoder, Error, Part};
+use alloc::vec::Vec;
+use rand_xoshiro::rand_core::RngCore;
+
+/// A model of a lossy channel between the encoder and the decoder. Each
+/// emitted part is subject to all of the channel effects independently.
+///
+/// The default channel delivers all parts unaltered and in order.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Channel {
+    /// The probability that a part is lost.
+    pub drop_rate: f64,
+    /// The probability that a part is delivered twice.
+    pub duplicate_rate: f64,
+    /// The number of parts held back by the channel, of which a random one is
+    /// delivered once the window is full. Parts are delivered in order if zero.
+    pub reorder_window: usize,
+    /// The probability that a bit of the part data is flipped.
+    pub corrupt_rate: f64,
+}
+
+/// The parameters of a simulation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Simulation {
+    /// The length of the simulated messages.
+    pub message_length: usize,
+    /// The maximum fragment length of the encoder.
+    pub max_fragment_length: usize,
+    /// The channel the parts are sent through.
+    pub channel: Channel,
+    /// The number of simulated transfers.
+    pub trials: usize,
+    /// The number of parts after which a transfer is considered stalled.
+    pub max_parts: usize,
+    /// Whether the decoder [solves](Decoder::solve) the buffered mixed parts
+    /// after each part which doesn't complete it.
+    pub solve: bool,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self {
+            message_length: 1000,
+            max_fragment_length: 100,
+            channel: Channel::default(),
+            trials: 100,
+            max_parts: 10_000,
+            solve: false,
+        }
+    }
+}
+
+/// The outcome of a simulated transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trial {
+    /// The number of parts emitted by the encoder.
+    pub parts_emitted: usize,
+    /// The number of parts delivered to the decoder, including duplicates.
+    pub parts_received: usize,
+    /// Whether the decoder completed before the transfer stalled.
+    pub complete: bool,
+    /// Whether the decoded message differs from the sent one, which can only
+    /// happen on channels corrupting parts.
+    pub corrupted: bool,
+}
+
+/// The outcomes of all transfers of a [`Simulation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// The simulated transfers.
+    pub trials: Vec<Trial>,
+}
+
+impl Report {
+    /// Returns the fraction of transfers which completed with the sent message.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::sim`] module documentation for an example.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn success_rate(&self) -> f64 {
+        if self.trials.is_empty() {
+            return 0.0;
+        }
+        let successes = self.successes().count();
+        successes as f64 / self.trials.len() as f64
+    }
+
+    /// Returns the number of parts the decoder needed to receive in the given
+    /// quantile of the successful transfers, e.g. the median for `0.5`. Returns
+    /// `None` if no transfer succeeded or the quantile isn't within `[0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::sim`] module documentation for an example.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn parts_received(&self, quantile: f64) -> Option<usize> {
+        if !(0.0..=1.0).contains(&quantile) {
+            return None;
+        }
+        let mut parts: Vec<usize> = self.successes().map(|t| t.parts_received).collect();
+        parts.sort_unstable();
+        let index = (quantile * (parts.len().checked_sub(1)? as f64)) as usize;
+        Some(parts[index])
+    }
+
+    /// Returns the average number of parts the decoder needed to receive in the
+    /// successful transfers, or `None` if no transfer succeeded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let report = ur::sim::Simulation::default().run().unwrap();
+    /// assert_eq!(report.mean_parts_received(), Some(10.0));
+    /// ```
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mean_parts_received(&self) -> Option<f64> {
+        let count = self.successes().count();
+        let sum: usize = self.successes().map(|t| t.parts_received).sum();
+        (count > 0).then(|| sum as f64 / count as f64)
+    }
+
+    fn successes(&self) -> impl Iterator<Item = &Trial> {
+        self.trials.iter().filter(|t| t.complete && !t.corrupted)
+    }
+}
+
+impl Simulation {
+    /// Runs the simulated transfers, each with a different message.
+    ///
+    /// # Examples
+    ///
+    /// See the [`crate::sim`] module documentation for an example.
+    ///
+    /// # Errors
+    ///
+    /// If the encoder can't be constructed for the message and fragment
+    /// lengths, an error will be returned.
+    pub fn run(&self) -> Result<Report, Error> {
+        let mut rng = crate::xoshiro::Xoshiro256::from("sim");
+        let trials = (0..self.trials)
+            .map(|_| self.trial(&mut rng))
+            .collect::<Result<_, _>>()?;
+        Ok(Report { trials })
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn trial(&self, rng: &mut crate::xoshiro::Xoshiro256) -> Result<Trial, Error> {
+        let channel = &self.channel;
+        let mut message = alloc::vec![0; self.message_length];
+        rng.fill_bytes(&mut message);
+        let mut encoder = Encoder::new(&message, self.max_fragment_length)?;
+        let mut decoder = Decoder::default();
+        let mut window = Vec::new();
+        let mut parts_received = 0;
+        while !decoder.complete() && encoder.current_sequence() < self.max_parts {
+            let mut part = encoder.next_part();
+            if rng.next_double() < channel.drop_rate {
+                continue;
+            }
+            if rng.next_double() < channel.corrupt_rate {
+                part = corrupt(&part, rng);
+            }
+            if rng.next_double() < channel.duplicate_rate {
+                window.push(part.clone());
+            }
+            window.push(part);
+            while window.len() > channel.reorder_window && !decoder.complete() {
+                let index = if channel.reorder_window == 0 {
+                    0
+                } else {
+                    rng.next_int(0, window.len() as u64 - 1) as usize
+                };
+                // corrupted parts may be rejected as inconsistent
+                decoder.receive(window.remove(index)).ok();
+                if self.solve && !decoder.complete() {
+                    decoder.solve().ok();
+                }
+                parts_received += 1;
+            }
+        }
+        let corrupted = match decoder.message() {
+            Ok(Some(received)) => received != message,
+            Ok(None) => false,
+            Err(_) => true,
+        };
+        Ok(Trial {
+            parts_emitted: encoder.current_sequence(),
+            parts_received,
+            complete: decoder.complete(),
+            corrupted,
+        })
+    }
+}
+
+/// Returns a copy of the part with a random bit of its data flipped.
+#[allow(clippy::cast_possible_truncation)]
+fn corrupt(part: &Part, rng: &mut crate::xoshiro::Xoshiro256) -> Part {
+    let mut data = part.data().to_vec();
+    let bit = rng.next_int(0, data.len() as u64 * 8 - 1) as usize;
+    data[bit / 8] ^= 1 << (bit % 8);
+    Part::new(
+        part.sequence(),
+        part.sequence_count(),
+        part.message_length(),
+        part.checksum(),
+        data,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lossless() {
+        let report = Simulation::default().run().unwrap();
+        assert_eq!(report.trials.len(), 100);
+        for trial in &report.trials {
+            assert_eq!(
+                *trial,
+                Trial {
+                    parts_emitted: 10,
+                    parts_received: 10,
+                    complete: true,
+                    corrupted: false,
+                }
+            );
+        }
+        assert_eq!(report.parts_received(0.0), Some(10));
+        assert_eq!(report.parts_received(1.0), Some(10));
+        assert_eq!(report.parts_received(1.5), None);
+    }
+
+    #[test]
+    fn test_channel_effects() {
+        let lossy = Channel {
+            drop_rate: 0.5,
+            ..Channel::default()
+        };
+        let report = Simulation {
+            channel: lossy,
+            ..Simulation::default()
+        }
+        .run()
+        .unwrap();
+        assert_eq!(report.success_rate(), 1.0);
+        let emitted: usize = report.trials.iter().map(|t| t.parts_emitted).sum();
+        let received: usize = report.trials.iter().map(|t| t.parts_received).sum();
+        assert!(emitted > received * 3 / 2);
+        assert!(report.mean_parts_received().unwrap() > 10.0);
+
+        let duplicating = Channel {
+            duplicate_rate: 0.5,
+            reorder_window: 5,
+            ..Channel::default()
+        };
+        let report = Simulation {
+            channel: duplicating,
+            ..Simulation::default()
+        }
+        .run()
+        .unwrap();
+        assert_eq!(report.success_rate(), 1.0);
+        assert!(report.mean_parts_received().unwrap() > 10.0);
+
+        let corrupting = Channel {
+            corrupt_rate: 0.2,
+            ..Channel::default()
+        };
+        let report = Simulation {
+            channel: corrupting,
+            ..Simulation::default()
+        }
+        .run()
+        .unwrap();
+        assert!(report.success_rate() < 1.0);
+        assert!(report.trials.iter().any(|t| t.corrupted));
+    }
+
+    #[test]
+    fn test_solve() {
+        let lossy = Simulation {
+            message_length: 3000,
+            channel: Channel {
+                drop_rate: 0.5,
+                ..Channel::default()
+            },
+            ..Simulation::default()
+        };
+        let peeled = lossy.run().unwrap().mean_parts_received().unwrap();
+        let solving = Simulation {
+            solve: true,
+            ..lossy
+        };
+        let solved = solving.run().unwrap().mean_parts_received().unwrap();
+        assert!(30.0 < solved && solved < peeled);
+    }
+
+    #[test]
+    fn test_stalled() {
+        let report = Simulation {
+            channel: Channel {
+                drop_rate: 0.9,
+                ..Channel::default()
+            },
+            max_parts: 10,
+            ..Simulation::default()
+        }
+        .run()
+        .unwrap();
+        assert!(report.trials.iter().all(|t| t.parts_emitted == 10));
+        assert!(report.trials.iter().any(|t| !t.complete));
+        assert_eq!(report.success_rate(), 0.0);
+        assert_eq!(report.parts_received(0.5), None);
+        assert_eq!(report.mean_parts_received(), None);
+    }
+
+    #[test]
+    fn test_invalid_encoder() {
+        let simulation = Simulation {
+            max_fragment_length: 0,
+            ..Simulation::default()
+        };
+        assert!(matches!(simulation.run(), Err(Error::InvalidFragmentLen)));
+    }
+}
@@ -29,12 +29,41 @@ extern crate alloc;
 use alloc::{string::String, vec::Vec};
 
 /// Errors that can happen during encoding and decoding of URs.
+///
+/// This is the crate-level error, returned by the functions of this module,
+/// which are also re-exported at the crate root. Every other error of the
+/// public API converts into it, so that callers combining the modules can
+/// propagate a single error type with `?`:
+///  - the errors of the [`crate::bytewords`], [`crate::fountain`] and
+///    [`crate::sampler`] modules,
+///  - the CBOR errors of decoding fountain parts and registry types, which are
+///    reported as [`crate::fountain::Error::CborDecode`] and
+///    [`crate::fountain::Error::CborEncode`].
+///
+/// The functions of the other modules deliberately keep returning their own,
+/// more specific errors.
+///
+/// # Examples
+///
+/// ```
+/// fn decode_part(bytewords: &str) -> Result<ur::fountain::Part, ur::Error> {
+///     let cbor = ur::bytewords::decode(bytewords, ur::bytewords::Style::Minimal)?;
+///     Ok(minicbor::decode(&cbor)?)
+/// }
+/// assert!(matches!(
+///     decode_part("lpadaxbkcyhpgyiabsfrftvojp"),
+///     Err(ur::Error::Bytewords(_))
+/// ));
+/// ```
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
     /// A bytewords error.
     Bytewords(crate::bytewords::Error),
     /// A fountain error.
     Fountain(crate::fountain::Error),
+    /// A sampler error.
+    Sampler(crate::sampler::Error),
     /// Invalid scheme.
     InvalidScheme,
     /// No type specified.
@@ -45,6 +74,10 @@ pub enum Error {
     InvalidIndices,
     /// Tried to decode a single-part UR as multi-part.
     NotMultiPart,
+    /// The payload exceeds the maximum length.
+    PayloadTooLarge,
+    /// The UR is of a different type than expected.
+    UnexpectedType,
 }
 
 impl core::fmt::Display for Error {
@@ -52,15 +85,73 @@ impl core::fmt::Display for Error {
         match self {
             Self::Bytewords(e) => write!(f, "{e}"),
             Self::Fountain(e) => write!(f, "{e}"),
+            Self::Sampler(e) => write!(f, "{e}"),
             Self::InvalidScheme => write!(f, "Invalid scheme"),
             Self::TypeUnspecified => write!(f, "No type specified"),
             Self::InvalidCharacters => write!(f, "Type contains invalid characters"),
             Self::InvalidIndices => write!(f, "Invalid indices"),
             Self::NotMultiPart => write!(f, "Can't decode single-part UR as multi-part"),
+            Self::PayloadTooLarge => write!(f, "Payload exceeds the maximum length"),
+            Self::UnexpectedType => write!(f, "Unexpected UR type"),
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Bytewords(e) => Some(e),
+            Self::Fountain(e) => Some(e),
+            Self::Sampler(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// Returns guidance on how to recover from the error, suitable to be shown
+    /// to users instead of the bare error, or `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut decoder = ur::Decoder::default();
+    /// let error = decoder.receive("ur:bytes/1-2/lpadaoaacypmwfwfiafwiehsdpcynbe").unwrap_err();
+    /// assert_eq!(error.hint(), Some("the input may be truncated, scan it again"));
+    /// let error = decoder.receive("UR:BYTES/1-2/LPADAOAACYPMWFWFIAFWIEHSDPCYNBEM").unwrap_err();
+    /// assert_eq!(
+    ///     error.hint(),
+    ///     Some("URs start with \"ur:\", convert uppercase URs to lowercase before decoding")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn hint(&self) -> Option<&'static str> {
+        let hint = match self {
+            Self::Bytewords(e) => return e.hint(),
+            Self::Fountain(e) => return e.hint(),
+            Self::Sampler(_) => "weights must be finite, non-negative and sum to a positive value",
+            Self::InvalidScheme => {
+                "URs start with \"ur:\", convert uppercase URs to lowercase before decoding"
+            }
+            Self::TypeUnspecified => "URs name their type followed by \"/\", e.g. \"ur:bytes/\"",
+            Self::InvalidCharacters => {
+                "types consist of lowercase letters, digits and hyphens only"
+            }
+            Self::InvalidIndices => {
+                "multi-part URs carry their sequence as in \"ur:bytes/1-3/\", the input may be \
+                 truncated"
+            }
+            Self::NotMultiPart => "decode single-part URs with `decode` instead of a `Decoder`",
+            Self::PayloadTooLarge => {
+                "raise the maximum payload length if the payload is expected to be this large"
+            }
+            Self::UnexpectedType => "the scanned UR isn't of the kind expected here",
+        };
+        Some(hint)
+    }
+}
+
 impl From<crate::bytewords::Error> for Error {
     fn from(e: crate::bytewords::Error) -> Self {
         Self::Bytewords(e)
@@ -73,8 +164,28 @@ impl From<crate::fountain::Error> for Error {
     }
 }
 
+impl From<crate::sampler::Error> for Error {
+    fn from(e: crate::sampler::Error) -> Self {
+        Self::Sampler(e)
+    }
+}
+
+impl From<minicbor::decode::Error> for Error {
+    fn from(e: minicbor::decode::Error) -> Self {
+        Self::Fountain(e.into())
+    }
+}
+
+impl From<minicbor::encode::Error<core::convert::Infallible>> for Error {
+    fn from(e: minicbor::encode::Error<core::convert::Infallible>) -> Self {
+        Self::Fountain(e.into())
+    }
+}
+
 /// Encodes a data payload into a single URI
 ///
+/// Unlike the multi-part [`Encoder`], this also supports empty payloads.
+///
 /// # Examples
 ///
 /// ```
@@ -82,6 +193,7 @@ impl From<crate::fountain::Error> for Error {
 ///     ur::ur::encode(b"data", &ur::Type::Bytes),
 ///     "ur:bytes/iehsjyhspmwfwfia"
 /// );
+/// assert_eq!(ur::ur::encode(b"", &ur::Type::Bytes), "ur:bytes/aeaeaeae");
 /// ```
 #[must_use]
 pub fn encode(data: &[u8], ur_type: &Type) -> String {
@@ -89,6 +201,88 @@ pub fn encode(data: &[u8], ur_type: &Type) -> String {
     alloc::format!("ur:{}/{body}", ur_type.encoding())
 }
 
+/// The number of words per line of [`to_readable`].
+const READABLE_LINE_WORDS: usize = 4;
+
+/// Renders a single URI (either single- or multi-part) for reading aloud or
+/// manual transcription.
+///
+/// The first line holds the type and, for multi-part URs, the sequence
+/// identifier. It is followed by the payload in standard `bytewords`, four
+/// words per line. Use [`from_readable`] to parse the rendering back.
+///
+/// # Examples
+///
+/// ```
+/// let readable = ur::ur::to_readable("ur:bytes/1-2/iehsjyhspmwfwfia").unwrap();
+/// assert_eq!(readable, "ur:bytes/1-2\nidle huts jury huts\npoem wolf wolf idea");
+/// ```
+///
+/// # Errors
+///
+/// This function errors for the same inputs as [`decode`].
+pub fn to_readable(value: &str) -> Result<String, Error> {
+    use core::fmt::Write;
+
+    let parsed = decode(value)?;
+    let mut readable = String::from("ur:");
+    readable.push_str(parsed.ur_type());
+    if let ParsedUr::MultiPart {
+        sequence,
+        sequence_count,
+        ..
+    } = parsed
+    {
+        // writing to a string can't fail
+        let _ = write!(readable, "/{sequence}-{sequence_count}");
+    }
+    let words = crate::bytewords::encode(parsed.payload(), crate::bytewords::Style::Standard);
+    for (index, word) in words.split(' ').enumerate() {
+        readable.push(if index % READABLE_LINE_WORDS == 0 {
+            '\n'
+        } else {
+            ' '
+        });
+        readable.push_str(word);
+    }
+    Ok(readable)
+}
+
+/// Parses a UR rendered by [`to_readable`] back into its URI.
+///
+/// The words may be separated by any whitespace and are case-insensitive, so
+/// manual transcriptions don't have to preserve the line breaks.
+///
+/// # Examples
+///
+/// ```
+/// let uri = ur::ur::from_readable("UR:BYTES\nIdle Huts Jury Huts  poem wolf wolf idea").unwrap();
+/// assert_eq!(uri, "ur:bytes/iehsjyhspmwfwfia");
+/// ```
+///
+/// # Errors
+///
+/// If the header isn't a valid UR prefix or the words aren't valid standard
+/// `bytewords`, e.g. because of a transcription error caught by the checksum,
+/// an error will be returned.
+pub fn from_readable(text: &str) -> Result<String, Error> {
+    let text = text.trim();
+    let (header, words) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let words = words
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_ascii_lowercase();
+    let payload = crate::bytewords::decode(&words, crate::bytewords::Style::Standard)?;
+    let uri = alloc::format!(
+        "{}/{}",
+        header.to_ascii_lowercase(),
+        crate::bytewords::encode(&payload, crate::bytewords::Style::Minimal)
+    );
+    parse(&uri)?;
+    Ok(uri)
+}
+
 /// The type of uniform resource.
 pub enum Type<'a> {
     /// A `bytes` uniform resource.
@@ -98,7 +292,7 @@ pub enum Type<'a> {
 }
 
 impl<'a> Type<'a> {
-    const fn encoding(&self) -> &'a str {
+    pub(crate) const fn encoding(&self) -> &'a str {
         match self {
             Self::Bytes => "bytes",
             Self::Custom(s) => s,
@@ -111,9 +305,12 @@ impl<'a> Type<'a> {
 /// # Examples
 ///
 /// See the [`crate::ur`] module documentation for an example.
-pub struct Encoder<'a> {
-    fountain: crate::fountain::Encoder,
+pub struct Encoder<'a, M = Vec<u8>> {
+    fountain: crate::fountain::Encoder<crate::fountain::XoshiroScheduler, M>,
     ur_type: Type<'a>,
+    padded: bool,
+    part: crate::fountain::Part,
+    cbor: Vec<u8>,
 }
 
 impl<'a> Encoder<'a> {
@@ -128,14 +325,11 @@ impl<'a> Encoder<'a> {
     /// # Errors
     ///
     /// If an empty message or a zero maximum fragment length is passed, an error
-    /// will be returned.
+    /// will be returned. Use [`encode`] to transmit empty messages as a single-part UR.
     ///
     /// [`bytes`]: Type::Bytes
     pub fn bytes(message: &[u8], max_fragment_length: usize) -> Result<Self, Error> {
-        Ok(Self {
-            fountain: crate::fountain::Encoder::new(message, max_fragment_length)?,
-            ur_type: Type::Bytes,
-        })
+        Self::from_source(message.to_vec(), max_fragment_length, Type::Bytes)
     }
 
     /// Creates a new [`custom`] [`Encoder`] for given a message payload.
@@ -145,16 +339,121 @@ impl<'a> Encoder<'a> {
     /// # Errors
     ///
     /// If an empty message or a zero maximum fragment length is passed, an error
-    /// will be returned.
+    /// will be returned. Use [`encode`] to transmit empty messages as a single-part UR.
     ///
     /// [`custom`]: Type::Custom
     pub fn new(message: &[u8], max_fragment_length: usize, s: &'a str) -> Result<Self, Error> {
+        Self::from_source(message.to_vec(), max_fragment_length, Type::Custom(s))
+    }
+}
+
+impl<'a, M: AsRef<[u8]>> Encoder<'a, M> {
+    /// Creates a new [`Encoder`] of the given type, reading the fragments from
+    /// the given message source without copying it.
+    ///
+    /// See [`crate::fountain::Encoder::from_source`] for the supported sources.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let firmware = vec![7; 1000];
+    /// let mut encoder = ur::Encoder::from_source(&firmware[..], 200, ur::Type::Bytes).unwrap();
+    /// assert_eq!(encoder.fragment_count(), 5);
+    /// assert!(encoder.next_part().unwrap().starts_with("ur:bytes/1-5/"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an empty message or a zero maximum fragment length is passed, an error
+    /// will be returned. Use [`encode`] to transmit empty messages as a single-part UR.
+    pub fn from_source(
+        message: M,
+        max_fragment_length: usize,
+        ur_type: Type<'a>,
+    ) -> Result<Self, Error> {
         Ok(Self {
-            fountain: crate::fountain::Encoder::new(message, max_fragment_length)?,
-            ur_type: Type::Custom(s),
+            fountain: crate::fountain::Encoder::from_source(message, max_fragment_length)?,
+            ur_type,
+            padded: false,
+            part: crate::fountain::Part::new(0, 0, 0, 0, Vec::new()),
+            cbor: Vec::new(),
         })
     }
 
+    /// Creates a new [`Encoder`] of the given type, choosing the longest
+    /// fragments for which no emitted URI exceeds the given number of characters.
+    ///
+    /// The `seq-count` path component and the CBOR-encoded sequence number grow
+    /// with the sequence number, such that later parts are longer than the
+    /// first ones. The fragment length accounts for the worst case of any
+    /// sequence number, so all parts fit into the same QR code version, which
+    /// avoids the animation jumping between sizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let message = vec![7; 1000];
+    /// let mut encoder = ur::Encoder::for_max_part_length(&message[..], 200, ur::Type::Bytes).unwrap();
+    /// assert!(encoder.next_part().unwrap().len() <= 200);
+    /// assert!(encoder.part_at(u32::MAX as usize).unwrap().len() <= 200);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If an empty message is passed, or if the maximum part length is too
+    /// short to carry a single byte of the message, an error will be returned.
+    pub fn for_max_part_length(
+        message: M,
+        max_part_length: usize,
+        ur_type: Type<'a>,
+    ) -> Result<Self, Error> {
+        let message_length = message.as_ref().len();
+        let mut max_fragment_length = (max_part_length / 2).min(message_length);
+        while max_fragment_length > 0 {
+            let fragment_length =
+                crate::fountain::bounded_fragment_length(message_length, 1, max_fragment_length);
+            let fragment_count = crate::fountain::div_ceil(message_length, fragment_length);
+            let part_length = max_part_length_of(
+                ur_type.encoding().len(),
+                fragment_count,
+                message_length,
+                fragment_length,
+            );
+            if part_length <= max_part_length {
+                return Self::from_source(message, fragment_length, ur_type);
+            }
+            max_fragment_length = fragment_length - 1;
+        }
+        if message_length == 0 {
+            return Err(crate::fountain::Error::EmptyMessage.into());
+        }
+        Err(crate::fountain::Error::InvalidFragmentLen.into())
+    }
+
+    /// Zero-pads the sequence numbers in the path of the emitted URIs to the
+    /// width of the sequence count, e.g. `ur:bytes/001-230/...`.
+    ///
+    /// Without padding the URIs grow whenever the sequence number gains a
+    /// digit, which makes animated QR codes jump in size. Note that the
+    /// CBOR-encoded sequence number in the payload still grows at the sequence
+    /// numbers 24 and 256, see [`Encoder::for_max_part_length`] for an upper
+    /// bound on the length of all parts. Decoders accept padded sequence
+    /// numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(&[0; 100], 10).unwrap().with_padded_sequence();
+    /// let first = encoder.next_part().unwrap();
+    /// assert!(first.starts_with("ur:bytes/01-10/"));
+    /// assert_eq!(encoder.part_at(10).unwrap().len(), first.len());
+    /// ```
+    #[must_use]
+    pub const fn with_padded_sequence(mut self) -> Self {
+        self.padded = true;
+        self
+    }
+
     /// Returns the URI corresponding to next fountain part.
     ///
     /// # Examples
@@ -166,12 +465,159 @@ impl<'a> Encoder<'a> {
     /// If serialization fails an error will be returned.
     pub fn next_part(&mut self) -> Result<String, Error> {
         let part = self.fountain.next_part();
-        let body = crate::bytewords::encode(&part.cbor()?, crate::bytewords::Style::Minimal);
-        Ok(alloc::format!(
-            "ur:{}/{}/{body}",
-            self.ur_type.encoding(),
-            part.sequence_id()
-        ))
+        self.encode_part(&part)
+    }
+
+    /// Writes the URI corresponding to next fountain part into the given string,
+    /// replacing its contents.
+    ///
+    /// Unlike [`next_part`], this reuses the allocations of the string and of
+    /// the encoder between parts, which avoids allocating on every frame when
+    /// animating a QR code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// let mut part = String::new();
+    /// encoder.next_part_into(&mut part).unwrap();
+    /// assert_eq!(part, "ur:bytes/1-3/lpadaxbkcywkwmhfwnfyghihjtcxfwfrbahy");
+    /// encoder.next_part_into(&mut part).unwrap();
+    /// assert_eq!(part, "ur:bytes/2-3/lpaoaxbkcywkwmhfwnfyiaishsjpmkdecasf");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If serialization fails an error will be returned.
+    ///
+    /// [`next_part`]: Encoder::next_part
+    pub fn next_part_into(&mut self, part: &mut String) -> Result<(), Error> {
+        self.fountain.next_part_into(&mut self.part);
+        part.clear();
+        self.part
+            .write_ur_string(&self.ur_type, &mut self.cbor, part, self.padded)
+            .map_err(Error::from)
+    }
+
+    /// Returns the URI corresponding to the fountain part with the given
+    /// sequence number, without advancing the encoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// let second = encoder.part_at(2).unwrap();
+    /// assert_eq!(second, "ur:bytes/2-3/lpaoaxbkcywkwmhfwnfyiaishsjpmkdecasf");
+    /// encoder.next_part().unwrap();
+    /// assert_eq!(encoder.next_part().unwrap(), second);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If serialization fails an error will be returned.
+    ///
+    /// # Panics
+    ///
+    /// Sequence numbers start at one and are transmitted as 32-bit integers,
+    /// so this panics if `sequence` is zero or exceeds `u32::MAX`.
+    pub fn part_at(&self, sequence: usize) -> Result<String, Error> {
+        self.encode_part(&self.fountain.part_at(sequence))
+    }
+
+    /// Returns the URI of the part most recently returned by [`next_part`],
+    /// without advancing the encoder, or `None` if no part was emitted yet.
+    ///
+    /// This lets a paused UI keep re-rendering the same frame, and only advance
+    /// the animation on its own timer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// assert_eq!(encoder.current_part().unwrap(), None);
+    /// let part = encoder.next_part().unwrap();
+    /// assert_eq!(encoder.current_part().unwrap(), Some(part.clone()));
+    /// assert_eq!(encoder.current_part().unwrap(), Some(part));
+    /// assert_eq!(encoder.current_index(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If serialization fails an error will be returned.
+    ///
+    /// [`next_part`]: Encoder::next_part
+    pub fn current_part(&self) -> Result<Option<String>, Error> {
+        match self.current_index() {
+            0 => Ok(None),
+            sequence => self.part_at(sequence).map(Some),
+        }
+    }
+
+    /// Moves the encoder to the given sequence number, such that the next
+    /// emitted part is the one with this sequence number.
+    ///
+    /// This lets a resumed or mirrored display align with another device which
+    /// is already partway through the part stream of the same message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(&[7; 100], 10).unwrap();
+    /// let shown = encoder.part_at(57).unwrap();
+    /// assert!(shown.starts_with("ur:bytes/57-10/"));
+    /// // a second display joins after the part with sequence number 57
+    /// let mut mirror = ur::Encoder::bytes(&[7; 100], 10).unwrap();
+    /// mirror.skip_to(58).unwrap();
+    /// assert_eq!(mirror.current_index(), 57);
+    /// assert!(mirror.next_part().unwrap().starts_with("ur:bytes/58-10/"));
+    /// assert!(matches!(mirror.skip_to(0), Err(ur::Error::InvalidIndices)));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Sequence numbers start at one and are transmitted as 32-bit integers,
+    /// so [`Error::InvalidIndices`] is returned if `sequence` is zero or
+    /// exceeds `u32::MAX`.
+    pub fn skip_to(&mut self, sequence: usize) -> Result<(), Error> {
+        if sequence == 0 || u32::try_from(sequence).is_err() {
+            return Err(Error::InvalidIndices);
+        }
+        self.fountain.set_sequence(sequence - 1);
+        Ok(())
+    }
+
+    /// Focuses the upcoming parts on the message segments the receiver reports
+    /// missing. See [`crate::fountain::Encoder::prioritize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let message = b"Ten chars!".repeat(10);
+    /// let mut encoder = ur::Encoder::bytes(&message, 4).unwrap();
+    /// let mut decoder = ur::Decoder::default();
+    /// // the receiver only catches every third part of the first pass
+    /// for _ in 0..encoder.fragment_count() {
+    ///     let part = encoder.next_part().unwrap();
+    ///     if encoder.current_index() % 3 == 0 {
+    ///         decoder.receive(&part).unwrap();
+    ///     }
+    /// }
+    /// // and shows its acknowledgment back to the sender
+    /// let ack = ur::ur::decode_ack(&decoder.ack().unwrap().unwrap()).unwrap();
+    /// encoder.prioritize(ack.missing().iter());
+    /// for _ in 0..ack.missing().len() {
+    ///     decoder.receive(&encoder.next_part().unwrap()).unwrap();
+    /// }
+    /// assert_eq!(decoder.message().unwrap(), Some(message));
+    /// ```
+    pub fn prioritize<I: IntoIterator<Item = usize>>(&mut self, missing_indexes: I) {
+        self.fountain.prioritize(missing_indexes);
+    }
+
+    fn encode_part(&self, part: &crate::fountain::Part) -> Result<String, Error> {
+        let mut ur = String::new();
+        part.write_ur_string(&self.ur_type, &mut Vec::new(), &mut ur, self.padded)?;
+        Ok(ur)
     }
 
     /// Returns the current count of already emitted parts.
@@ -201,33 +647,406 @@ impl<'a> Encoder<'a> {
     pub fn fragment_count(&self) -> usize {
         self.fountain.fragment_count()
     }
+
+    /// Returns whether `redundancy` times as many parts as there are segments
+    /// have been emitted, treating redundancies below `1.0` and NaN as `1.0`.
+    /// See [`crate::fountain::Encoder::complete_with_redundancy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(&[7; 100], 10).unwrap();
+    /// let mut parts = Vec::new();
+    /// while !encoder.complete_with_redundancy(2.0) {
+    ///     parts.push(encoder.next_part().unwrap());
+    /// }
+    /// assert_eq!(parts.len(), 20);
+    /// ```
+    #[must_use]
+    pub fn complete_with_redundancy(&self, redundancy: f64) -> bool {
+        self.fountain.complete_with_redundancy(redundancy)
+    }
+
+    /// Returns the length of the message segments carried by each part. See
+    /// [`crate::fountain::Encoder::fragment_length`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// assert_eq!(encoder.fragment_length(), 4);
+    /// assert_eq!(encoder.padding_length(), 2);
+    /// ```
+    #[must_use]
+    pub const fn fragment_length(&self) -> usize {
+        self.fountain.fragment_length()
+    }
+
+    /// Returns the number of zero bytes padding the last message segment. See
+    /// [`crate::fountain::Encoder::padding_length`].
+    ///
+    /// # Examples
+    ///
+    /// See [`Encoder::fragment_length`] for an example.
+    #[must_use]
+    pub const fn padding_length(&self) -> usize {
+        self.fountain.padding_length()
+    }
+
+    /// Returns the checksum of the message as a phrase of four standard
+    /// `bytewords`, e.g. `able acid also lava`.
+    ///
+    /// Displaying the phrase on the sending and the receiving screen lets users
+    /// verify that both devices handle the same payload, see
+    /// [`Decoder::checksum_phrase`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let encoder = ur::Encoder::bytes(b"data", 3).unwrap();
+    /// assert_eq!(encoder.checksum_phrase(), "poem wolf wolf idea");
+    /// ```
+    #[must_use]
+    pub fn checksum_phrase(&self) -> String {
+        checksum_phrase(self.fountain.checksum())
+    }
+}
+
+/// An adapter splitting the parts of an [`Encoder`] across several
+/// simultaneously displayed QR codes.
+///
+/// The parts are dealt round-robin, such that each display shows a distinct
+/// part at any time and the displays together advance through the part
+/// stream as many times faster. Scanning the displays with a single
+/// [`Decoder`], or with one decoder per display which are combined with
+/// [`Decoder::merge`], recovers the message.
+///
+/// # Examples
+///
+/// ```
+/// use core::num::NonZeroUsize;
+/// let encoder = ur::Encoder::bytes(&[7; 100], 10).unwrap();
+/// let mut displays = ur::ur::MultiDisplay::new(encoder, NonZeroUsize::new(3).unwrap());
+/// let mut decoder = ur::Decoder::default();
+/// let mut frames = 0;
+/// while !decoder.complete() {
+///     for part in displays.next_parts().unwrap() {
+///         decoder.receive(&part).unwrap();
+///     }
+///     frames += 1;
+/// }
+/// assert_eq!(frames, 4);
+/// ```
+pub struct MultiDisplay<'a, M = Vec<u8>> {
+    encoder: Encoder<'a, M>,
+    display_count: core::num::NonZeroUsize,
 }
 
-/// An enum used to indicate whether a UR is single- or
-/// multip-part. See e.g. [`decode`] where it is returned.
-#[derive(Debug, PartialEq, Eq)]
-pub enum Kind {
+impl<'a, M: AsRef<[u8]>> MultiDisplay<'a, M> {
+    /// Creates an adapter dealing the parts of the given encoder to the given
+    /// number of displays.
+    ///
+    /// # Examples
+    ///
+    /// See the [`MultiDisplay`] documentation for an example.
+    #[must_use]
+    pub const fn new(encoder: Encoder<'a, M>, display_count: core::num::NonZeroUsize) -> Self {
+        Self {
+            encoder,
+            display_count,
+        }
+    }
+
+    /// Returns the URIs to show next, one per display.
+    ///
+    /// The display with index `i` shows the parts whose sequence number is
+    /// congruent to `i + 1` modulo the number of displays, as long as the
+    /// adapter is advanced from a fresh encoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::num::NonZeroUsize;
+    /// let encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// let mut displays = ur::ur::MultiDisplay::new(encoder, NonZeroUsize::new(2).unwrap());
+    /// let parts = displays.next_parts().unwrap();
+    /// assert!(parts[0].starts_with("ur:bytes/1-3/"));
+    /// assert!(parts[1].starts_with("ur:bytes/2-3/"));
+    /// let parts = displays.next_parts().unwrap();
+    /// assert!(parts[0].starts_with("ur:bytes/3-3/"));
+    /// assert!(parts[1].starts_with("ur:bytes/4-3/"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If serialization fails an error will be returned.
+    pub fn next_parts(&mut self) -> Result<Vec<String>, Error> {
+        (0..self.display_count.get())
+            .map(|_| self.encoder.next_part())
+            .collect()
+    }
+
+    /// Writes the URIs to show next into the given strings, one per display,
+    /// replacing their contents. The vector is resized to the number of
+    /// displays, and the allocations of the strings are reused between
+    /// frames, see [`Encoder::next_part_into`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::num::NonZeroUsize;
+    /// let encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// let mut displays = ur::ur::MultiDisplay::new(encoder, NonZeroUsize::new(2).unwrap());
+    /// let mut parts = Vec::new();
+    /// displays.next_parts_into(&mut parts).unwrap();
+    /// assert_eq!(parts.len(), 2);
+    /// assert!(parts[1].starts_with("ur:bytes/2-3/"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If serialization fails an error will be returned.
+    pub fn next_parts_into(&mut self, parts: &mut Vec<String>) -> Result<(), Error> {
+        parts.resize_with(self.display_count.get(), String::new);
+        for part in parts {
+            self.encoder.next_part_into(part)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of displays.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::num::NonZeroUsize;
+    /// let encoder = ur::Encoder::bytes(b"data", 3).unwrap();
+    /// let displays = ur::ur::MultiDisplay::new(encoder, NonZeroUsize::new(2).unwrap());
+    /// assert_eq!(displays.display_count(), 2);
+    /// ```
+    #[must_use]
+    pub const fn display_count(&self) -> usize {
+        self.display_count.get()
+    }
+
+    /// Returns the wrapped encoder, e.g. to query its checksum phrase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::num::NonZeroUsize;
+    /// let encoder = ur::Encoder::bytes(b"data", 3).unwrap();
+    /// let displays = ur::ur::MultiDisplay::new(encoder, NonZeroUsize::new(2).unwrap());
+    /// assert_eq!(displays.encoder().fragment_count(), 2);
+    /// ```
+    #[must_use]
+    pub const fn encoder(&self) -> &Encoder<'a, M> {
+        &self.encoder
+    }
+
+    /// Consumes the adapter, returning the wrapped encoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::num::NonZeroUsize;
+    /// let encoder = ur::Encoder::bytes(b"data", 3).unwrap();
+    /// let mut displays = ur::ur::MultiDisplay::new(encoder, NonZeroUsize::new(2).unwrap());
+    /// displays.next_parts().unwrap();
+    /// assert_eq!(displays.into_encoder().current_index(), 2);
+    /// ```
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn into_encoder(self) -> Encoder<'a, M> {
+        self.encoder
+    }
+}
+
+/// Renders a message checksum as a phrase of standard `bytewords`.
+fn checksum_phrase(checksum: u32) -> String {
+    let mut phrase = String::new();
+    for byte in checksum.to_be_bytes() {
+        if !phrase.is_empty() {
+            phrase.push(' ');
+        }
+        phrase.push_str(crate::bytewords::word_for(byte));
+    }
+    phrase
+}
+
+/// Returns the length of the longest URI emitted for a message of the given
+/// length and fragmentation, i.e. with a sequence number of `u32::MAX`.
+const fn max_part_length_of(
+    type_length: usize,
+    fragment_count: usize,
+    message_length: usize,
+    fragment_length: usize,
+) -> usize {
+    let header = "ur:/-/".len() + type_length + decimal_length(u32::MAX as usize);
+    let cbor = 1
+        + cbor_uint_length(u32::MAX as usize)
+        + cbor_uint_length(fragment_count)
+        + cbor_uint_length(message_length)
+        + cbor_uint_length(u32::MAX as usize)
+        + cbor_uint_length(fragment_length)
+        + fragment_length;
+    // two characters per byte, including the four checksum bytes
+    header + decimal_length(fragment_count) + 2 * (cbor + 4)
+}
+
+pub(crate) const fn decimal_length(mut value: usize) -> usize {
+    let mut length = 1;
+    while value >= 10 {
+        value /= 10;
+        length += 1;
+    }
+    length
+}
+
+const fn cbor_uint_length(value: usize) -> usize {
+    match value {
+        0..=23 => 1,
+        24..=0xff => 2,
+        0x100..=0xffff => 3,
+        0x1_0000..=0xffff_ffff => 5,
+        _ => 9,
+    }
+}
+
+/// A parsed UR, as returned by [`decode`].
+///
+/// Both kinds of URs carry their type and their `bytewords`-decoded payload.
+/// The payload of a multi-part UR is the CBOR-encoded fountain part, which
+/// can be fed to a [`crate::fountain::Decoder`] after decoding it with
+/// `minicbor::decode::<ur::fountain::Part>`.
+///
+/// The payload is a [`Vec<u8>`] for [`decode`] and a slice of the given buffer
+/// for [`decode_into`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedUr<'a, P = Vec<u8>> {
     /// This UR contains the full data payload.
-    SinglePart,
+    SinglePart {
+        /// The type of the UR, e.g. `bytes`.
+        ur_type: &'a str,
+        /// The data payload.
+        payload: P,
+    },
     /// This UR contains part of the data payload.
-    MultiPart,
+    MultiPart {
+        /// The type of the UR, e.g. `bytes`.
+        ur_type: &'a str,
+        /// The sequence number of the part, as parsed from the `seq-count` path component.
+        sequence: usize,
+        /// The total number of message segments, as parsed from the `seq-count` path component.
+        sequence_count: usize,
+        /// The raw body of the part, i.e. the CBOR-encoded fountain part.
+        payload: P,
+    },
+}
+
+impl<'a, P> ParsedUr<'a, P> {
+    /// Returns the type of the UR.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let parsed = ur::decode("ur:crypto-seed/iehsjyhspmwfwfia").unwrap();
+    /// assert_eq!(parsed.ur_type(), "crypto-seed");
+    /// ```
+    #[must_use]
+    pub const fn ur_type(&self) -> &'a str {
+        match self {
+            Self::SinglePart { ur_type, .. } | Self::MultiPart { ur_type, .. } => ur_type,
+        }
+    }
+
+    /// Returns whether the UR contains part of the data payload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert!(ur::decode("ur:bytes/1-2/iehsjyhspmwfwfia").unwrap().is_multi_part());
+    /// assert!(!ur::decode("ur:bytes/iehsjyhspmwfwfia").unwrap().is_multi_part());
+    /// ```
+    #[must_use]
+    pub const fn is_multi_part(&self) -> bool {
+        matches!(self, Self::MultiPart { .. })
+    }
+
+    /// Returns the payload of the UR.
+    ///
+    /// # Examples
+    ///
+    /// See the [`decode`] documentation for an example.
+    #[must_use]
+    pub const fn payload(&self) -> &P {
+        match self {
+            Self::SinglePart { payload, .. } | Self::MultiPart { payload, .. } => payload,
+        }
+    }
+
+    /// Consumes the parsed UR, returning its payload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let parsed = ur::decode("ur:bytes/iehsjyhspmwfwfia").unwrap();
+    /// assert_eq!(parsed.into_payload(), b"data");
+    /// ```
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn into_payload(self) -> P {
+        match self {
+            Self::SinglePart { payload, .. } | Self::MultiPart { payload, .. } => payload,
+        }
+    }
+
+    fn with_payload<Q>(self, payload: Q) -> ParsedUr<'a, Q> {
+        match self {
+            Self::SinglePart { ur_type, .. } => ParsedUr::SinglePart { ur_type, payload },
+            Self::MultiPart {
+                ur_type,
+                sequence,
+                sequence_count,
+                ..
+            } => ParsedUr::MultiPart {
+                ur_type,
+                sequence,
+                sequence_count,
+                payload,
+            },
+        }
+    }
 }
 
-/// Decodes a single URI (either single- or multi-part)
-/// into a tuple consisting of the [`Kind`] and the data
-/// payload.
+/// Decodes a single URI (either single- or multi-part) into a [`ParsedUr`],
+/// which carries the type and the data payload.
+///
+/// For multi-part URs, the [`ParsedUr`] also carries the sequence number and
+/// total number of segments indicated by the URI.
 ///
 /// # Examples
 ///
 /// ```
+/// use ur::ur::{decode, ParsedUr};
 /// assert_eq!(
-///     ur::ur::decode("ur:bytes/iehsjyhspmwfwfia").unwrap(),
-///     (ur::ur::Kind::SinglePart, b"data".to_vec())
+///     decode("ur:bytes/iehsjyhspmwfwfia").unwrap(),
+///     ParsedUr::SinglePart {
+///         ur_type: "bytes",
+///         payload: b"data".to_vec()
+///     }
 /// );
+/// let parsed = decode("ur:bytes/1-2/iehsjyhspmwfwfia").unwrap();
 /// assert_eq!(
-///     ur::ur::decode("ur:bytes/1-2/iehsjyhspmwfwfia").unwrap(),
-///     (ur::ur::Kind::MultiPart, b"data".to_vec())
+///     parsed,
+///     ParsedUr::MultiPart {
+///         ur_type: "bytes",
+///         sequence: 1,
+///         sequence_count: 2,
+///         payload: b"data".to_vec()
+///     }
 /// );
+/// assert_eq!(parsed.payload(), b"data");
 /// ```
 ///
 /// # Errors
@@ -235,11 +1054,392 @@ pub enum Kind {
 /// This function errors for invalid inputs, for example
 /// an invalid scheme different from "ur" or an invalid number
 /// of "/" separators.
-pub fn decode(value: &str) -> Result<(Kind, Vec<u8>), Error> {
+pub fn decode(value: &str) -> Result<ParsedUr<'_>, Error> {
+    let parsed = parse(value)?;
+    let payload = crate::bytewords::decode(parsed.payload(), crate::bytewords::Style::Minimal)?;
+    Ok(parsed.with_payload(payload))
+}
+
+/// The result of [`decode_lenient`], which also accepts bare payloads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LenientUr<'a> {
+    /// A well-formed UR.
+    Typed(ParsedUr<'a>),
+    /// A minimal `bytewords` payload stripped of the `ur:type/` prefix, whose
+    /// type is unknown.
+    Untyped(Vec<u8>),
+}
+
+/// Decodes a single URI like [`decode`], but falls back to decoding strings
+/// without the `ur:` scheme as bare minimal `bytewords` payloads.
+///
+/// Some tools strip the `ur:type/` prefix and only share the payload, whose
+/// type must then be known from the context.
+///
+/// # Examples
+///
+/// ```
+/// use ur::ur::{decode_lenient, LenientUr};
+/// assert_eq!(
+///     decode_lenient("iehsjyhspmwfwfia").unwrap(),
+///     LenientUr::Untyped(b"data".to_vec())
+/// );
+/// assert!(matches!(
+///     decode_lenient("ur:bytes/iehsjyhspmwfwfia").unwrap(),
+///     LenientUr::Typed(parsed) if parsed.ur_type() == "bytes"
+/// ));
+/// ```
+///
+/// # Errors
+///
+/// This function errors for the same URIs as [`decode`], and for bare
+/// payloads which aren't valid minimal `bytewords`. Strings with a scheme
+/// other than `ur` are rejected with [`Error::InvalidScheme`].
+pub fn decode_lenient(value: &str) -> Result<LenientUr<'_>, Error> {
+    if value.starts_with("ur:") || value.contains([':', '/']) {
+        return decode(value).map(LenientUr::Typed);
+    }
+    Ok(LenientUr::Untyped(crate::bytewords::decode(
+        value,
+        crate::bytewords::Style::Minimal,
+    )?))
+}
+
+/// Decodes a single URI (either single- or multi-part) like [`decode`], but
+/// rejects payloads longer than the given number of bytes before allocating.
+///
+/// Services decoding URs from untrusted sources should use this to bound the
+/// memory a single request can claim.
+///
+/// # Examples
+///
+/// ```
+/// use ur::ur::{decode_with_limit, Error};
+/// let ur = "ur:bytes/iehsjyhspmwfwfia";
+/// assert_eq!(decode_with_limit(ur, 4).unwrap().payload(), b"data");
+/// assert!(matches!(
+///     decode_with_limit(ur, 3),
+///     Err(Error::PayloadTooLarge)
+/// ));
+/// ```
+///
+/// # Errors
+///
+/// This function errors for the same inputs as [`decode`], and if the payload
+/// is longer than `max_payload_length`.
+pub fn decode_with_limit(value: &str, max_payload_length: usize) -> Result<ParsedUr<'_>, Error> {
+    let parsed = parse(value)?;
+    let encoded = parsed.payload();
+    // every payload byte and the four checksum bytes take two characters
+    if encoded.len() / 2 > max_payload_length.saturating_add(4) {
+        return Err(Error::PayloadTooLarge);
+    }
+    let payload = crate::bytewords::decode(encoded, crate::bytewords::Style::Minimal)?;
+    Ok(parsed.with_payload(payload))
+}
+
+/// Decodes a single URI (either single- or multi-part) like [`decode`], but
+/// writes the data payload into the given buffer instead of allocating it.
+///
+/// Returns the [`ParsedUr`] with the prefix of the buffer holding the payload.
+/// Use [`crate::bytewords::decoded_length`] to size the buffer for a given URI.
+///
+/// # Examples
+///
+/// ```
+/// use ur::ur::{decode_into, ParsedUr};
+/// let mut buffer = [0; 8];
+/// assert_eq!(
+///     decode_into("ur:bytes/iehsjyhspmwfwfia", &mut buffer).unwrap(),
+///     ParsedUr::SinglePart {
+///         ur_type: "bytes",
+///         payload: &b"data"[..]
+///     }
+/// );
+/// ```
+///
+/// # Errors
+///
+/// This function errors for the same inputs as [`decode`], and if the payload
+/// doesn't fit into the buffer.
+pub fn decode_into<'a, 'b>(
+    value: &'a str,
+    buffer: &'b mut [u8],
+) -> Result<ParsedUr<'a, &'b [u8]>, Error> {
+    let parsed = parse(value)?;
+    let payload =
+        crate::bytewords::decode_into(parsed.payload(), crate::bytewords::Style::Minimal, buffer)?;
+    Ok(parsed.with_payload(payload))
+}
+
+/// A detailed explanation of why a string fails to decode as a UR, as
+/// returned by [`diagnose`].
+///
+/// Positions are byte offsets into the diagnosed string.
+///
+/// # Examples
+///
+/// See the [`diagnose`] documentation for an example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnosis {
+    /// The string is a valid UR.
+    Valid,
+    /// The string is a valid UR in uppercase, as usually encoded into QR codes
+    /// to benefit from their alphanumeric mode, and has to be converted to
+    /// lowercase before decoding.
+    Uppercase,
+    /// The string doesn't start with the `ur:` scheme.
+    InvalidScheme,
+    /// The string has no type, or no `/` separating the type from the payload.
+    TypeUnspecified,
+    /// The type contains a character other than lowercase letters, digits and
+    /// hyphens.
+    InvalidTypeCharacter {
+        /// The position of the character.
+        position: usize,
+        /// The invalid character.
+        character: char,
+    },
+    /// The `seq-count` path component of a multi-part UR isn't a pair of
+    /// positive 32-bit integers.
+    InvalidIndices {
+        /// The position of the path component.
+        position: usize,
+        /// The path component.
+        indices: String,
+    },
+    /// The payload contains a character which isn't ASCII.
+    NonAscii {
+        /// The position of the character.
+        position: usize,
+    },
+    /// The payload has an odd number of characters, or is too short to hold a
+    /// checksum.
+    InvalidLength {
+        /// The number of characters of the payload.
+        length: usize,
+    },
+    /// The payload contains a pair of letters which isn't a minimal
+    /// `bytewords` word.
+    InvalidWord {
+        /// The position of the word.
+        position: usize,
+        /// The unrecognized word.
+        word: String,
+    },
+    /// The CRC32 checksum at the end of the payload doesn't match its data,
+    /// e.g. because a character was misread or the string was truncated.
+    ChecksumMismatch {
+        /// The position of the checksum.
+        position: usize,
+        /// The checksum contained in the payload.
+        expected: u32,
+        /// The checksum of the payload data.
+        computed: u32,
+    },
+    /// The payload of a multi-part UR isn't a CBOR-encoded fountain part.
+    InvalidCbor {
+        /// The description of the CBOR error.
+        message: String,
+    },
+    /// The `seq-count` path component of a multi-part UR differs from the
+    /// sequence number and count of the fountain part in the payload.
+    IndicesMismatch {
+        /// The sequence number and count in the path.
+        path: (usize, usize),
+        /// The sequence number and count of the fountain part.
+        part: (usize, usize),
+    },
+}
+
+impl Diagnosis {
+    /// Returns whether the diagnosed string is a valid UR.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert!(ur::diagnose("ur:bytes/iehsjyhspmwfwfia").is_valid());
+    /// ```
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        matches!(self, Self::Valid)
+    }
+}
+
+impl core::fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Valid => write!(f, "valid UR"),
+            Self::Uppercase => write!(f, "uppercase UR, convert it to lowercase before decoding"),
+            Self::InvalidScheme => write!(f, "expected the string to start with \"ur:\""),
+            Self::TypeUnspecified => write!(f, "expected a type followed by \"/\" after \"ur:\""),
+            Self::InvalidTypeCharacter {
+                position,
+                character,
+            } => write!(
+                f,
+                "invalid character {character:?} in type at position {position}, expected \
+                 lowercase letters, digits and hyphens"
+            ),
+            Self::InvalidIndices { position, indices } => write!(
+                f,
+                "invalid sequence {indices:?} at position {position}, expected two positive \
+                 32-bit integers separated by \"-\""
+            ),
+            Self::NonAscii { position } => {
+                write!(f, "non-ASCII character in payload at position {position}")
+            }
+            Self::InvalidLength { length } => write!(
+                f,
+                "invalid payload length {length}, expected an even number of at least 8 \
+                 characters"
+            ),
+            Self::InvalidWord { position, word } => {
+                write!(f, "invalid bytewords word {word:?} at position {position}")
+            }
+            Self::ChecksumMismatch {
+                position,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "checksum {expected:08x} at position {position} doesn't match the computed \
+                 checksum {computed:08x}, the payload may be misread or truncated"
+            ),
+            Self::InvalidCbor { message } => write!(f, "invalid CBOR payload: {message}"),
+            Self::IndicesMismatch { path, part } => write!(
+                f,
+                "sequence {}-{} in path doesn't match sequence {}-{} of the fountain part",
+                path.0, path.1, part.0, part.1
+            ),
+        }
+    }
+}
+
+/// Explains in detail why a string fails to decode as a UR, e.g. to debug
+/// interoperability problems between wallets.
+///
+/// The checks are stricter than the ones of [`decode`]: types have to be
+/// lowercase, and the payloads of multi-part URs have to be fountain parts
+/// matching the path.
+///
+/// # Examples
+///
+/// ```
+/// use ur::ur::Diagnosis;
+/// assert_eq!(ur::diagnose("ur:bytes/iehsjyhspmwfwfia"), Diagnosis::Valid);
+/// assert_eq!(ur::diagnose("UR:BYTES/IEHSJYHSPMWFWFIA"), Diagnosis::Uppercase);
+/// assert_eq!(
+///     ur::diagnose("ur:bytes/iehsjyhspmwfwfid"),
+///     Diagnosis::ChecksumMismatch {
+///         position: 17,
+///         expected: 0xadf3f362,
+///         computed: 0xadf3f363,
+///     }
+/// );
+/// ```
+#[must_use]
+pub fn diagnose(value: &str) -> Diagnosis {
+    let Some(strip_scheme) = value.strip_prefix("ur:") else {
+        if matches!(value.get(..3), Some(scheme) if scheme.eq_ignore_ascii_case("ur:"))
+            && diagnose(&value.to_ascii_lowercase()).is_valid()
+        {
+            return Diagnosis::Uppercase;
+        }
+        return Diagnosis::InvalidScheme;
+    };
+    let Some((ur_type, rest)) = strip_scheme.split_once('/') else {
+        return Diagnosis::TypeUnspecified;
+    };
+    if ur_type.is_empty() {
+        return Diagnosis::TypeUnspecified;
+    }
+    if let Some((index, character)) = ur_type
+        .char_indices()
+        .find(|(_, c)| !(c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '-'))
+    {
+        return Diagnosis::InvalidTypeCharacter {
+            position: "ur:".len() + index,
+            character,
+        };
+    }
+    let (indices, payload) = match rest.rsplit_once('/') {
+        None => (None, rest),
+        Some((indices, payload)) => {
+            let position = value.len() - rest.len();
+            let parsed = indices.split_once('-').and_then(|(sequence, count)| {
+                let sequence = sequence.parse::<u32>().ok().filter(|&s| s > 0)?;
+                let count = count.parse::<u32>().ok().filter(|&c| c > 0)?;
+                Some((sequence as usize, count as usize))
+            });
+            let Some(parsed) = parsed else {
+                return Diagnosis::InvalidIndices {
+                    position,
+                    indices: indices.into(),
+                };
+            };
+            (Some(parsed), payload)
+        }
+    };
+
+    let offset = value.len() - payload.len();
+    let data = match crate::bytewords::decode(payload, crate::bytewords::Style::Minimal) {
+        Ok(data) => data,
+        Err(crate::bytewords::Error::NonAscii) => {
+            let index = payload.find(|c: char| !c.is_ascii()).unwrap_or_default();
+            return Diagnosis::NonAscii {
+                position: offset + index,
+            };
+        }
+        Err(crate::bytewords::Error::InvalidWord { position, word }) => {
+            return Diagnosis::InvalidWord {
+                position: offset + 2 * position,
+                word,
+            };
+        }
+        Err(_) if payload.len() % 2 == 1 || payload.len() < 8 => {
+            return Diagnosis::InvalidLength {
+                length: payload.len(),
+            };
+        }
+        Err(_) => {
+            // all words are valid, so only the checksum can mismatch
+            let bytes: Vec<u8> = (0..payload.len())
+                .step_by(2)
+                .filter_map(|i| crate::bytewords::minimal_byte(&payload[i..i + 2]))
+                .collect();
+            let (data, checksum) = bytes.split_at(bytes.len() - 4);
+            return Diagnosis::ChecksumMismatch {
+                position: value.len() - 8,
+                expected: u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]),
+                computed: crate::CRC32.checksum(data),
+            };
+        }
+    };
+
+    let Some((sequence, sequence_count)) = indices else {
+        return Diagnosis::Valid;
+    };
+    match crate::fountain::Part::from_cbor(&data) {
+        Err(e) => Diagnosis::InvalidCbor {
+            message: alloc::format!("{e}"),
+        },
+        Ok(part) if (part.sequence(), part.sequence_count()) != (sequence, sequence_count) => {
+            Diagnosis::IndicesMismatch {
+                path: (sequence, sequence_count),
+                part: (part.sequence(), part.sequence_count()),
+            }
+        }
+        Ok(_) => Diagnosis::Valid,
+    }
+}
+
+/// Splits a URI into a [`ParsedUr`] with the `bytewords`-encoded payload.
+fn parse(value: &str) -> Result<ParsedUr<'_, &str>, Error> {
     let strip_scheme = value.strip_prefix("ur:").ok_or(Error::InvalidScheme)?;
-    let (r#type, strip_type) = strip_scheme.split_once('/').ok_or(Error::TypeUnspecified)?;
+    let (ur_type, strip_type) = strip_scheme.split_once('/').ok_or(Error::TypeUnspecified)?;
 
-    if !r#type
+    if !ur_type
         .trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '-')
         .is_empty()
     {
@@ -247,21 +1447,70 @@ pub fn decode(value: &str) -> Result<(Kind, Vec<u8>), Error> {
     }
 
     match strip_type.rsplit_once('/') {
-        None => Ok((
-            Kind::SinglePart,
-            crate::bytewords::decode(strip_type, crate::bytewords::Style::Minimal)?,
-        )),
+        None => Ok(ParsedUr::SinglePart {
+            ur_type,
+            payload: strip_type,
+        }),
         Some((indices, payload)) => {
             let (idx, idx_total) = indices.split_once('-').ok_or(Error::InvalidIndices)?;
-            if idx.parse::<u16>().is_err() || idx_total.parse::<u16>().is_err() {
+            let (Ok(sequence), Ok(sequence_count)) = (idx.parse::<u32>(), idx_total.parse::<u32>())
+            else {
                 return Err(Error::InvalidIndices);
-            }
+            };
+
+            Ok(ParsedUr::MultiPart {
+                ur_type,
+                sequence: sequence as usize,
+                sequence_count: sequence_count as usize,
+                payload,
+            })
+        }
+    }
+}
 
-            Ok((
-                Kind::MultiPart,
-                crate::bytewords::decode(payload, crate::bytewords::Style::Minimal)?,
-            ))
+/// Decodes an acknowledgment returned by [`Decoder::ack`], which the encoding
+/// side received over a back-channel.
+///
+/// # Examples
+///
+/// See the [`Decoder::ack`] documentation for an example.
+///
+/// # Errors
+///
+/// If the URI isn't a well-formed single-part UR of the
+/// [`Ack::UR_TYPE`](crate::fountain::Ack::UR_TYPE) type, or its payload isn't
+/// a valid acknowledgment, an error will be returned.
+pub fn decode_ack(value: &str) -> Result<crate::fountain::Ack, Error> {
+    match decode(value)? {
+        ParsedUr::SinglePart { ur_type, payload } if ur_type == crate::fountain::Ack::UR_TYPE => {
+            Ok(minicbor::decode(&payload)?)
         }
+        _ => Err(Error::UnexpectedType),
+    }
+}
+
+/// The outcome of [`Decoder::receive_many`].
+///
+/// # Examples
+///
+/// See the [`Decoder::receive_many`] documentation for an example.
+#[derive(Debug)]
+pub struct BatchReceipt {
+    /// The result of receiving each of the URIs, in order.
+    pub results: Vec<Result<(), Error>>,
+    /// The progress of the transfer after receiving all URIs.
+    pub progress: crate::fountain::Progress,
+}
+
+impl BatchReceipt {
+    /// Returns the number of URIs which failed to be received.
+    ///
+    /// # Examples
+    ///
+    /// See the [`Decoder::receive_many`] documentation for an example.
+    #[must_use]
+    pub fn error_count(&self) -> usize {
+        self.results.iter().filter(|r| r.is_err()).count()
     }
 }
 
@@ -271,11 +1520,57 @@ pub fn decode(value: &str) -> Result<(Kind, Vec<u8>), Error> {
 ///
 /// See the [`crate::ur`] module documentation for an example.
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Decoder {
     fountain: crate::fountain::Decoder,
 }
 
-impl Decoder {
+impl Decoder {
+    /// Sets the [`Limits`] on the resources the underlying fountain decoder
+    /// commits to a transfer, protecting against malicious parts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ur::fountain::{Error, Limits};
+    /// let mut decoder = ur::Decoder::default().with_limits(Limits {
+    ///     max_fragment_count: 1,
+    ///     ..Limits::default()
+    /// });
+    /// assert!(matches!(
+    ///     decoder.receive("ur:bytes/1-2/lpadaoaacypmwfwfiafwiehsdpcynbem"),
+    ///     Err(ur::ur::Error::Fountain(Error::LimitExceeded))
+    /// ));
+    /// ```
+    ///
+    /// [`Limits`]: crate::fountain::Limits
+    #[must_use]
+    pub fn with_limits(self, limits: crate::fountain::Limits) -> Self {
+        Self {
+            fountain: self.fountain.with_limits(limits),
+        }
+    }
+
+    /// Only accepts parts of the transfer with the given message checksum, and
+    /// silently ignores parts of any other transfer. See
+    /// [`crate::fountain::Decoder::with_expected_checksum`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(b"data", 3).unwrap();
+    /// let mut decoder = ur::Decoder::default().with_expected_checksum(0);
+    /// decoder.receive(&encoder.next_part().unwrap()).unwrap();
+    /// decoder.receive(&encoder.next_part().unwrap()).unwrap();
+    /// assert!(!decoder.complete());
+    /// ```
+    #[must_use]
+    pub fn with_expected_checksum(self, checksum: u32) -> Self {
+        Self {
+            fountain: self.fountain.with_expected_checksum(checksum),
+        }
+    }
+
     /// Receives a URI representing a CBOR and `bytewords`-encoded fountain part
     /// into the decoder.
     ///
@@ -293,26 +1588,205 @@ impl Decoder {
     ///
     /// In all these cases, an error will be returned.
     pub fn receive(&mut self, value: &str) -> Result<(), Error> {
-        let (kind, decoded) = decode(value)?;
-        if kind != Kind::MultiPart {
+        let parsed = decode(value)?;
+        if !parsed.is_multi_part() {
             return Err(Error::NotMultiPart);
         }
 
-        self.fountain
-            .receive(crate::fountain::Part::from_cbor(decoded.as_slice())?)?;
+        self.fountain.receive(crate::fountain::Part::from_cbor(
+            parsed.payload().as_slice(),
+        )?)?;
+        Ok(())
+    }
+
+    /// Receives a batch of URIs, e.g. a recorded list of scanned frames,
+    /// returning the result of each URI along with the resulting progress.
+    ///
+    /// Each URI is passed to [`receive`], such that an invalid URI doesn't
+    /// prevent the remaining ones from being received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// let mut frames: Vec<String> = (0..3).map(|_| encoder.next_part().unwrap()).collect();
+    /// frames.insert(1, String::from("ur:bytes/garbage"));
+    /// let mut decoder = ur::Decoder::default();
+    /// let receipt = decoder.receive_many(&frames);
+    /// assert_eq!(receipt.results.len(), 4);
+    /// assert!(receipt.results[1].is_err());
+    /// assert_eq!(receipt.error_count(), 1);
+    /// assert_eq!(receipt.progress.estimated_percent_complete, 1.0);
+    /// assert!(decoder.complete());
+    /// ```
+    ///
+    /// [`receive`]: Decoder::receive
+    pub fn receive_many<I>(&mut self, values: I) -> BatchReceipt
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let results = values
+            .into_iter()
+            .map(|value| self.receive(value.as_ref()))
+            .collect();
+        BatchReceipt {
+            results,
+            progress: self.progress(),
+        }
+    }
+
+    /// Merges the parts received by another decoder of the same transfer into
+    /// this decoder, e.g. when each code of a [`MultiDisplay`] is scanned by
+    /// its own decoder. See [`crate::fountain::Decoder::merge`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::num::NonZeroUsize;
+    /// let encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// let mut displays = ur::ur::MultiDisplay::new(encoder, NonZeroUsize::new(2).unwrap());
+    /// let mut decoders = [ur::Decoder::default(), ur::Decoder::default()];
+    /// for _ in 0..2 {
+    ///     for (decoder, part) in decoders.iter_mut().zip(displays.next_parts().unwrap()) {
+    ///         decoder.receive(&part).unwrap();
+    ///     }
+    /// }
+    /// let [mut first, second] = decoders;
+    /// first.merge(&second).unwrap();
+    /// assert_eq!(first.message().unwrap().as_deref(), Some(&b"Ten chars!"[..]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the decoders received parts of different transfers, or if merging
+    /// would exceed the limits of this decoder, an error will be returned.
+    pub fn merge(&mut self, other: &Self) -> Result<(), Error> {
+        self.fountain.merge(&other.fountain)?;
         Ok(())
     }
 
+    /// Returns [`Stats`] on the parts received by the underlying fountain
+    /// decoder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut decoder = ur::Decoder::default();
+    /// let part = "ur:bytes/1-2/lpadaoaacypmwfwfiafwiehsdpcynbem";
+    /// decoder.receive(part).unwrap();
+    /// decoder.receive(part).unwrap();
+    /// assert_eq!(decoder.stats().duplicate_parts, 1);
+    /// ```
+    ///
+    /// [`Stats`]: crate::fountain::Stats
+    #[must_use]
+    pub fn stats(&self) -> crate::fountain::Stats {
+        self.fountain.stats()
+    }
+
+    /// Returns the [`Progress`] of the transfer, e.g. to bind it to a label
+    /// of the user interface.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut decoder = ur::Decoder::default();
+    /// decoder
+    ///     .receive("ur:bytes/1-2/lpadaoaacypmwfwfiafwiehsdpcynbem")
+    ///     .unwrap();
+    /// let progress = decoder.progress();
+    /// assert_eq!(progress.label, "1/2 fragments");
+    /// assert!(progress.estimated_percent_complete > 0.0);
+    /// ```
+    ///
+    /// [`Progress`]: crate::fountain::Progress
+    #[must_use]
+    pub fn progress(&self) -> crate::fountain::Progress {
+        self.fountain.progress()
+    }
+
+    /// Returns a single-part UR acknowledging the message segments recovered
+    /// so far, or `None` if no part has been received yet.
+    ///
+    /// The UR is short enough to be shown as a single QR code back to the
+    /// encoding side, which can parse it with [`decode_ack`]. See
+    /// [`crate::fountain::Ack`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(b"Ten chars!", 4).unwrap();
+    /// let mut decoder = ur::Decoder::default();
+    /// assert_eq!(decoder.ack().unwrap(), None);
+    /// encoder.next_part().unwrap();
+    /// decoder.receive(&encoder.next_part().unwrap()).unwrap();
+    /// let ack = decoder.ack().unwrap().unwrap();
+    /// assert!(ack.starts_with("ur:fountain-ack/"));
+    /// assert_eq!(ur::ur::decode_ack(&ack).unwrap().missing().to_vec(), vec![0, 2]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the acknowledgment can't be CBOR-encoded, an error will be returned.
+    pub fn ack(&self) -> Result<Option<String>, Error> {
+        let Some(ack) = self.fountain.ack() else {
+            return Ok(None);
+        };
+        let cbor = minicbor::to_vec(&ack)?;
+        Ok(Some(encode(
+            &cbor,
+            &Type::Custom(crate::fountain::Ack::UR_TYPE),
+        )))
+    }
+
+    /// Returns the checksum of the message being decoded as a phrase of four
+    /// standard `bytewords`, or `None` if no part has been received yet. See
+    /// [`Encoder::checksum_phrase`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut encoder = ur::Encoder::bytes(b"data", 3).unwrap();
+    /// let mut decoder = ur::Decoder::default();
+    /// assert_eq!(decoder.checksum_phrase(), None);
+    /// decoder.receive(&encoder.next_part().unwrap()).unwrap();
+    /// assert_eq!(decoder.checksum_phrase(), Some(encoder.checksum_phrase()));
+    /// ```
+    #[must_use]
+    pub fn checksum_phrase(&self) -> Option<String> {
+        self.fountain.checksum().map(checksum_phrase)
+    }
+
     /// Returns whether the decoder is complete and hence the message available.
     ///
     /// # Examples
     ///
     /// See the [`crate::ur`] module documentation for an example.
     #[must_use]
-    pub fn complete(&self) -> bool {
+    pub const fn complete(&self) -> bool {
         self.fountain.complete()
     }
 
+    /// Clears all state of the current transfer, such that the decoder can be
+    /// reused for a new one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut decoder = ur::Decoder::default();
+    /// decoder
+    ///     .receive("ur:bytes/1-2/lpadaoaacypmwfwfiafwiehsdpcynbem")
+    ///     .unwrap();
+    /// decoder.reset();
+    /// decoder
+    ///     .receive("ur:my-scheme/1-2/lpadaobkcywkwmhfwnfeghihjtcxiansvomopr")
+    ///     .unwrap();
+    /// ```
+    pub fn reset(&mut self) {
+        self.fountain.reset();
+    }
+
     /// If [`complete`], returns the decoded message, `None` otherwise.
     ///
     /// # Errors
@@ -346,7 +1820,214 @@ mod tests {
         let expected = "ur:bytes/hdeymejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtgwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsdwkbrkch";
         assert_eq!(encoded, expected);
         let decoded = decode(&encoded).unwrap();
-        assert_eq!((Kind::SinglePart, ur), decoded);
+        assert_eq!(
+            decoded,
+            ParsedUr::SinglePart {
+                ur_type: "bytes",
+                payload: ur
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_into() {
+        let ur = make_message_ur(50, "Wolf");
+        let encoded = encode(&ur, &Type::Bytes);
+        let mut buffer = [0; 64];
+        assert_eq!(
+            decode_into(&encoded, &mut buffer).unwrap(),
+            ParsedUr::SinglePart {
+                ur_type: "bytes",
+                payload: ur.as_slice()
+            }
+        );
+        assert!(matches!(
+            decode_into(&encoded, &mut buffer[..ur.len() - 1]),
+            Err(Error::Bytewords(crate::bytewords::Error::BufferTooSmall))
+        ));
+        assert!(matches!(
+            decode_into("uhr:bytes/aeaeaeae", &mut buffer),
+            Err(Error::InvalidScheme)
+        ));
+    }
+
+    #[test]
+    fn test_decode_with_limit() {
+        let ur = make_message_ur(50, "Wolf");
+        let encoded = encode(&ur, &Type::Bytes);
+        assert_eq!(
+            decode_with_limit(&encoded, ur.len())
+                .unwrap()
+                .into_payload(),
+            ur
+        );
+        assert!(matches!(
+            decode_with_limit(&encoded, ur.len() - 1),
+            Err(Error::PayloadTooLarge)
+        ));
+        assert!(matches!(
+            decode_with_limit("ur:bytes/1-2/iehsjyhspmwfwfia", 0),
+            Err(Error::PayloadTooLarge)
+        ));
+        assert!(matches!(
+            decode_with_limit("ur:bytes/iehsjyhspmwfwfi", 4),
+            Err(Error::Bytewords(crate::bytewords::Error::InvalidLength))
+        ));
+        assert!(matches!(
+            decode_with_limit("ur:bytes", usize::MAX),
+            Err(Error::TypeUnspecified)
+        ));
+        assert_eq!(
+            decode_with_limit("ur:bytes/aeaeaeae", 0)
+                .unwrap()
+                .into_payload(),
+            Vec::<u8>::new()
+        );
+    }
+
+    #[test]
+    fn test_decode_lenient() {
+        let ur = make_message_ur(50, "Wolf");
+        let encoded = encode(&ur, &Type::Bytes);
+        assert_eq!(
+            decode_lenient(&encoded).unwrap(),
+            LenientUr::Typed(decode(&encoded).unwrap())
+        );
+        let bare = encoded.strip_prefix("ur:bytes/").unwrap();
+        assert_eq!(decode_lenient(bare).unwrap(), LenientUr::Untyped(ur));
+        assert_eq!(
+            decode_lenient("aeaeaeae").unwrap(),
+            LenientUr::Untyped(vec![])
+        );
+        assert!(matches!(
+            decode_lenient("uhr:bytes/aeadaolazmjendeoti"),
+            Err(Error::InvalidScheme)
+        ));
+        assert!(matches!(
+            decode_lenient("bytes/aeadaolazmjendeoti"),
+            Err(Error::InvalidScheme)
+        ));
+        assert!(matches!(
+            decode_lenient("aeadaolazojendeowf"),
+            Err(Error::Bytewords(crate::bytewords::Error::InvalidChecksum))
+        ));
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let part = "ur:bytes/1-3/lpadaxbkcywkwmhfwnfyghihjtcxfwfrbahy";
+        assert_eq!(diagnose(part), Diagnosis::Valid);
+        assert_eq!(diagnose(&part.to_uppercase()), Diagnosis::Uppercase);
+        assert_eq!(diagnose("UR:BYTES/INVALID"), Diagnosis::InvalidScheme);
+        assert_eq!(diagnose("bytes/iehsjyhspmwfwfia"), Diagnosis::InvalidScheme);
+        assert_eq!(diagnose("ur:bytes"), Diagnosis::TypeUnspecified);
+        assert_eq!(diagnose("ur:/iehsjyhspmwfwfia"), Diagnosis::TypeUnspecified);
+        assert_eq!(
+            diagnose("ur:byTes/iehsjyhspmwfwfia"),
+            Diagnosis::InvalidTypeCharacter {
+                position: 5,
+                character: 'T'
+            }
+        );
+        assert_eq!(
+            diagnose("ur:bytes/0-3/lpadaxbkcywkwmhfwnfyghihjtcxfwfrbahy"),
+            Diagnosis::InvalidIndices {
+                position: 9,
+                indices: "0-3".into()
+            }
+        );
+        assert_eq!(
+            diagnose("ur:bytes/iehsjyhsémwfwfia"),
+            Diagnosis::NonAscii { position: 17 }
+        );
+        assert_eq!(
+            diagnose("ur:bytes/iehsjyh"),
+            Diagnosis::InvalidLength { length: 7 }
+        );
+        assert_eq!(
+            diagnose("ur:bytes/iehs"),
+            Diagnosis::InvalidLength { length: 4 }
+        );
+        assert_eq!(
+            diagnose("ur:bytes/iehsjyhspmwfwfzz"),
+            Diagnosis::InvalidWord {
+                position: 23,
+                word: "zz".into()
+            }
+        );
+        assert_eq!(
+            diagnose("ur:bytes/1-3/iehsjyhspmwfwfia"),
+            Diagnosis::InvalidCbor {
+                message: "unexpected type string at position 0: expected array".into()
+            }
+        );
+        let mismatch = diagnose(&part.replace("1-3", "2-3"));
+        assert_eq!(
+            mismatch,
+            Diagnosis::IndicesMismatch {
+                path: (2, 3),
+                part: (1, 3)
+            }
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            "sequence 2-3 in path doesn't match sequence 1-3 of the fountain part"
+        );
+    }
+
+    #[test]
+    fn test_readable() {
+        let ur = make_message_ur(50, "Wolf");
+        let encoded = encode(&ur, &Type::Custom("crypto-seed"));
+        let readable = to_readable(&encoded).unwrap();
+        let lines: Vec<_> = readable.lines().collect();
+        assert_eq!(lines[0], "ur:crypto-seed");
+        assert_eq!(lines[1], "hard easy maze jolt");
+        // the payload and checksum words
+        assert_eq!(lines.len(), 1 + crate::fountain::div_ceil(ur.len() + 4, 4));
+        assert_eq!(from_readable(&readable).unwrap(), encoded);
+        assert_eq!(from_readable(&readable.to_uppercase()).unwrap(), encoded);
+
+        assert!(matches!(
+            from_readable(&readable.replacen("jolt", "joly", 1)),
+            Err(Error::Bytewords(_))
+        ));
+        assert!(matches!(
+            from_readable(&readable.replacen("jolt", "maze", 1)),
+            Err(Error::Bytewords(_))
+        ));
+        let mut encoder = Encoder::bytes(&ur, 20).unwrap();
+        let part = encoder.next_part().unwrap();
+        let readable = to_readable(&part).unwrap();
+        assert!(readable.starts_with("ur:bytes/1-3\n"));
+        assert_eq!(from_readable(&readable).unwrap(), part);
+        assert!(matches!(
+            from_readable(&readable.replacen("ur:", "uhr:", 1)),
+            Err(Error::InvalidScheme)
+        ));
+        assert!(matches!(
+            from_readable("ur:bytes/1-x\nable tied also webs lung"),
+            Err(Error::InvalidIndices)
+        ));
+        assert!(matches!(
+            from_readable("ur:bytes"),
+            Err(Error::Bytewords(_))
+        ));
+        assert!(matches!(
+            to_readable("ur:bytes"),
+            Err(Error::TypeUnspecified)
+        ));
+    }
+
+    #[test]
+    fn test_empty_single_part_ur() {
+        let encoded = encode(&[], &Type::Bytes);
+        assert_eq!(encoded, "ur:bytes/aeaeaeae");
+        assert_eq!(decode(&encoded).unwrap().into_payload(), Vec::<u8>::new());
+        assert!(matches!(
+            Encoder::bytes(&[], 10),
+            Err(Error::Fountain(crate::fountain::Error::EmptyMessage))
+        ));
     }
 
     #[test]
@@ -376,10 +2057,86 @@ mod tests {
             "ur:bytes/20-9/lpbbascfadaxcywenbpljkhdcayapmrleeleaxpasfrtrdkncffwjyjzgyetdmlewtkpktgllepfrltataztksmhkbot",
         ];
         assert_eq!(encoder.fragment_count(), 9);
+        let mut skipped = Encoder::bytes(&ur, 30).unwrap();
+        assert!(matches!(skipped.skip_to(0), Err(Error::InvalidIndices)));
+        assert!(matches!(
+            skipped.skip_to(usize::MAX),
+            Err(Error::InvalidIndices)
+        ));
+        for (index, e) in expected.iter().enumerate().rev() {
+            skipped.skip_to(index + 1).unwrap();
+            assert_eq!(skipped.next_part().unwrap(), *e);
+        }
+        let mut reused = Encoder::bytes(&ur, 30).unwrap();
+        let mut part = String::new();
         for (index, e) in expected.into_iter().enumerate() {
             assert_eq!(encoder.current_index(), index);
             assert_eq!(encoder.next_part().unwrap(), e);
+            assert_eq!(encoder.current_part().unwrap().unwrap(), e);
+            reused.next_part_into(&mut part).unwrap();
+            assert_eq!(part, e);
+        }
+    }
+
+    #[test]
+    fn test_for_max_part_length() {
+        let ur = make_message_ur(1000, "Wolf");
+        for max_part_length in [80, 100, 250, 1000, 3000] {
+            let mut encoder =
+                Encoder::for_max_part_length(&ur[..], max_part_length, Type::Custom("crypto-psbt"))
+                    .unwrap();
+            let mut longest = 0;
+            for _ in 0..20 {
+                longest = longest.max(encoder.next_part().unwrap().len());
+            }
+            for sequence in [255, 256, 65535, 65536, u32::MAX as usize] {
+                longest = longest.max(encoder.part_at(sequence).unwrap().len());
+            }
+            let count = encoder.fragment_count();
+            let fragment_length = crate::fountain::div_ceil(ur.len(), count);
+            assert_eq!(
+                longest,
+                max_part_length_of(11, count, ur.len(), fragment_length)
+            );
+            assert!(longest <= max_part_length);
+            // one fragment less would exceed the maximum
+            if count > 1 {
+                let fragment_length = crate::fountain::div_ceil(ur.len(), count - 1);
+                assert!(
+                    max_part_length_of(11, count - 1, ur.len(), fragment_length) > max_part_length
+                );
+            }
+        }
+        assert!(matches!(
+            Encoder::for_max_part_length(&ur[..], 60, Type::Bytes),
+            Err(Error::Fountain(crate::fountain::Error::InvalidFragmentLen))
+        ));
+        assert!(matches!(
+            Encoder::for_max_part_length(&[][..], 100, Type::Bytes),
+            Err(Error::Fountain(crate::fountain::Error::EmptyMessage))
+        ));
+    }
+
+    #[test]
+    fn test_padded_sequence() {
+        let ur = make_message_ur(256, "Wolf");
+        let mut padded = Encoder::bytes(&ur, 20).unwrap().with_padded_sequence();
+        let mut encoder = Encoder::bytes(&ur, 20).unwrap();
+        let mut decoder = Decoder::default();
+        let mut part = String::new();
+        for sequence in 1..=23 {
+            let plain = encoder.next_part().unwrap();
+            padded.next_part_into(&mut part).unwrap();
+            assert_eq!(part, padded.part_at(sequence).unwrap());
+            if sequence < 10 {
+                assert_eq!(part, plain.replacen("ur:bytes/", "ur:bytes/0", 1));
+            } else {
+                assert_eq!(part, plain);
+            }
+            assert_eq!(part.len(), 87);
+            decoder.receive(&part).unwrap();
         }
+        assert_eq!(decoder.message().unwrap(), Some(ur));
     }
 
     #[test]
@@ -414,7 +2171,13 @@ mod tests {
 
         // Decoding should yield the same data
         let decoded = decode(e.as_str()).unwrap();
-        assert_eq!((Kind::SinglePart, data), decoded);
+        assert_eq!(
+            decoded,
+            ParsedUr::SinglePart {
+                ur_type: "crypto-request",
+                payload: data
+            }
+        );
     }
 
     #[test]
@@ -453,6 +2216,90 @@ mod tests {
         ));
         decode("ur:bytes/aeadaolazmjendeoti").unwrap();
         decode("ur:whatever-12/aeadaolazmjendeoti").unwrap();
+        assert_eq!(
+            decode("ur:bytes/70000-4294967295/aeadaolazmjendeoti").unwrap(),
+            ParsedUr::MultiPart {
+                ur_type: "bytes",
+                sequence: 70000,
+                sequence_count: 4_294_967_295,
+                payload: vec![0, 1, 2, 128, 255]
+            }
+        );
+        assert!(matches!(
+            decode("ur:bytes/1-4294967296/aeadaolazmjendeoti"),
+            Err(Error::InvalidIndices)
+        ));
+    }
+
+    #[test]
+    fn test_checksum_phrase() {
+        assert_eq!(checksum_phrase(0), "able able able able");
+        assert_eq!(checksum_phrase(0x0001_02ff), "able acid also zoom");
+        let ur = make_message_ur(256, "Wolf");
+        let mut encoder = Encoder::bytes(&ur, 30).unwrap();
+        let mut decoder = Decoder::default();
+        decoder.receive(&encoder.next_part().unwrap()).unwrap();
+        let phrase = encoder.checksum_phrase();
+        assert_eq!(phrase, checksum_phrase(crate::CRC32.checksum(&ur)));
+        assert_eq!(decoder.checksum_phrase(), Some(phrase));
+        decoder.reset();
+        assert_eq!(decoder.checksum_phrase(), None);
+    }
+
+    #[test]
+    fn test_multi_display() {
+        let ur = make_message_ur(256, "Wolf");
+        let count = core::num::NonZeroUsize::new(3).unwrap();
+        let mut displays = MultiDisplay::new(Encoder::bytes(&ur, 30).unwrap(), count);
+        let mut encoder = Encoder::bytes(&ur, 30).unwrap();
+        let mut parts = vec![String::new(); 5];
+        for _ in 0..4 {
+            displays.next_parts_into(&mut parts).unwrap();
+            assert_eq!(parts.len(), 3);
+            for part in &parts {
+                assert_eq!(*part, encoder.next_part().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_ack() {
+        let ur = make_message_ur(256, "Wolf");
+        let mut encoder = Encoder::bytes(&ur, 30).unwrap();
+        let mut decoder = Decoder::default();
+        for _ in 0..encoder.fragment_count() {
+            let part = encoder.next_part().unwrap();
+            if ![3, 6, 9].contains(&encoder.current_index()) {
+                decoder.receive(&part).unwrap();
+            }
+        }
+        let value = decoder.ack().unwrap().unwrap();
+        let ack = decode_ack(&value).unwrap();
+        assert_eq!(ack.checksum(), crate::CRC32.checksum(&ur));
+        assert_eq!(ack.fragment_count(), encoder.fragment_count());
+        assert_eq!(ack.missing().to_vec(), vec![2, 5, 8]);
+
+        assert!(matches!(
+            decode_ack("ur:bytes/iehsjyhspmwfwfia"),
+            Err(Error::UnexpectedType)
+        ));
+        let multi_part = value.replacen('/', "/1-2/", 1);
+        assert!(matches!(
+            decode_ack(&multi_part),
+            Err(Error::UnexpectedType)
+        ));
+        // a bitmap of two bytes for a single segment
+        let cbor = [0x83, 0x00, 0x01, 0x42, 0x01, 0x00];
+        assert!(matches!(
+            decode_ack(&encode(&cbor, &Type::Custom("fountain-ack"))),
+            Err(Error::Fountain(crate::fountain::Error::CborDecode(_)))
+        ));
+        // a received segment beyond the number of segments
+        let cbor = [0x83, 0x00, 0x01, 0x41, 0x02];
+        assert!(matches!(
+            decode_ack(&encode(&cbor, &Type::Custom("fountain-ack"))),
+            Err(Error::Fountain(crate::fountain::Error::CborDecode(_)))
+        ));
     }
 
     #[test]
@@ -465,4 +2312,46 @@ mod tests {
             "ur:my-scheme/1-2/lpadaobkcywkwmhfwnfeghihjtcxiansvomopr"
         );
     }
+
+    #[test]
+    fn test_error_conversions() {
+        fn words(value: &str) -> Result<Vec<u8>, Error> {
+            Ok(crate::bytewords::decode(
+                value,
+                crate::bytewords::Style::Minimal,
+            )?)
+        }
+        fn part(cbor: &[u8]) -> Result<crate::fountain::Part, Error> {
+            Ok(minicbor::decode(cbor)?)
+        }
+        fn digest(digest: crate::registry::SeedDigest) -> Result<Vec<u8>, Error> {
+            Ok(minicbor::to_vec(digest)?)
+        }
+        fn sampler(weights: Vec<f64>) -> Result<crate::sampler::Weighted, Error> {
+            Ok(crate::sampler::Weighted::new(weights)?)
+        }
+        fn message() -> Result<(), Error> {
+            crate::fountain::Encoder::new(b"", 10)?;
+            Ok(())
+        }
+        assert!(matches!(words("zz"), Err(Error::Bytewords(_))));
+        assert!(matches!(
+            part(&[0x01]),
+            Err(Error::Fountain(crate::fountain::Error::CborDecode(_)))
+        ));
+        assert!(matches!(
+            sampler(vec![1.0, f64::NAN]),
+            Err(Error::Sampler(crate::sampler::Error::InvalidWeight))
+        ));
+        assert!(matches!(
+            sampler(Vec::new()),
+            Err(Error::Sampler(crate::sampler::Error::InvalidTotalWeight))
+        ));
+        assert!(sampler(Vec::new()).unwrap_err().hint().is_some());
+        assert!(digest(crate::registry::SeedDigest::from_digest([7; 32])).is_ok());
+        assert!(matches!(
+            message(),
+            Err(Error::Fountain(crate::fountain::Error::EmptyMessage))
+        ));
+    }
 }
@@ -1,11 +1,38 @@
+//! The random number generator of the [`uniform resource`](https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-005-ur.md) fountain encoder.
+//!
+//! The fountain encoder and decoder have to agree on the fragments mixed into
+//! each part, which are chosen by a [`Xoshiro256`] generator seeded with the
+//! part's sequence number and the message checksum. Implementations of related
+//! specifications can use it to produce bit-identical random streams.
+//!
+//! # Examples
+//!
+//! ```
+//! use ur::xoshiro::Xoshiro256;
+//! let mut rng = Xoshiro256::from("Wolf");
+//! assert_eq!(rng.next() % 100, 42);
+//! assert_eq!(rng.next_int(1, 10), 5);
+//! ```
+//!
+//! [`Xoshiro256`] implements the [`rand_core`] traits, so it can be used with
+//! the wider `rand` ecosystem.
+
 extern crate alloc;
 use alloc::vec::Vec;
-use bitcoin_hashes::Hash;
 use rand_xoshiro::rand_core::RngCore;
 use rand_xoshiro::rand_core::SeedableRng;
 use rand_xoshiro::Xoshiro256StarStar;
 
+pub use rand_xoshiro::rand_core;
+
+/// A `xoshiro256**` generator as specified for the fountain encoder.
+///
+/// Generators created from byte slices or strings are seeded with their SHA-256
+/// digest, which is read as four big-endian 64-bit words. The derived numbers
+/// are mapped exactly like the reference implementation, see
+/// [`Xoshiro256::next_double`] and [`Xoshiro256::next_int`].
 #[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
 pub struct Xoshiro256 {
     inner: Xoshiro256StarStar,
 }
@@ -18,29 +45,41 @@ impl From<Xoshiro256StarStar> for Xoshiro256 {
 
 impl From<&[u8]> for Xoshiro256 {
     fn from(from: &[u8]) -> Self {
-        let hash = bitcoin_hashes::sha256::Hash::hash(from);
-        Self::from(hash.to_byte_array())
+        Self::from(crate::sha256::hash(from))
     }
 }
 
 #[allow(clippy::cast_precision_loss)]
 #[allow(clippy::cast_possible_truncation)]
 impl Xoshiro256 {
+    /// Returns the next 64-bit number of the stream.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> u64 {
         self.inner.next_u64()
     }
 
+    /// Returns the next number of the stream, mapped to the interval `[0, 1)`.
     pub fn next_double(&mut self) -> f64 {
         self.next() as f64 / (u64::MAX as f64 + 1.0)
     }
 
+    /// Returns the next number of the stream, mapped to the inclusive range
+    /// from `low` to `high` via [`Xoshiro256::next_double`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low` is greater than `high`.
     #[allow(clippy::cast_sign_loss)]
     pub fn next_int(&mut self, low: u64, high: u64) -> u64 {
-        (self.next_double() * ((high - low + 1) as f64)) as u64 + low
+        assert!(low <= high, "the range must not be empty");
+        // the range may span all 64-bit numbers, so its length is computed as
+        // a float, which may round the product up to the length
+        let offset = self.next_double() * ((high - low) as f64 + 1.0);
+        (offset as u64).min(high - low) + low
     }
 
-    pub fn shuffled<T>(&mut self, mut items: Vec<T>) -> Vec<T> {
+    /// Shuffles the items by repeatedly removing a randomly chosen item.
+    pub(crate) fn shuffled<T>(&mut self, mut items: Vec<T>) -> Vec<T> {
         let mut shuffled = Vec::<T>::with_capacity(items.len());
         while !items.is_empty() {
             let index = self.next_int(0, (items.len() - 1) as u64) as usize;
@@ -50,17 +89,18 @@ impl Xoshiro256 {
         shuffled
     }
 
-    pub fn choose_degree(&mut self, length: usize) -> u32 {
+    /// Chooses the number of fragments mixed into a fountain part for a
+    /// message of `length` fragments, where a degree `d` has a weight of `1/d`.
+    /// Returns zero for an empty message.
+    pub(crate) fn choose_degree(&mut self, length: usize) -> u32 {
         let degree_weights: Vec<f64> = (1..=length).map(|x| 1.0 / x as f64).collect();
-        let sampler = crate::sampler::Weighted::new(degree_weights);
-        sampler.next(self) + 1
+        crate::sampler::Weighted::new(degree_weights).map_or(0, |sampler| sampler.next(self) + 1)
     }
 }
 
 impl From<&str> for Xoshiro256 {
     fn from(value: &str) -> Self {
-        let hash = bitcoin_hashes::sha256::Hash::hash(value.as_bytes());
-        Self::from(hash.to_byte_array())
+        Self::from(crate::sha256::hash(value.as_bytes()))
     }
 }
 
@@ -82,8 +122,36 @@ impl From<[u8; 32]> for Xoshiro256 {
     }
 }
 
+impl RngCore for Xoshiro256 {
+    fn next_u32(&mut self) -> u32 {
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.inner.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.inner.try_fill_bytes(dest)
+    }
+}
+
+/// Seeds the generator like [`Xoshiro256::from`] a 32-byte digest, i.e. the
+/// seed is read as four big-endian 64-bit words.
+impl SeedableRng for Xoshiro256 {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::from(seed)
+    }
+}
+
 #[cfg(test)]
-pub mod test_utils {
+pub(crate) mod test_utils {
     use super::*;
 
     impl super::Xoshiro256 {
@@ -92,13 +160,13 @@ pub mod test_utils {
             self.next_int(0, 255) as u8
         }
 
-        pub fn next_bytes(&mut self, n: usize) -> Vec<u8> {
+        pub(crate) fn next_bytes(&mut self, n: usize) -> Vec<u8> {
             (0..n).map(|_| self.next_byte()).collect()
         }
 
         #[must_use]
-        pub fn from_crc(bytes: &[u8]) -> Self {
-            Self::from(&crate::crc32().checksum(bytes).to_be_bytes()[..])
+        pub(crate) fn from_crc(bytes: &[u8]) -> Self {
+            Self::from(&crate::CRC32.checksum(bytes).to_be_bytes()[..])
         }
     }
 
@@ -157,6 +225,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rng_full_range() {
+        let mut rng = Xoshiro256::from("Wolf");
+        for _ in 0..100 {
+            rng.next_int(0, u64::MAX);
+            assert!(rng.next_int(u64::MAX - 1, u64::MAX) >= u64::MAX - 1);
+            assert_eq!(rng.next_int(7, 7), 7);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the range must not be empty")]
+    fn test_rng_empty_range() {
+        Xoshiro256::from("Wolf").next_int(1, 0);
+    }
+
+    #[test]
+    fn test_rng_core() {
+        let mut rng = Xoshiro256::from("Wolf");
+        let mut expected = rng.clone();
+        assert_eq!(rng.next_u64(), expected.next());
+        assert_eq!(u64::from(rng.next_u32()), expected.next() >> 32);
+        let mut bytes = [0; 8];
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes, expected.next().to_le_bytes());
+
+        let mut seeded = Xoshiro256::from_seed(crate::sha256::hash(b"Wolf"));
+        let mut expected = Xoshiro256::from("Wolf");
+        for _ in 0..10 {
+            assert_eq!(seeded.next(), expected.next());
+        }
+    }
+
     #[test]
     fn test_shuffle() {
         let mut rng = Xoshiro256::from("Wolf");